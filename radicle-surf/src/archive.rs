@@ -0,0 +1,596 @@
+//! Export of the tree of a revision as a `tar`, `tar.gz` or `zip` archive,
+//! in the manner of `git archive`.
+//!
+//! An archive can be written via [`crate::Repository::archive`], or
+//! [`crate::Repository::archive_with`] for a sub-directory:
+//!
+//! ```no_run
+//! use radicle_surf::{
+//!     archive::{ArchiveOptions, Format},
+//!     Repository,
+//! };
+//!
+//! let repo = Repository::open("/path/to/repo")?;
+//! let rev = "e24124b7538658220b5aaf3b6ef53758f0a106dc";
+//! let mut tarball = Vec::new();
+//! repo.archive(rev, Format::TarGz, "project/", &mut tarball)?;
+//!
+//! let options = ArchiveOptions::new(Format::Zip)
+//!     .prefix("docs/")
+//!     .path("docs");
+//! let file = std::fs::File::create("docs.zip").unwrap();
+//! repo.archive_with(rev, &options, file)?;
+//! # Ok::<(), radicle_surf::Error>(())
+//! ```
+//!
+//! Files are written with the mode `0644`, executable files and
+//! directories with `0755`, and symbolic links with `0777`, all owned by
+//! `root` and last modified at the time of the commit. Submodules are
+//! written as empty directories.
+//!
+//! As with `git archive`, paths with the `export-ignore` attribute in a
+//! `.gitattributes` file are left out, and `$Format:...$` placeholders in
+//! files with the `export-subst` attribute are expanded for the commit.
+//! The supported placeholders of the format are `%H`, `%h`, `%T`, `%t`,
+//! `%P`, `%p`, `%an`, `%ae`, `%at`, `%cn`, `%ce`, `%ct`, `%s`, `%b`, `%B`,
+//! `%n` and `%%`, while any other placeholder is left as is.
+
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use flate2::{
+    write::{DeflateEncoder, GzEncoder},
+    Compression,
+};
+use radicle_git_ext::Oid;
+
+use crate::{language::Attributes, Error, Repository};
+
+pub mod error {
+    use std::{io, path::PathBuf};
+
+    use thiserror::Error;
+
+    /// An error writing an archive.
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum Archive {
+        #[error(transparent)]
+        Io(#[from] io::Error),
+        #[error("the path '{0}' is not a directory")]
+        NotDirectory(PathBuf),
+        /// The archive exceeds the limits of a `zip` file without the
+        /// ZIP64 extensions, i.e. 4 GiB or 65535 entries, or a path or
+        /// comment is longer than 65535 bytes.
+        #[error("the archive is too large for the zip format")]
+        TooLarge,
+    }
+}
+
+/// The format of an archive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// An uncompressed `tar` archive.
+    Tar,
+    /// A `tar` archive compressed with `gzip`.
+    TarGz,
+    /// A `zip` archive, where files are compressed with `deflate` unless
+    /// that does not make them smaller.
+    ///
+    /// The ZIP64 extensions are not supported, so an archive, or any file in
+    /// it, must be smaller than 4 GiB and hold at most 65535 entries.
+    /// Otherwise, writing fails with `TooLarge`, after part of the archive
+    /// may have been written already.
+    Zip,
+}
+
+/// Options for writing an archive.
+///
+/// ```no_run
+/// use radicle_surf::archive::{ArchiveOptions, Format};
+///
+/// let options = ArchiveOptions::new(Format::Tar)
+///     .prefix("project-1.0/")
+///     .path("src");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArchiveOptions {
+    format: Format,
+    prefix: String,
+    path: Option<PathBuf>,
+}
+
+impl ArchiveOptions {
+    /// Writes archives in the given `format`, of the whole tree and without
+    /// a prefix.
+    pub fn new(format: Format) -> Self {
+        Self {
+            format,
+            prefix: String::new(),
+            path: None,
+        }
+    }
+
+    /// Prepends `prefix` to the path of every entry. Like the `--prefix`
+    /// option of `git archive`, it is used verbatim, so a directory prefix
+    /// must end with a `/`.
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Only archives the directory at `path`, relative to the repository
+    /// root, whose entries are written relative to it.
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+}
+
+/// An entry of the tree being archived.
+struct Entry {
+    /// The path relative to the archived directory.
+    path: PathBuf,
+    kind: Kind,
+    oid: Oid,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Directory,
+    File,
+    Executable,
+    Symlink,
+}
+
+impl Kind {
+    fn mode(self) -> u32 {
+        match self {
+            Self::Directory | Self::Executable => 0o755,
+            Self::File => 0o644,
+            Self::Symlink => 0o777,
+        }
+    }
+}
+
+/// Writes the directory at `options.path` of `commit` to `writer`.
+pub(crate) fn write(
+    repo: &Repository,
+    commit: &git2::Commit,
+    options: &ArchiveOptions,
+    writer: impl Write,
+) -> Result<(), Error> {
+    let root = commit.tree()?;
+    let dir = options.path.as_deref().unwrap_or(Path::new(""));
+    let tree = if dir.as_os_str().is_empty() {
+        root.clone()
+    } else {
+        let entry = root.get_path(dir)?;
+        if entry.kind() != Some(git2::ObjectType::Tree) {
+            return Err(error::Archive::NotDirectory(dir.to_path_buf()).into());
+        }
+        repo.find_tree(entry.id().into())?
+    };
+
+    let mut entries = Vec::new();
+    tree.walk(git2::TreeWalkMode::PreOrder, |parent, entry| {
+        let kind = match entry.filemode() {
+            mode if mode == i32::from(git2::FileMode::Tree) => Kind::Directory,
+            mode if mode == i32::from(git2::FileMode::Commit) => Kind::Directory,
+            mode if mode == i32::from(git2::FileMode::BlobExecutable) => Kind::Executable,
+            mode if mode == i32::from(git2::FileMode::Link) => Kind::Symlink,
+            _ => Kind::File,
+        };
+        if let Ok(name) = entry.name() {
+            entries.push(Entry {
+                path: Path::new(parent).join(name),
+                kind,
+                oid: entry.id().into(),
+            });
+        }
+        git2::TreeWalkResult::Ok
+    })?;
+
+    // The attributes of the directory also come from the `.gitattributes`
+    // of its parents.
+    let mut sources = dir
+        .ancestors()
+        .skip(1)
+        .filter_map(|parent| {
+            let path = parent.join(".gitattributes");
+            let entry = root.get_path(&path).ok()?;
+            (entry.kind() == Some(git2::ObjectType::Blob)).then(|| (path, entry.id().into()))
+        })
+        .collect::<Vec<_>>();
+    sources.extend(
+        entries
+            .iter()
+            .filter(|entry| entry.kind != Kind::Directory)
+            .map(|entry| (dir.join(&entry.path), entry.oid)),
+    );
+    let attributes = Attributes::load(repo, &sources, "export-")?;
+
+    // Entries follow their parent directory, so an ignored directory is
+    // directly followed by its contents.
+    let mut ignored: Option<PathBuf> = None;
+    entries.retain(|entry| {
+        if ignored
+            .as_ref()
+            .is_some_and(|ignored| entry.path.starts_with(ignored))
+        {
+            return false;
+        }
+        let ignore = attributes.get(&dir.join(&entry.path), "export-ignore") == Some(true);
+        if ignore && entry.kind == Kind::Directory {
+            ignored = Some(entry.path.clone());
+        }
+        !ignore
+    });
+
+    let mtime = commit.time().seconds().max(0) as u64;
+    let mut archive = match options.format {
+        Format::Tar => Writer::Tar(tar::Builder::new(Tar::Plain(writer))),
+        Format::TarGz => Writer::Tar(tar::Builder::new(Tar::Gz(GzEncoder::new(
+            writer,
+            Compression::default(),
+        )))),
+        Format::Zip => Writer::Zip(Zip::new(writer)),
+    };
+    // Like `git archive`, a directory prefix is an entry of its own.
+    if options.prefix.ends_with('/') {
+        archive.append(&options.prefix, Kind::Directory, mtime, &[])?;
+    }
+    for entry in entries {
+        let mut path = options.prefix.clone();
+        path.push_str(&entry.path.to_string_lossy());
+        if entry.kind == Kind::Directory {
+            path.push('/');
+            archive.append(&path, entry.kind, mtime, &[])?;
+            continue;
+        }
+
+        let blob = repo.find_blob(entry.oid)?;
+        let subst = entry.kind != Kind::Symlink
+            && attributes.get(&dir.join(&entry.path), "export-subst") == Some(true);
+        if subst {
+            let content = substitute(blob.content(), commit)?;
+            archive.append(&path, entry.kind, mtime, &content)?;
+        } else {
+            archive.append(&path, entry.kind, mtime, blob.content())?;
+        }
+    }
+    archive.finish(&commit.id().to_string())
+}
+
+/// The writer of an archive of either format.
+enum Writer<W: Write> {
+    Tar(tar::Builder<Tar<W>>),
+    Zip(Zip<W>),
+}
+
+impl<W: Write> Writer<W> {
+    fn append(&mut self, path: &str, kind: Kind, mtime: u64, data: &[u8]) -> Result<(), Error> {
+        match self {
+            Self::Tar(builder) => {
+                let mut header = tar::Header::new_gnu();
+                header.set_mode(kind.mode());
+                header.set_mtime(mtime);
+                header.set_uid(0);
+                header.set_gid(0);
+                header.set_username("root").map_err(error::Archive::from)?;
+                header.set_groupname("root").map_err(error::Archive::from)?;
+                match kind {
+                    Kind::Directory => {
+                        header.set_entry_type(tar::EntryType::Directory);
+                        header.set_size(0);
+                        builder.append_data(&mut header, path, io::empty())
+                    }
+                    Kind::Symlink => {
+                        header.set_entry_type(tar::EntryType::Symlink);
+                        header.set_size(0);
+                        let target = String::from_utf8_lossy(data);
+                        builder.append_link(&mut header, path, target.as_ref())
+                    }
+                    Kind::File | Kind::Executable => {
+                        header.set_entry_type(tar::EntryType::Regular);
+                        header.set_size(data.len() as u64);
+                        builder.append_data(&mut header, path, data)
+                    }
+                }
+                .map_err(error::Archive::from)?;
+                Ok(())
+            }
+            Self::Zip(zip) => zip.append(path, kind, mtime, data),
+        }
+    }
+
+    /// Finishes the archive, where `comment` is the comment of a `zip`
+    /// archive.
+    fn finish(self, comment: &str) -> Result<(), Error> {
+        match self {
+            Self::Tar(builder) => {
+                let mut writer = match builder.into_inner().map_err(error::Archive::from)? {
+                    Tar::Plain(writer) => writer,
+                    Tar::Gz(encoder) => encoder.finish().map_err(error::Archive::from)?,
+                };
+                writer.flush().map_err(error::Archive::from)?;
+                Ok(())
+            }
+            Self::Zip(zip) => zip.finish(comment),
+        }
+    }
+}
+
+/// The underlying writer of a `tar` archive, which may be compressed.
+enum Tar<W: Write> {
+    Plain(W),
+    Gz(GzEncoder<W>),
+}
+
+impl<W: Write> Write for Tar<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(writer) => writer.write(buf),
+            Self::Gz(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(writer) => writer.flush(),
+            Self::Gz(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// A writer of `zip` archives that streams to a writer without seeking, as
+/// the size and checksum of each entry are known before it is written.
+struct Zip<W: Write> {
+    writer: W,
+    /// The number of bytes written so far.
+    offset: u64,
+    /// The central directory, written at the end of the archive.
+    central: Vec<u8>,
+    entries: u64,
+}
+
+impl<W: Write> Zip<W> {
+    /// The version of the specification needed to extract entries, i.e.
+    /// 2.0 for `deflate` and directories.
+    const VERSION: u16 = 20;
+    /// The "version made by", i.e. the [`Self::VERSION`] on UNIX, so that
+    /// the external attributes hold UNIX modes.
+    const MADE_BY: u16 = 3 << 8 | Self::VERSION;
+    /// Marks names and comments as UTF-8.
+    const UTF8: u16 = 1 << 11;
+    const STORE: u16 = 0;
+    const DEFLATE: u16 = 8;
+
+    fn new(writer: W) -> Self {
+        Self {
+            writer,
+            offset: 0,
+            central: Vec::new(),
+            entries: 0,
+        }
+    }
+
+    fn append(&mut self, path: &str, kind: Kind, mtime: u64, data: &[u8]) -> Result<(), Error> {
+        let crc = crc32fast::hash(data);
+        let deflated = match kind {
+            Kind::File | Kind::Executable if !data.is_empty() => {
+                let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(data).map_err(error::Archive::from)?;
+                Some(encoder.finish().map_err(error::Archive::from)?)
+            }
+            _ => None,
+        };
+        let (method, compressed) = match &deflated {
+            Some(deflated) if deflated.len() < data.len() => (Self::DEFLATE, deflated.as_slice()),
+            _ => (Self::STORE, data),
+        };
+
+        let size = u32::try_from(data.len()).map_err(|_| error::Archive::TooLarge)?;
+        let compressed_size =
+            u32::try_from(compressed.len()).map_err(|_| error::Archive::TooLarge)?;
+        let offset = u32::try_from(self.offset).map_err(|_| error::Archive::TooLarge)?;
+        let name_len = u16::try_from(path.len()).map_err(|_| error::Archive::TooLarge)?;
+        let (time, date) = dos_time(mtime);
+        let mode = match kind {
+            Kind::Directory => 0o040000,
+            Kind::Symlink => 0o120000,
+            Kind::File | Kind::Executable => 0o100000,
+        } | kind.mode();
+        // The MS-DOS directory attribute, for tools ignoring UNIX modes.
+        let dos = if kind == Kind::Directory { 0x10 } else { 0 };
+        // The extended timestamp field, holding the modification time.
+        let mut extra = Vec::with_capacity(9);
+        extra.extend(0x5455u16.to_le_bytes());
+        extra.extend(5u16.to_le_bytes());
+        extra.push(1);
+        extra.extend((mtime.min(u64::from(u32::MAX)) as u32).to_le_bytes());
+        let extra_len = u16::try_from(extra.len()).map_err(|_| error::Archive::TooLarge)?;
+
+        let mut local = Vec::with_capacity(30 + path.len() + extra.len());
+        local.extend(0x04034b50u32.to_le_bytes());
+        local.extend(Self::VERSION.to_le_bytes());
+        local.extend(Self::UTF8.to_le_bytes());
+        local.extend(method.to_le_bytes());
+        local.extend(time.to_le_bytes());
+        local.extend(date.to_le_bytes());
+        local.extend(crc.to_le_bytes());
+        local.extend(compressed_size.to_le_bytes());
+        local.extend(size.to_le_bytes());
+        local.extend(name_len.to_le_bytes());
+        local.extend(extra_len.to_le_bytes());
+        local.extend(path.as_bytes());
+        local.extend(&extra);
+        self.write(&local)?;
+        self.write(compressed)?;
+
+        let central = &mut self.central;
+        central.extend(0x02014b50u32.to_le_bytes());
+        central.extend(Self::MADE_BY.to_le_bytes());
+        central.extend(Self::VERSION.to_le_bytes());
+        central.extend(Self::UTF8.to_le_bytes());
+        central.extend(method.to_le_bytes());
+        central.extend(time.to_le_bytes());
+        central.extend(date.to_le_bytes());
+        central.extend(crc.to_le_bytes());
+        central.extend(compressed_size.to_le_bytes());
+        central.extend(size.to_le_bytes());
+        central.extend(name_len.to_le_bytes());
+        central.extend(extra_len.to_le_bytes());
+        // The comment length, disk number and internal attributes.
+        central.extend([0; 6]);
+        central.extend((mode << 16 | dos).to_le_bytes());
+        central.extend(offset.to_le_bytes());
+        central.extend(path.as_bytes());
+        central.extend(&extra);
+        self.entries += 1;
+        Ok(())
+    }
+
+    fn finish(mut self, comment: &str) -> Result<(), Error> {
+        let entries = u16::try_from(self.entries).map_err(|_| error::Archive::TooLarge)?;
+        let size = u32::try_from(self.central.len()).map_err(|_| error::Archive::TooLarge)?;
+        let offset = u32::try_from(self.offset).map_err(|_| error::Archive::TooLarge)?;
+        let comment_len = u16::try_from(comment.len()).map_err(|_| error::Archive::TooLarge)?;
+        let central = std::mem::take(&mut self.central);
+        self.write(&central)?;
+
+        let mut end = Vec::with_capacity(22 + comment.len());
+        end.extend(0x06054b50u32.to_le_bytes());
+        // The number of this disk and of the disk with the central
+        // directory.
+        end.extend([0; 4]);
+        end.extend(entries.to_le_bytes());
+        end.extend(entries.to_le_bytes());
+        end.extend(size.to_le_bytes());
+        end.extend(offset.to_le_bytes());
+        end.extend(comment_len.to_le_bytes());
+        end.extend(comment.as_bytes());
+        self.write(&end)?;
+        self.writer.flush().map_err(error::Archive::from)?;
+        Ok(())
+    }
+
+    fn write(&mut self, buf: &[u8]) -> Result<(), error::Archive> {
+        self.writer.write_all(buf)?;
+        self.offset += buf.len() as u64;
+        Ok(())
+    }
+}
+
+/// Converts `seconds` since the UNIX epoch into the MS-DOS time and date of
+/// `zip` archives, in UTC. Times before 1980, the MS-DOS epoch, are
+/// clamped to it.
+fn dos_time(seconds: u64) -> (u16, u16) {
+    const DOS_EPOCH: u64 = 315532800;
+    let seconds = seconds.max(DOS_EPOCH);
+    let (days, time) = (seconds / 86400, seconds % 86400);
+    let (hour, minute, second) = (time / 3600, time % 3600 / 60, time % 60);
+
+    // The proleptic Gregorian date of `days` since the epoch, where years
+    // start in March so that leap days come last.
+    let days = days + 719468;
+    let era = days / 146097;
+    let day_of_era = days % 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    let time = hour << 11 | minute << 5 | (second / 2);
+    let date = (year - 1980).min(127) << 9 | month << 5 | day;
+    (time as u16, date as u16)
+}
+
+/// Expands the `$Format:...$` placeholders of `content` for `commit`.
+fn substitute(content: &[u8], commit: &git2::Commit) -> Result<Vec<u8>, Error> {
+    const START: &[u8] = b"$Format:";
+    let mut result = Vec::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = find(rest, START) {
+        let Some(len) = rest[start + START.len()..].iter().position(|b| *b == b'$') else {
+            break;
+        };
+        let format = &rest[start + START.len()..start + START.len() + len];
+        result.extend_from_slice(&rest[..start]);
+        result.extend_from_slice(pretty(&String::from_utf8_lossy(format), commit)?.as_bytes());
+        rest = &rest[start + START.len() + len + 1..];
+    }
+    result.extend_from_slice(rest);
+    Ok(result)
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Formats `commit` according to the subset of the pretty formats of
+/// `git log` supported by [`substitute`].
+fn pretty(format: &str, commit: &git2::Commit) -> Result<String, Error> {
+    let short = |object: &git2::Object| -> Result<String, Error> {
+        Ok(object.short_id()?.as_str().unwrap_or_default().to_owned())
+    };
+    let lossy = |bytes: &[u8]| String::from_utf8_lossy(bytes).into_owned();
+    let message = lossy(commit.message_bytes());
+    let (subject, body) = match message.split_once("\n\n") {
+        Some((subject, body)) => (subject.trim(), body.trim_start_matches('\n')),
+        None => (message.trim(), ""),
+    };
+
+    let mut result = String::with_capacity(format.len());
+    let mut rest = format;
+    while let Some(i) = rest.find('%') {
+        result.push_str(&rest[..i]);
+        rest = &rest[i + 1..];
+        let placeholders: &[&str] = &[
+            "an", "ae", "at", "cn", "ce", "ct", "H", "h", "T", "t", "P", "p", "s", "b", "B", "n",
+            "%",
+        ];
+        let Some(placeholder) = placeholders.iter().find(|p| rest.starts_with(**p)) else {
+            result.push('%');
+            continue;
+        };
+        let value = match *placeholder {
+            "H" => commit.id().to_string(),
+            "h" => short(commit.as_object())?,
+            "T" => commit.tree_id().to_string(),
+            "t" => short(commit.tree()?.as_object())?,
+            "P" => commit
+                .parent_ids()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(" "),
+            "p" => commit
+                .parents()
+                .map(|parent| short(parent.as_object()))
+                .collect::<Result<Vec<_>, _>>()?
+                .join(" "),
+            "an" => lossy(commit.author().name_bytes()),
+            "ae" => lossy(commit.author().email_bytes()),
+            "at" => commit.author().when().seconds().to_string(),
+            "cn" => lossy(commit.committer().name_bytes()),
+            "ce" => lossy(commit.committer().email_bytes()),
+            "ct" => commit.committer().when().seconds().to_string(),
+            "s" => subject.replace('\n', " "),
+            "b" => body.to_owned(),
+            "B" => message.clone(),
+            "n" => "\n".to_owned(),
+            _ => "%".to_owned(),
+        };
+        result.push_str(&value);
+        rest = &rest[placeholder.len()..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
@@ -0,0 +1,113 @@
+//! Represents the line-by-line attribution of a file, i.e. `git blame`.
+//!
+//! A [`Blame`] can be retrieved via [`crate::Repository::blame`].
+
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use radicle_git_ext::Oid;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::{Commit, Error, Mailmap, Repository};
+
+/// The line-by-line attribution of a file at a given revision.
+///
+/// The [`Hunk`]s are ordered by their line ranges and cover the whole
+/// file.
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Blame {
+    /// The path of the blamed file, relative to the repository root.
+    path: PathBuf,
+    /// The hunks of consecutive lines that share the same origin.
+    hunks: Vec<Hunk>,
+}
+
+impl Blame {
+    /// Builds a `Blame` for `path` from a [`git2::Blame`], loading
+    /// each origin commit only once and resolving its author and committer
+    /// using the `mailmap`.
+    pub(crate) fn new(
+        path: PathBuf,
+        blame: git2::Blame,
+        repo: &Repository,
+        mailmap: &Mailmap,
+    ) -> Result<Self, Error> {
+        let mut commits: BTreeMap<Oid, Commit> = BTreeMap::new();
+        let mut hunks = Vec::with_capacity(blame.len());
+
+        for hunk in blame.iter() {
+            let oid: Oid = hunk.final_commit_id().into();
+            let commit = match commits.get(&oid) {
+                Some(commit) => commit.clone(),
+                None => {
+                    let commit = Commit::try_from(repo.find_commit(oid)?)?;
+                    let commit = mailmap.resolve_commit(commit);
+                    commits.insert(oid, commit.clone());
+                    commit
+                }
+            };
+            let len = hunk.lines_in_hunk() as u32;
+            let start = hunk.final_start_line() as u32;
+            let orig_start = hunk.orig_start_line() as u32;
+            let orig_path = hunk
+                .path()
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| path.clone());
+
+            hunks.push(Hunk {
+                commit,
+                lines: start..start + len,
+                orig_path,
+                orig_lines: orig_start..orig_start + len,
+                boundary: hunk.is_boundary(),
+            });
+        }
+
+        Ok(Self { path, hunks })
+    }
+
+    /// The path of the blamed file, relative to the repository root.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns an iterator of the hunks in this `Blame`.
+    pub fn hunks(&self) -> impl Iterator<Item = &Hunk> {
+        self.hunks.iter()
+    }
+
+    /// Returns the owned hunks of this `Blame`.
+    pub fn into_hunks(self) -> Vec<Hunk> {
+        self.hunks
+    }
+
+    /// Returns the [`Hunk`] that contains the 1-based `line`, if any.
+    pub fn hunk_for_line(&self, line: u32) -> Option<&Hunk> {
+        self.hunks.iter().find(|hunk| hunk.lines.contains(&line))
+    }
+}
+
+/// A set of consecutive lines that originate from the same commit.
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hunk {
+    /// The commit that last changed these lines.
+    pub commit: Commit,
+    /// Line range in the blamed file. Line numbers are 1-based.
+    pub lines: Range<u32>,
+    /// The path of the file in `commit`, which may differ from the
+    /// blamed path if the file was renamed or copied since.
+    pub orig_path: PathBuf,
+    /// Line range in the file at `orig_path` in `commit`.
+    pub orig_lines: Range<u32>,
+    /// Whether the blame was stopped at the boundary of the history,
+    /// i.e. `commit` may not be the real origin of these lines.
+    pub boundary: bool,
+}
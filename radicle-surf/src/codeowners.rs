@@ -0,0 +1,205 @@
+//! Parsing of `CODEOWNERS` files and lookup of the owners of paths.
+//!
+//! [`CodeOwners`] can be retrieved via [`crate::Repository::codeowners`],
+//! or parsed with [`CodeOwners::parse`].
+//!
+//! Each line of a `CODEOWNERS` file consists of a pattern followed by the
+//! owners of the paths it matches, e.g. `@user`, `@org/team` or an email.
+//! Patterns follow the rules of `.gitignore` files, where a pattern
+//! matching a directory matches everything within it. As on GitHub and
+//! GitLab, the exception are patterns ending with `/*`, e.g. `docs/*`,
+//! which only match the files directly within the directory. When several
+//! rules match a path, the last one wins, so a rule without owners removes
+//! the owners of an earlier one.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use regex::Regex;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::{
+    diff::{Diff, FileDiff},
+    language,
+};
+
+/// The rules of a `CODEOWNERS` file.
+#[derive(Clone, Debug)]
+pub struct CodeOwners {
+    rules: Vec<Rule>,
+}
+
+/// A line of a `CODEOWNERS` file.
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug)]
+pub struct Rule {
+    /// The pattern of the rule, as written.
+    pub pattern: String,
+    /// The owners of the paths matching the pattern.
+    pub owners: Vec<String>,
+    /// The line number of the rule, starting at `1`.
+    pub line: usize,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    regex: Regex,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    target: Target,
+}
+
+/// The kind of paths a pattern matches.
+#[derive(Clone, Copy, Debug)]
+enum Target {
+    /// Files, and directories along with everything within them.
+    Any,
+    /// Directories along with everything within them, for patterns
+    /// ending with `/`.
+    Directory,
+    /// Only files, for patterns ending with `/*`.
+    File,
+}
+
+impl PartialEq for Rule {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern && self.owners == other.owners && self.line == other.line
+    }
+}
+
+impl Eq for Rule {}
+
+impl Rule {
+    /// Whether the rule applies to the file at `path`, i.e. the pattern
+    /// matches the file or one of its parent directories.
+    fn matches(&self, path: &Path) -> bool {
+        let matches = |path: &Path| self.regex.is_match(&path.to_string_lossy());
+        let mut dirs = path
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| !dir.as_os_str().is_empty());
+        match self.target {
+            Target::Any => matches(path) || dirs.any(matches),
+            Target::Directory => dirs.any(matches),
+            Target::File => matches(path),
+        }
+    }
+}
+
+impl CodeOwners {
+    /// Parses the content of a `CODEOWNERS` file.
+    ///
+    /// Blank lines and comments starting with `#` are skipped, as are
+    /// lines with a pattern that cannot be parsed, e.g. a negated one. A
+    /// `#` at the start of a pattern, and spaces within it, are escaped
+    /// with a backslash.
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .enumerate()
+            .filter_map(|(i, line)| rule(i + 1, line))
+            .collect();
+        Self { rules }
+    }
+
+    /// Returns the rules, in the order of the file.
+    pub fn rules(&self) -> impl Iterator<Item = &Rule> {
+        self.rules.iter()
+    }
+
+    /// Returns the rule that determines the owners of the file at `path`,
+    /// relative to the repository root, i.e. the last matching one.
+    pub fn rule_for(&self, path: impl AsRef<Path>) -> Option<&Rule> {
+        let path = path.as_ref();
+        self.rules.iter().rev().find(|rule| rule.matches(path))
+    }
+
+    /// Returns the owners of the file at `path`, relative to the repository
+    /// root, which are empty if no rule matches.
+    pub fn owners(&self, path: impl AsRef<Path>) -> &[String] {
+        self.rule_for(path).map_or(&[], |rule| &rule.owners)
+    }
+
+    /// Returns the owners of the files changed by `diff`, along with the
+    /// paths they own, ordered by owner and path.
+    ///
+    /// Both the old and new path of a moved file are considered, while
+    /// copied files are only considered at their new path.
+    pub fn affected_owners(&self, diff: &Diff) -> BTreeMap<String, Vec<PathBuf>> {
+        let mut owners = BTreeMap::<String, Vec<PathBuf>>::new();
+        for file in diff.files() {
+            let old = match file {
+                FileDiff::Moved(moved) => Some(moved.old_path.as_path()),
+                _ => None,
+            };
+            for path in old.into_iter().chain([file.path()]) {
+                for owner in self.owners(path) {
+                    owners
+                        .entry(owner.clone())
+                        .or_default()
+                        .push(path.to_path_buf());
+                }
+            }
+        }
+        for paths in owners.values_mut() {
+            paths.sort();
+            paths.dedup();
+        }
+        owners
+    }
+}
+
+/// Parses the rule at `line`, returning `None` for blank lines, comments
+/// and invalid patterns.
+fn rule(line: usize, content: &str) -> Option<Rule> {
+    let content = content.trim();
+    if content.is_empty() || content.starts_with('#') {
+        return None;
+    }
+
+    // The pattern ends at the first whitespace that is not escaped.
+    let mut pattern = String::new();
+    let mut chars = content.char_indices();
+    let mut rest = "";
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, c @ ('#' | ' '))) => pattern.push(c),
+                Some((_, c)) => {
+                    pattern.push('\\');
+                    pattern.push(c);
+                }
+                None => pattern.push('\\'),
+            },
+            c if c.is_whitespace() => {
+                rest = &content[i..];
+                break;
+            }
+            c => pattern.push(c),
+        }
+    }
+    if pattern.starts_with('!') {
+        return None;
+    }
+    let owners = rest
+        .split_whitespace()
+        .take_while(|owner| !owner.starts_with('#'))
+        .map(str::to_owned)
+        .collect();
+
+    let target = if pattern.ends_with("/*") {
+        Target::File
+    } else if pattern.len() > 1 && pattern.ends_with('/') {
+        Target::Directory
+    } else {
+        Target::Any
+    };
+    let regex = language::glob(Path::new(""), pattern.trim_end_matches('/')).ok()?;
+    Some(Rule {
+        pattern,
+        owners,
+        line,
+        regex,
+        target,
+    })
+}
@@ -3,6 +3,8 @@ use std::{convert::TryFrom, str};
 use radicle_git_ext::Oid;
 use thiserror::Error;
 
+use crate::Note;
+
 #[cfg(feature = "serde")]
 use serde::{ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
 
@@ -73,7 +75,7 @@ where
 }
 
 #[cfg(feature = "serde")]
-fn serialize_time<S>(t: &Time, serializer: S) -> Result<S::Ok, S::Error>
+pub(crate) fn serialize_time<S>(t: &Time, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
@@ -126,6 +128,10 @@ pub struct Commit {
     pub summary: String,
     /// The parents of this commit.
     pub parents: Vec<Oid>,
+    /// The notes attached to this commit, which are empty unless retrieved
+    /// via [`crate::Repository::notes`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub notes: Vec<Note>,
 }
 
 impl Commit {
@@ -146,7 +152,7 @@ impl Serialize for Commit {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("Commit", 7)?;
+        let mut state = serializer.serialize_struct("Commit", 8)?;
         state.serialize_field("id", &self.id.to_string())?;
         state.serialize_field("author", &self.author)?;
         state.serialize_field("committer", &self.committer)?;
@@ -161,6 +167,11 @@ impl Serialize for Commit {
                 .map(|oid| oid.to_string())
                 .collect::<Vec<String>>(),
         )?;
+        if self.notes.is_empty() {
+            state.skip_field("notes")?;
+        } else {
+            state.serialize_field("notes", &self.notes)?;
+        }
         state.end()
     }
 }
@@ -185,6 +196,7 @@ impl TryFrom<git2::Commit<'_>> for Commit {
             message,
             summary,
             parents,
+            notes: Vec::new(),
         })
     }
 }
@@ -12,7 +12,21 @@ use serde::{ser, ser::SerializeStruct, Serialize, Serializer};
 
 use git_ext::Oid;
 
+pub mod combined;
 pub mod git;
+pub mod inline;
+pub mod parse;
+
+mod patch;
+
+mod options;
+pub use options::{DiffOptions, Whitespace};
+
+mod stat;
+pub use stat::DirStat;
+
+mod stream;
+pub use stream::DiffStream;
 
 /// The serializable representation of a `git diff`.
 ///
@@ -119,6 +133,7 @@ impl Diff {
         new_path: PathBuf,
         old: DiffFile,
         new: DiffFile,
+        similarity: u16,
         content: DiffContent,
     ) {
         self.update_stats(&DiffContent::Empty);
@@ -127,6 +142,7 @@ impl Diff {
             new_path,
             old,
             new,
+            similarity,
             diff: content,
         });
         self.files.push(diff);
@@ -138,6 +154,7 @@ impl Diff {
         new_path: PathBuf,
         old: DiffFile,
         new: DiffFile,
+        similarity: u16,
         content: DiffContent,
     ) {
         self.update_stats(&DiffContent::Empty);
@@ -146,6 +163,7 @@ impl Diff {
             new_path,
             old,
             new,
+            similarity,
             diff: content,
         });
         self.files.push(diff);
@@ -162,6 +180,36 @@ impl Diff {
         let diff = FileDiff::Deleted(Deleted { path, diff, old });
         self.files.push(diff);
     }
+
+    /// Inserts any kind of [`FileDiff`], updating the stats accordingly.
+    pub(crate) fn insert(&mut self, file: FileDiff) {
+        match file {
+            FileDiff::Added(Added { path, diff, new }) => self.insert_added(path, diff, new),
+            FileDiff::Deleted(Deleted { path, diff, old }) => self.insert_deleted(path, diff, old),
+            FileDiff::Modified(Modified {
+                path,
+                diff,
+                old,
+                new,
+            }) => self.insert_modified(path, diff, old, new),
+            FileDiff::Moved(Moved {
+                old_path,
+                old,
+                new_path,
+                new,
+                similarity,
+                diff,
+            }) => self.insert_moved(old_path, new_path, old, new, similarity, diff),
+            FileDiff::Copied(Copied {
+                old_path,
+                new_path,
+                old,
+                new,
+                similarity,
+                diff,
+            }) => self.insert_copied(old_path, new_path, old, new, similarity, diff),
+        }
+    }
 }
 
 /// A file that was added within a [`Diff`].
@@ -193,6 +241,8 @@ pub struct Moved {
     /// The new path to this file, relative to the repository root.
     pub new_path: PathBuf,
     pub new: DiffFile,
+    /// How similar the old and new file are, in percent.
+    pub similarity: u16,
     pub diff: DiffContent,
 }
 
@@ -209,11 +259,12 @@ impl Serialize for Moved {
             state.serialize_field("current", &self.new)?;
             state.end()
         } else {
-            let mut state = serializer.serialize_struct("Moved", 5)?;
+            let mut state = serializer.serialize_struct("Moved", 6)?;
             state.serialize_field("oldPath", &self.old_path)?;
             state.serialize_field("newPath", &self.new_path)?;
             state.serialize_field("old", &self.old)?;
             state.serialize_field("new", &self.new)?;
+            state.serialize_field("similarity", &self.similarity)?;
             state.serialize_field("diff", &self.diff)?;
             state.end()
         }
@@ -229,6 +280,8 @@ pub struct Copied {
     pub new_path: PathBuf,
     pub old: DiffFile,
     pub new: DiffFile,
+    /// How similar the old and new file are, in percent.
+    pub similarity: u16,
     pub diff: DiffContent,
 }
 
@@ -245,11 +298,12 @@ impl Serialize for Copied {
             state.serialize_field("current", &self.new)?;
             state.end()
         } else {
-            let mut state = serializer.serialize_struct("Copied", 5)?;
+            let mut state = serializer.serialize_struct("Copied", 6)?;
             state.serialize_field("oldPath", &self.old_path)?;
             state.serialize_field("newPath", &self.new_path)?;
             state.serialize_field("old", &self.old)?;
             state.serialize_field("new", &self.new)?;
+            state.serialize_field("similarity", &self.similarity)?;
             state.serialize_field("diff", &self.diff)?;
             state.end()
         }
@@ -288,6 +342,7 @@ pub struct Modified {
     serde(tag = "type", rename_all = "camelCase")
 )]
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum DiffContent {
     /// The file is a binary file and so no set of changes can be provided.
     Binary,
@@ -298,7 +353,12 @@ pub enum DiffContent {
         stats: FileStats,
         eof: EofNewLine,
     },
+    /// The content of the file did not change, e.g. it was only renamed or
+    /// its mode changed, or it is empty.
     Empty,
+    /// The file exceeds the limits of a [`DiffStream`], so its changes were
+    /// not loaded.
+    TooLarge,
 }
 
 impl DiffContent {
@@ -314,6 +374,7 @@ impl DiffContent {
             DiffContent::Plain { stats, .. } => Some(stats),
             DiffContent::Empty => None,
             DiffContent::Binary => None,
+            DiffContent::TooLarge => None,
         }
     }
 }
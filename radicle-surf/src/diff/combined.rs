@@ -0,0 +1,407 @@
+//! The combined diff of a merge commit against all of its parents, i.e.
+//! `git diff --cc`.
+//!
+//! Only the files that differ from every parent are part of a
+//! [`CombinedDiff`], and only the hunks that differ from every parent are
+//! kept, so that changes taken as is from one of the parents are hidden.
+//! What remains are the changes that were made while merging, such as
+//! conflict resolutions.
+//!
+//! A [`CombinedDiff`] can be retrieved via
+//! [`crate::Repository::diff_combined`].
+
+use std::{
+    collections::BTreeMap,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::{Error, Repository};
+
+use super::{git::error, DiffFile, DiffOptions, FileMode, Line};
+
+/// The number of unchanged lines surrounding each hunk.
+const CONTEXT_LINES: usize = 3;
+
+/// The combined diff of a commit against all of its parents.
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CombinedDiff {
+    files: Vec<CombinedFile>,
+}
+
+/// A file that differs from every parent within a [`CombinedDiff`].
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CombinedFile {
+    /// The path to this file, relative to the repository root.
+    pub path: PathBuf,
+    /// The file in each parent, in the order of the parents, or `None` if
+    /// it does not exist in that parent.
+    pub parents: Vec<Option<DiffFile>>,
+    /// The file in the commit, or `None` if it was deleted.
+    pub new: Option<DiffFile>,
+    pub diff: CombinedContent,
+}
+
+/// The set of changes of a [`CombinedFile`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize),
+    serde(tag = "type", rename_all = "camelCase")
+)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CombinedContent {
+    /// The file is a binary file or a submodule, in the commit or in any of
+    /// the parents.
+    Binary,
+    /// The hunks of a plaintext file.
+    Plain { hunks: Vec<CombinedHunk> },
+}
+
+/// A set of changes across multiple lines of a [`CombinedFile`].
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CombinedHunk {
+    /// The header, e.g. `@@@ -1,3 -1,2 +1,4 @@@`.
+    pub header: Line,
+    pub lines: Vec<CombinedLine>,
+    /// The line range in each parent, in the order of the parents.
+    pub parents: Vec<Range<u32>>,
+    /// The line range in the commit.
+    pub new: Range<u32>,
+}
+
+/// A line of a [`CombinedHunk`], with its origin relative to each parent.
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CombinedLine {
+    pub line: Line,
+    /// The origin relative to each parent, in the order of the parents.
+    ///
+    /// For a line that was deleted, the parents that did not contain it are
+    /// [`Origin::Context`], like the blank columns of `git diff --cc`.
+    pub origins: Vec<Origin>,
+    /// The line number in the commit, or `None` if the line was deleted.
+    pub line_no: Option<u32>,
+}
+
+/// The origin of a [`CombinedLine`] relative to a single parent.
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Origin {
+    Context,
+    Addition,
+    Deletion,
+}
+
+impl CombinedDiff {
+    /// Computes the combined diff of `commit` against its parents, or
+    /// against the empty tree if it has none.
+    pub(crate) fn new(repo: &Repository, commit: &git2::Commit) -> Result<Self, Error> {
+        let parents = commit.parents().map(Some).collect::<Vec<_>>();
+        let parents = if parents.is_empty() {
+            vec![None]
+        } else {
+            parents
+        };
+        let options = DiffOptions::default().no_renames().no_copies();
+
+        // The changed files against each parent, with their sides.
+        let mut changes = Vec::with_capacity(parents.len());
+        for parent in &parents {
+            let diff = repo.diff_commits(&[], parent.as_ref(), commit, &options)?;
+            let mut changed = BTreeMap::new();
+            for delta in diff.deltas() {
+                let old = (delta.status() != git2::Delta::Added)
+                    .then(|| DiffFile::try_from(delta.old_file()))
+                    .transpose()
+                    .map_err(error::Diff::from)?;
+                let new = (delta.status() != git2::Delta::Deleted)
+                    .then(|| DiffFile::try_from(delta.new_file()))
+                    .transpose()
+                    .map_err(error::Diff::from)?;
+                let path = delta
+                    .new_file()
+                    .path()
+                    .or_else(|| delta.old_file().path())
+                    .ok_or(error::Diff::PathUnavailable)?;
+                changed.insert(path.to_path_buf(), (old, new));
+            }
+            changes.push(changed);
+        }
+
+        let mut files = Vec::new();
+        let Some((first, rest)) = changes.split_first() else {
+            return Ok(Self { files });
+        };
+        for (path, (_, new)) in first {
+            if !rest.iter().all(|changed| changed.contains_key(path)) {
+                continue;
+            }
+            let parents = changes
+                .iter()
+                .map(|changed| changed[path].0.clone())
+                .collect::<Vec<_>>();
+            if let Some(file) = CombinedFile::new(repo, path, parents, new.clone())? {
+                files.push(file);
+            }
+        }
+        Ok(Self { files })
+    }
+
+    /// Returns an iterator of the files in the diff.
+    pub fn files(&self) -> impl Iterator<Item = &CombinedFile> {
+        self.files.iter()
+    }
+
+    /// Returns owned files in the diff.
+    pub fn into_files(self) -> Vec<CombinedFile> {
+        self.files
+    }
+}
+
+impl CombinedFile {
+    /// Computes the combined diff of the file at `path`, or `None` if none
+    /// of its hunks differ from every parent.
+    fn new(
+        repo: &Repository,
+        path: &Path,
+        parents: Vec<Option<DiffFile>>,
+        new: Option<DiffFile>,
+    ) -> Result<Option<Self>, Error> {
+        if new
+            .iter()
+            .chain(parents.iter().flatten())
+            .any(|file| file.mode == FileMode::Commit)
+        {
+            return Ok(Some(Self {
+                path: path.to_path_buf(),
+                parents,
+                new,
+                diff: CombinedContent::Binary,
+            }));
+        }
+
+        let blob = |file: &Option<DiffFile>| {
+            file.as_ref()
+                .map(|file| repo.find_blob(file.oid))
+                .transpose()
+        };
+        let new_blob = blob(&new)?;
+        let parent_blobs = parents.iter().map(blob).collect::<Result<Vec<_>, _>>()?;
+
+        let is_binary = new_blob
+            .iter()
+            .chain(parent_blobs.iter().flatten())
+            .any(|blob| blob.is_binary());
+        let diff = if is_binary {
+            CombinedContent::Binary
+        } else {
+            let contents = parent_blobs
+                .iter()
+                .map(|blob| blob.as_ref().map_or(&[][..], |b| b.content()))
+                .collect::<Vec<_>>();
+            let hunks = hunks(&contents, new_blob.as_ref().map_or(&[], |b| b.content()))?;
+            if hunks.is_empty() {
+                return Ok(None);
+            }
+            CombinedContent::Plain { hunks }
+        };
+
+        Ok(Some(Self {
+            path: path.to_path_buf(),
+            parents,
+            new,
+            diff,
+        }))
+    }
+}
+
+impl CombinedLine {
+    /// Whether this line exists in the parent at `index`.
+    fn in_parent(&self, index: usize) -> bool {
+        match self.line_no {
+            Some(_) => self.origins[index] == Origin::Context,
+            None => self.origins[index] == Origin::Deletion,
+        }
+    }
+}
+
+/// Computes the hunks of `new` against all `parents` that differ from
+/// every parent.
+fn hunks(parents: &[&[u8]], new: &[u8]) -> Result<Vec<CombinedHunk>, Error> {
+    let new_lines = new.split_inclusive(|b| *b == b'\n').collect::<Vec<_>>();
+    let mut added = vec![vec![false; new_lines.len()]; parents.len()];
+    // The lines deleted from each parent, keyed by the number of lines of
+    // `new` that precede them.
+    let mut deleted = vec![BTreeMap::<usize, Vec<Vec<u8>>>::new(); parents.len()];
+
+    for (i, parent) in parents.iter().enumerate() {
+        let mut opts = git2::DiffOptions::new();
+        opts.context_lines(0);
+        let patch = git2::Patch::from_buffers(parent, None, new, None, Some(&mut opts))?;
+        for h in 0..patch.num_hunks() {
+            let (hunk, len) = patch.hunk(h)?;
+            let position = if hunk.new_lines() == 0 {
+                hunk.new_start()
+            } else {
+                hunk.new_start() - 1
+            } as usize;
+            for l in 0..len {
+                let line = patch.line_in_hunk(h, l)?;
+                match (line.origin_value(), line.new_lineno()) {
+                    (git2::DiffLineType::Addition, Some(n)) => added[i][n as usize - 1] = true,
+                    (git2::DiffLineType::Deletion, _) => deleted[i]
+                        .entry(position)
+                        .or_default()
+                        .push(line.content().to_vec()),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    let mut lines = Vec::new();
+    for position in 0..=new_lines.len() {
+        lines.extend(coalesce(&deleted, position));
+        if let Some(line) = new_lines.get(position) {
+            lines.push(CombinedLine {
+                line: Line(line.to_vec()),
+                origins: added
+                    .iter()
+                    .map(|added| {
+                        if added[position] {
+                            Origin::Addition
+                        } else {
+                            Origin::Context
+                        }
+                    })
+                    .collect(),
+                line_no: Some(position as u32 + 1),
+            });
+        }
+    }
+
+    Ok(windows(&lines, parents.len())
+        .into_iter()
+        .filter(|window| {
+            (0..parents.len()).all(|i| {
+                lines[window.clone()]
+                    .iter()
+                    .any(|line| line.origins[i] != Origin::Context)
+            })
+        })
+        .map(|window| hunk(&lines, window, parents.len()))
+        .collect())
+}
+
+/// Merges the lines deleted from each parent at `position`, so that a line
+/// deleted from several parents appears only once.
+fn coalesce(deleted: &[BTreeMap<usize, Vec<Vec<u8>>>], position: usize) -> Vec<CombinedLine> {
+    let mut lines: Vec<CombinedLine> = Vec::new();
+    for (i, parent) in deleted.iter().enumerate() {
+        let line = |content: &[u8]| {
+            let mut origins = vec![Origin::Context; deleted.len()];
+            origins[i] = Origin::Deletion;
+            CombinedLine {
+                line: Line(content.to_vec()),
+                origins,
+                line_no: None,
+            }
+        };
+        // Lines that are not deleted from the previous parents are placed
+        // right before the next line that is.
+        let mut from = 0;
+        let mut pending = Vec::new();
+        for content in parent.get(&position).into_iter().flatten() {
+            let matching = lines[from..]
+                .iter()
+                .position(|l| l.line.0 == *content && l.origins[i] == Origin::Context);
+            match matching {
+                Some(k) => {
+                    let at = from + k;
+                    let n = pending.len();
+                    lines.splice(at..at, pending.drain(..));
+                    lines[at + n].origins[i] = Origin::Deletion;
+                    from = at + n + 1;
+                }
+                None => pending.push(line(content)),
+            }
+        }
+        lines.append(&mut pending);
+    }
+    lines
+}
+
+/// The ranges of `lines` that contain changes, including the surrounding
+/// context, where overlapping ranges are merged.
+fn windows(lines: &[CombinedLine], parents: usize) -> Vec<Range<usize>> {
+    let mut windows: Vec<Range<usize>> = Vec::new();
+    let changed = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| (0..parents).any(|i| line.origins[i] != Origin::Context));
+    for (i, _) in changed {
+        let start = i.saturating_sub(CONTEXT_LINES);
+        let end = (i + 1 + CONTEXT_LINES).min(lines.len());
+        match windows.last_mut() {
+            Some(last) if last.end >= start => last.end = end,
+            _ => windows.push(start..end),
+        }
+    }
+    windows
+}
+
+/// Builds the hunk of the `window` of `lines`.
+fn hunk(lines: &[CombinedLine], window: Range<usize>, parents: usize) -> CombinedHunk {
+    let range = |before: usize, len: usize| {
+        let start = if len == 0 { before } else { before + 1 };
+        start as u32..(start + len) as u32
+    };
+    let parent_ranges = (0..parents)
+        .map(|i| {
+            let before = lines[..window.start]
+                .iter()
+                .filter(|l| l.in_parent(i))
+                .count();
+            let len = lines[window.clone()]
+                .iter()
+                .filter(|l| l.in_parent(i))
+                .count();
+            range(before, len)
+        })
+        .collect::<Vec<_>>();
+    let new = {
+        let before = lines[..window.start]
+            .iter()
+            .filter(|l| l.line_no.is_some())
+            .count();
+        let len = lines[window.clone()]
+            .iter()
+            .filter(|l| l.line_no.is_some())
+            .count();
+        range(before, len)
+    };
+
+    let marker = "@".repeat(parents + 1);
+    let mut header = marker.clone();
+    for range in &parent_ranges {
+        header.push_str(&format!(" -{},{}", range.start, range.end - range.start));
+    }
+    header.push_str(&format!(
+        " +{},{} {marker}\n",
+        new.start,
+        new.end - new.start
+    ));
+
+    CombinedHunk {
+        header: Line::from(header),
+        lines: lines[window].to_vec(),
+        parents: parent_ranges,
+        new,
+    }
+}
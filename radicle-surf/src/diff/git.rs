@@ -1,8 +1,8 @@
-use std::convert::TryFrom;
+use std::{convert::TryFrom, path::Path};
 
 use super::{
-    Diff, DiffContent, DiffFile, EofNewLine, FileMode, FileStats, Hunk, Hunks, Line, Modification,
-    Stats,
+    Added, Copied, Deleted, Diff, DiffContent, DiffFile, EofNewLine, FileDiff, FileMode, FileStats,
+    Hunk, Hunks, Line, Modification, Modified, Moved, Stats,
 };
 
 pub mod error {
@@ -215,8 +215,6 @@ impl TryFrom<git2::Diff<'_>> for Diff {
     type Error = error::Diff;
 
     fn try_from(git_diff: git2::Diff) -> Result<Diff, Self::Error> {
-        use git2::Delta;
-
         let mut diff = Diff::new();
 
         // This allows libgit2 to run the binary detection.
@@ -224,154 +222,122 @@ impl TryFrom<git2::Diff<'_>> for Diff {
         git_diff.foreach(&mut |_, _| true, None, None, None)?;
 
         for (idx, delta) in git_diff.deltas().enumerate() {
-            match delta.status() {
-                Delta::Added => created(&mut diff, &git_diff, idx, &delta)?,
-                Delta::Deleted => deleted(&mut diff, &git_diff, idx, &delta)?,
-                Delta::Modified => modified(&mut diff, &git_diff, idx, &delta)?,
-                Delta::Renamed => renamed(&mut diff, &git_diff, idx, &delta)?,
-                Delta::Copied => copied(&mut diff, &git_diff, idx, &delta)?,
-                status => {
-                    return Err(error::Diff::DeltaUnhandled(status));
-                }
-            }
+            let file = file_diff(&delta, |path| {
+                let is_binary = match delta.status() {
+                    git2::Delta::Deleted => delta.old_file().is_binary(),
+                    _ => delta.new_file().is_binary(),
+                };
+                let patch = git2::Patch::from_diff(&git_diff, idx)?;
+                content(&delta, path, is_binary, patch)
+            })?;
+            diff.insert(file);
         }
 
         Ok(diff)
     }
 }
 
-fn created(
-    diff: &mut Diff,
-    git_diff: &git2::Diff<'_>,
-    idx: usize,
-    delta: &git2::DiffDelta<'_>,
-) -> Result<(), error::Diff> {
-    let diff_file = delta.new_file();
-    let is_binary = diff_file.is_binary();
-    let path = diff_file
-        .path()
-        .ok_or(error::Diff::PathUnavailable)?
-        .to_path_buf();
-    let new = DiffFile::try_from(diff_file)?;
-
-    let patch = git2::Patch::from_diff(git_diff, idx)?;
-    if is_binary {
-        diff.insert_added(path, DiffContent::Binary, new);
-    } else if let Some(patch) = patch {
-        diff.insert_added(path, DiffContent::try_from(patch)?, new);
-    } else {
-        return Err(error::Diff::PatchUnavailable(path));
-    }
-    Ok(())
-}
-
-fn deleted(
-    diff: &mut Diff,
-    git_diff: &git2::Diff<'_>,
-    idx: usize,
-    delta: &git2::DiffDelta<'_>,
-) -> Result<(), error::Diff> {
-    let diff_file = delta.old_file();
-    let is_binary = diff_file.is_binary();
-    let path = diff_file
-        .path()
-        .ok_or(error::Diff::PathUnavailable)?
-        .to_path_buf();
-    let patch = git2::Patch::from_diff(git_diff, idx)?;
-    let old = DiffFile::try_from(diff_file)?;
-
-    if is_binary {
-        diff.insert_deleted(path, DiffContent::Binary, old);
-    } else if let Some(patch) = patch {
-        diff.insert_deleted(path, DiffContent::try_from(patch)?, old);
-    } else {
-        return Err(error::Diff::PatchUnavailable(path));
+/// Converts a `delta` into a [`FileDiff`], where `load` is called with the
+/// path of the file to load its changes.
+pub(crate) fn file_diff<F>(delta: &git2::DiffDelta<'_>, load: F) -> Result<FileDiff, error::Diff>
+where
+    F: FnOnce(&Path) -> Result<DiffContent, error::Diff>,
+{
+    use git2::Delta;
+
+    let path = |file: git2::DiffFile<'_>| {
+        file.path()
+            .map(Path::to_path_buf)
+            .ok_or(error::Diff::PathUnavailable)
+    };
+    match delta.status() {
+        Delta::Added | Delta::Untracked => {
+            let path = path(delta.new_file())?;
+            let new = DiffFile::try_from(delta.new_file())?;
+            let diff = load(&path)?;
+            Ok(FileDiff::Added(Added { path, diff, new }))
+        }
+        Delta::Deleted => {
+            let path = path(delta.old_file())?;
+            let old = DiffFile::try_from(delta.old_file())?;
+            let diff = load(&path)?;
+            Ok(FileDiff::Deleted(Deleted { path, diff, old }))
+        }
+        Delta::Modified => {
+            let path = path(delta.new_file())?;
+            let old = DiffFile::try_from(delta.old_file())?;
+            let new = DiffFile::try_from(delta.new_file())?;
+            let diff = load(&path)?;
+            Ok(FileDiff::Modified(Modified {
+                path,
+                diff,
+                old,
+                new,
+            }))
+        }
+        Delta::Renamed => {
+            let old_path = path(delta.old_file())?;
+            let new_path = path(delta.new_file())?;
+            let old = DiffFile::try_from(delta.old_file())?;
+            let new = DiffFile::try_from(delta.new_file())?;
+            let diff = load(&new_path)?;
+            Ok(FileDiff::Moved(Moved {
+                old_path,
+                old,
+                new_path,
+                new,
+                similarity: similarity(delta),
+                diff,
+            }))
+        }
+        Delta::Copied => {
+            let old_path = path(delta.old_file())?;
+            let new_path = path(delta.new_file())?;
+            let old = DiffFile::try_from(delta.old_file())?;
+            let new = DiffFile::try_from(delta.new_file())?;
+            let diff = load(&new_path)?;
+            Ok(FileDiff::Copied(Copied {
+                old_path,
+                new_path,
+                old,
+                new,
+                similarity: similarity(delta),
+                diff,
+            }))
+        }
+        status => Err(error::Diff::DeltaUnhandled(status)),
     }
-    Ok(())
 }
 
-fn modified(
-    diff: &mut Diff,
-    git_diff: &git2::Diff<'_>,
-    idx: usize,
-    delta: &git2::DiffDelta<'_>,
-) -> Result<(), error::Diff> {
-    let diff_file = delta.new_file();
-    let path = diff_file
-        .path()
-        .ok_or(error::Diff::PathUnavailable)?
-        .to_path_buf();
-    let patch = git2::Patch::from_diff(git_diff, idx)?;
-    let old = DiffFile::try_from(delta.old_file())?;
-    let new = DiffFile::try_from(delta.new_file())?;
+/// The similarity of a renamed or copied file, in percent.
+fn similarity(delta: &git2::DiffDelta<'_>) -> u16 {
+    use git2::Binding as _;
 
-    if diff_file.is_binary() {
-        diff.insert_modified(path, DiffContent::Binary, old, new);
-        Ok(())
-    } else if let Some(patch) = patch {
-        diff.insert_modified(path, DiffContent::try_from(patch)?, old, new);
-        Ok(())
-    } else {
-        Err(error::Diff::PatchUnavailable(path))
-    }
+    // SAFETY: the raw delta is valid for the lifetime of `delta`. `git2`
+    // does not expose the similarity computed by libgit2 otherwise.
+    unsafe { (*delta.raw()).similarity }
 }
 
-fn renamed(
-    diff: &mut Diff,
-    git_diff: &git2::Diff<'_>,
-    idx: usize,
+/// The changes of the file at `path` of `delta`, given its `patch`.
+///
+/// A patch without any hunks, e.g. of a file whose mode changed, is
+/// [`DiffContent::Empty`]. Renamed and copied files without any changes may
+/// have no patch at all, while any other file must have one.
+pub(crate) fn content(
     delta: &git2::DiffDelta<'_>,
-) -> Result<(), error::Diff> {
-    let old_path = delta
-        .old_file()
-        .path()
-        .ok_or(error::Diff::PathUnavailable)?
-        .to_path_buf();
-    let new_path = delta
-        .new_file()
-        .path()
-        .ok_or(error::Diff::PathUnavailable)?
-        .to_path_buf();
-    let patch = git2::Patch::from_diff(git_diff, idx)?;
-    let old = DiffFile::try_from(delta.old_file())?;
-    let new = DiffFile::try_from(delta.new_file())?;
-
-    if delta.new_file().is_binary() {
-        diff.insert_moved(old_path, new_path, old, new, DiffContent::Binary);
-    } else if let Some(patch) = patch {
-        diff.insert_moved(old_path, new_path, old, new, DiffContent::try_from(patch)?);
-    } else {
-        diff.insert_moved(old_path, new_path, old, new, DiffContent::Empty);
+    path: &Path,
+    is_binary: bool,
+    patch: Option<git2::Patch<'_>>,
+) -> Result<DiffContent, error::Diff> {
+    if is_binary {
+        return Ok(DiffContent::Binary);
     }
-    Ok(())
-}
-
-fn copied(
-    diff: &mut Diff,
-    git_diff: &git2::Diff<'_>,
-    idx: usize,
-    delta: &git2::DiffDelta<'_>,
-) -> Result<(), error::Diff> {
-    let old_path = delta
-        .old_file()
-        .path()
-        .ok_or(error::Diff::PathUnavailable)?
-        .to_path_buf();
-    let new_path = delta
-        .new_file()
-        .path()
-        .ok_or(error::Diff::PathUnavailable)?
-        .to_path_buf();
-    let patch = git2::Patch::from_diff(git_diff, idx)?;
-    let old = DiffFile::try_from(delta.old_file())?;
-    let new = DiffFile::try_from(delta.new_file())?;
-
-    if delta.new_file().is_binary() {
-        diff.insert_copied(old_path, new_path, old, new, DiffContent::Binary);
-    } else if let Some(patch) = patch {
-        diff.insert_copied(old_path, new_path, old, new, DiffContent::try_from(patch)?);
-    } else {
-        diff.insert_copied(old_path, new_path, old, new, DiffContent::Empty);
+    match patch {
+        Some(patch) if patch.num_hunks() == 0 => Ok(DiffContent::Empty),
+        Some(patch) => Ok(DiffContent::try_from(patch)?),
+        None => match delta.status() {
+            git2::Delta::Renamed | git2::Delta::Copied => Ok(DiffContent::Empty),
+            _ => Err(error::Diff::PatchUnavailable(path.to_path_buf())),
+        },
     }
-    Ok(())
 }
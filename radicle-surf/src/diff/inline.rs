@@ -0,0 +1,232 @@
+//! Intra-line changes of a [`Hunk`], for highlighting the exact parts of
+//! a line that were changed.
+//!
+//! Within a hunk, each run of deleted lines that is directly followed by a
+//! run of added lines is paired line by line, i.e. the first deletion with
+//! the first addition, and so on. The tokens of each pair are then compared
+//! to find the ranges that differ.
+//!
+//! See [`Hunk::highlight`].
+
+use std::ops::Range;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use super::{Hunk, Line, Modification};
+
+/// Pairs of lines whose tokens exceed this product are not compared, and
+/// are considered changed as a whole.
+const MAX_COMPARISONS: usize = 1 << 16;
+
+/// The granularity of the intra-line changes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Granularity {
+    /// Compare lines word by word, where a word is a run of alphanumeric
+    /// characters or underscores, a run of whitespace, or any other single
+    /// character.
+    Word,
+    /// Compare lines character by character.
+    Char,
+}
+
+/// A [`Modification`] enriched with the byte ranges of its [`Line`] that
+/// were changed.
+///
+/// The ranges are always empty for context lines, and for deleted or added
+/// lines that could not be paired.
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Highlighted {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub modification: Modification,
+    /// The changed byte ranges, ordered and non-overlapping.
+    pub changes: Vec<Range<usize>>,
+}
+
+impl Hunk<Modification> {
+    /// Returns this hunk where each line carries the ranges that were
+    /// changed within it, compared at the given `granularity`.
+    pub fn highlight(&self, granularity: Granularity) -> Hunk<Highlighted> {
+        let mut lines = self
+            .lines
+            .iter()
+            .map(|modification| Highlighted {
+                modification: modification.clone(),
+                changes: Vec::new(),
+            })
+            .collect::<Vec<_>>();
+
+        let mut i = 0;
+        while i < lines.len() {
+            let deletions = run(&lines[i..], |m| matches!(m, Modification::Deletion(_)));
+            let additions = run(&lines[i + deletions..], |m| {
+                matches!(m, Modification::Addition(_))
+            });
+            for k in 0..deletions.min(additions) {
+                let (old, new) = (i + k, i + deletions + k);
+                let (old_changes, new_changes) =
+                    changes(line(&lines[old]), line(&lines[new]), granularity);
+                lines[old].changes = old_changes;
+                lines[new].changes = new_changes;
+            }
+            i += (deletions + additions).max(1);
+        }
+
+        Hunk {
+            header: self.header.clone(),
+            lines,
+            old: self.old.clone(),
+            new: self.new.clone(),
+        }
+    }
+}
+
+/// The number of consecutive lines at the start of `lines` that satisfy
+/// `pred`.
+fn run(lines: &[Highlighted], pred: impl Fn(&Modification) -> bool) -> usize {
+    lines
+        .iter()
+        .take_while(|line| pred(&line.modification))
+        .count()
+}
+
+fn line(highlighted: &Highlighted) -> &Line {
+    match &highlighted.modification {
+        Modification::Addition(addition) => &addition.line,
+        Modification::Deletion(deletion) => &deletion.line,
+        Modification::Context { line, .. } => line,
+    }
+}
+
+/// Returns the changed ranges of `old` and `new`.
+///
+/// Lines that are not valid UTF-8, or that are too long to compare, are
+/// considered changed as a whole.
+fn changes(
+    old: &Line,
+    new: &Line,
+    granularity: Granularity,
+) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+    let whole = |line: &Line| {
+        let len = content(line.as_bytes()).len();
+        (len > 0).then_some(0..len).into_iter().collect::<Vec<_>>()
+    };
+    let (Ok(old_str), Ok(new_str)) = (
+        std::str::from_utf8(content(old.as_bytes())),
+        std::str::from_utf8(content(new.as_bytes())),
+    ) else {
+        return (whole(old), whole(new));
+    };
+
+    let old_tokens = tokenize(old_str, granularity);
+    let new_tokens = tokenize(new_str, granularity);
+    if old_tokens.len().saturating_mul(new_tokens.len()) > MAX_COMPARISONS {
+        return (whole(old), whole(new));
+    }
+
+    let (old_common, new_common) = lcs(old_str, &old_tokens, new_str, &new_tokens);
+    (
+        ranges(&old_tokens, &old_common),
+        ranges(&new_tokens, &new_common),
+    )
+}
+
+/// Strips the line ending from `bytes`.
+fn content(bytes: &[u8]) -> &[u8] {
+    let bytes = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+    bytes.strip_suffix(b"\r").unwrap_or(bytes)
+}
+
+/// Splits `s` into the byte ranges of its tokens.
+fn tokenize(s: &str, granularity: Granularity) -> Vec<Range<usize>> {
+    let mut tokens: Vec<Range<usize>> = Vec::new();
+    let mut prev: Option<Class> = None;
+    for (i, c) in s.char_indices() {
+        let class = Class::of(c);
+        let end = i + c.len_utf8();
+        match (granularity, tokens.last_mut(), prev) {
+            (Granularity::Word, Some(last), Some(p)) if p == class && class != Class::Other => {
+                last.end = end
+            }
+            _ => tokens.push(i..end),
+        }
+        prev = Some(class);
+    }
+    tokens
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Class {
+    Word,
+    Space,
+    Other,
+}
+
+impl Class {
+    fn of(c: char) -> Self {
+        if c.is_alphanumeric() || c == '_' {
+            Self::Word
+        } else if c.is_whitespace() {
+            Self::Space
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// Computes the longest common subsequence of the tokens, returning for
+/// each token of either side whether it is part of it.
+fn lcs(
+    old: &str,
+    old_tokens: &[Range<usize>],
+    new: &str,
+    new_tokens: &[Range<usize>],
+) -> (Vec<bool>, Vec<bool>) {
+    let (n, m) = (old_tokens.len(), new_tokens.len());
+    let eq = |i: usize, j: usize| old[old_tokens[i].clone()] == new[new_tokens[j].clone()];
+
+    // `table[i][j]` is the length of the LCS of `old[i..]` and `new[j..]`.
+    let mut table = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[i][j] = if eq(i, j) {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+
+    let mut old_common = vec![false; n];
+    let mut new_common = vec![false; m];
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if eq(i, j) {
+            old_common[i] = true;
+            new_common[j] = true;
+            i += 1;
+            j += 1;
+        } else if table[i + 1][j] >= table[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    (old_common, new_common)
+}
+
+/// Merges the adjacent tokens that are not common into ranges.
+fn ranges(tokens: &[Range<usize>], common: &[bool]) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for (token, common) in tokens.iter().zip(common) {
+        if *common {
+            continue;
+        }
+        match ranges.last_mut() {
+            Some(last) if last.end == token.start => last.end = token.end,
+            _ => ranges.push(token.clone()),
+        }
+    }
+    ranges
+}
@@ -0,0 +1,155 @@
+/// Options for computing a [`super::Diff`].
+///
+/// The defaults match `git diff` with rename and copy detection enabled:
+///
+/// ```no_run
+/// use radicle_surf::diff::{DiffOptions, Whitespace};
+///
+/// let options = DiffOptions::default()
+///     .context_lines(5)
+///     .whitespace(Whitespace::IgnoreChange)
+///     .rename_threshold(70);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiffOptions {
+    context_lines: u32,
+    interhunk_lines: u32,
+    whitespace: Whitespace,
+    ignore_blank_lines: bool,
+    renames: Option<u16>,
+    copies: Option<u16>,
+    max_size: Option<i64>,
+}
+
+/// How whitespace is handled when comparing lines.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Whitespace {
+    /// Whitespace is compared like any other character.
+    #[default]
+    Compare,
+    /// Ignore all whitespace, i.e. `git diff -w`.
+    IgnoreAll,
+    /// Ignore changes in the amount of whitespace, i.e. `git diff -b`.
+    IgnoreChange,
+    /// Ignore whitespace at the end of lines, i.e.
+    /// `git diff --ignore-space-at-eol`.
+    IgnoreEol,
+}
+
+/// The default similarity threshold of rename and copy detection, in
+/// percent.
+const DEFAULT_THRESHOLD: u16 = 50;
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            context_lines: 3,
+            interhunk_lines: 0,
+            whitespace: Whitespace::default(),
+            ignore_blank_lines: false,
+            renames: Some(DEFAULT_THRESHOLD),
+            copies: Some(DEFAULT_THRESHOLD),
+            max_size: None,
+        }
+    }
+}
+
+impl DiffOptions {
+    /// Sets the number of unchanged lines surrounding each hunk. The
+    /// default is `3`.
+    pub fn context_lines(mut self, lines: u32) -> Self {
+        self.context_lines = lines;
+        self
+    }
+
+    /// Sets the maximum number of unchanged lines between two hunks
+    /// before they are merged into one. The default is `0`.
+    pub fn interhunk_lines(mut self, lines: u32) -> Self {
+        self.interhunk_lines = lines;
+        self
+    }
+
+    /// Sets how whitespace is handled when comparing lines.
+    pub fn whitespace(mut self, whitespace: Whitespace) -> Self {
+        self.whitespace = whitespace;
+        self
+    }
+
+    /// Ignores changes whose lines are all blank.
+    pub fn ignore_blank_lines(mut self, ignore: bool) -> Self {
+        self.ignore_blank_lines = ignore;
+        self
+    }
+
+    /// Sets the similarity, in percent, for a deleted and an added file to
+    /// be considered a rename. The default is `50`.
+    pub fn rename_threshold(mut self, threshold: u16) -> Self {
+        self.renames = Some(threshold);
+        self
+    }
+
+    /// Sets the similarity, in percent, for an added file to be considered
+    /// a copy of a modified file. The default is `50`.
+    pub fn copy_threshold(mut self, threshold: u16) -> Self {
+        self.copies = Some(threshold);
+        self
+    }
+
+    /// Disables rename detection, so that renamed files are reported as
+    /// deleted and added.
+    ///
+    /// Note that libgit2 may still pair a deleted and an added file while
+    /// copy detection is enabled, see [`DiffOptions::no_copies`].
+    pub fn no_renames(mut self) -> Self {
+        self.renames = None;
+        self
+    }
+
+    /// Disables copy detection, so that copied files are reported as added.
+    pub fn no_copies(mut self) -> Self {
+        self.copies = None;
+        self
+    }
+
+    /// Sets the size, in bytes, above which a file is treated as binary,
+    /// and so no content is loaded for it. The default is libgit2's, i.e.
+    /// 512 MiB.
+    pub fn max_size(mut self, bytes: i64) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    /// Applies these options to the `git2` options for computing a diff.
+    pub(crate) fn apply(&self, opts: &mut git2::DiffOptions) {
+        opts.context_lines(self.context_lines)
+            .interhunk_lines(self.interhunk_lines)
+            .ignore_whitespace(self.whitespace == Whitespace::IgnoreAll)
+            .ignore_whitespace_change(self.whitespace == Whitespace::IgnoreChange)
+            .ignore_whitespace_eol(self.whitespace == Whitespace::IgnoreEol)
+            .ignore_blank_lines(self.ignore_blank_lines);
+        if let Some(max_size) = self.max_size {
+            opts.max_size(max_size);
+        }
+    }
+
+    /// Returns the `git2` options for detecting renames and copies, or
+    /// `None` if both are disabled.
+    ///
+    /// Note that libgit2 falls back to the `diff.renames` configuration when
+    /// no detection is enabled, so `find_similar` must not be called at all.
+    pub(crate) fn find_options(&self) -> Option<git2::DiffFindOptions> {
+        if self.renames.is_none() && self.copies.is_none() {
+            return None;
+        }
+        let mut find_opts = git2::DiffFindOptions::new();
+        if let Some(threshold) = self.renames {
+            find_opts.renames(true);
+            find_opts.rename_threshold(threshold);
+        }
+        if let Some(threshold) = self.copies {
+            find_opts.copies(true);
+            find_opts.copy_threshold(threshold);
+        }
+        Some(find_opts)
+    }
+}
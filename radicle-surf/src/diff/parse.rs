@@ -0,0 +1,540 @@
+//! Parsing of unified patches, as produced by `git diff` or `diff -u`,
+//! into a [`Diff`].
+//!
+//! Besides the hunks of each file, the extended headers of `git diff` are
+//! understood, i.e. file modes, `index` lines, renames and copies with their
+//! similarity, as well as binary markers and missing newlines at the end of
+//! files. Any text before the first file, such as the headers of an email,
+//! is skipped.
+//!
+//! Object ids that are abbreviated in the patch are padded with zeros, and
+//! are null if the patch does not mention them at all, e.g. for files that
+//! were renamed without changes.
+
+use std::{path::PathBuf, str::FromStr};
+
+use git_ext::Oid;
+
+use super::{
+    Diff, DiffContent, DiffFile, EofNewLine, FileMode, FileStats, Hunk, Hunks, Line, Modification,
+};
+
+pub mod error {
+    use thiserror::Error;
+
+    /// An error parsing a unified patch. The line numbers are 1-based.
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum Parse {
+        #[error("invalid file mode `{mode}` on line {line}")]
+        FileMode { line: usize, mode: String },
+        #[error("invalid hunk header on line {0}")]
+        HunkHeader(usize),
+        #[error("unexpected line {0} in hunk")]
+        HunkLine(usize),
+        #[error("hunk is missing lines at line {0}")]
+        HunkTruncated(usize),
+        #[error("missing file path for the diff on line {0}")]
+        MissingPath(usize),
+        #[error("invalid object id `{oid}` on line {line}")]
+        Oid { line: usize, oid: String },
+        #[error("invalid file path on line {0}")]
+        Path(usize),
+        #[error("invalid similarity index on line {0}")]
+        Similarity(usize),
+    }
+}
+
+impl Diff {
+    /// Parses a unified patch, as produced by `git diff` or `diff -u`.
+    ///
+    /// See the [module documentation](crate::diff::parse) for how the headers of
+    /// the patch are interpreted.
+    pub fn parse(patch: &[u8]) -> Result<Self, error::Parse> {
+        let mut lines = Lines::new(patch);
+        let mut diff = Diff::new();
+
+        while let Some(line) = lines.peek() {
+            if line.starts_with(b"diff --git ") || is_unified_start(&lines) {
+                file(&mut lines)?.insert(&mut diff);
+            } else {
+                lines.next();
+            }
+        }
+        Ok(diff)
+    }
+}
+
+impl FromStr for Diff {
+    type Err = error::Parse;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s.as_bytes())
+    }
+}
+
+/// The lines of a patch, each including its line ending.
+struct Lines<'a> {
+    lines: Vec<&'a [u8]>,
+    pos: usize,
+}
+
+impl<'a> Lines<'a> {
+    fn new(patch: &'a [u8]) -> Self {
+        Self {
+            lines: patch.split_inclusive(|b| *b == b'\n').collect(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<&'a [u8]> {
+        self.lines.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        let line = self.peek()?;
+        self.pos += 1;
+        Some(line)
+    }
+
+    /// The 1-based number of the line returned by the last call to `next`.
+    fn number(&self) -> usize {
+        self.pos
+    }
+}
+
+/// Whether the next lines start a file of a plain unified diff, i.e.
+/// `---` directly followed by `+++`.
+fn is_unified_start(lines: &Lines) -> bool {
+    let next = lines.lines.get(lines.pos + 1);
+    lines.peek().is_some_and(|l| l.starts_with(b"--- "))
+        && next.is_some_and(|l| l.starts_with(b"+++ "))
+}
+
+/// Everything that is known about a file from its headers and hunks.
+#[derive(Default)]
+struct File {
+    old_path: Option<PathBuf>,
+    new_path: Option<PathBuf>,
+    old_mode: Option<FileMode>,
+    new_mode: Option<FileMode>,
+    old_oid: Option<Oid>,
+    new_oid: Option<Oid>,
+    added: bool,
+    deleted: bool,
+    renamed: bool,
+    copied: bool,
+    similarity: Option<u16>,
+    content: Option<DiffContent>,
+}
+
+impl File {
+    fn insert(self, diff: &mut Diff) {
+        let null = || Oid::from(git2::Oid::ZERO_SHA1);
+        let old = DiffFile {
+            oid: self.old_oid.unwrap_or_else(null),
+            mode: self.old_mode.unwrap_or(FileMode::Blob),
+        };
+        let new = DiffFile {
+            oid: self.new_oid.unwrap_or_else(null),
+            mode: self.new_mode.unwrap_or(FileMode::Blob),
+        };
+        // Renames, copies and mode changes may come without any hunks.
+        let content = self.content.unwrap_or(DiffContent::Empty);
+        // `file` ensures that at least one of the paths is known.
+        let (old_path, new_path) = match (self.old_path, self.new_path) {
+            (Some(old), Some(new)) => (old, new),
+            (Some(path), None) | (None, Some(path)) => (path.clone(), path),
+            (None, None) => unreachable!(),
+        };
+
+        if self.added {
+            diff.insert_added(new_path, content, new);
+        } else if self.deleted {
+            diff.insert_deleted(old_path, content, old);
+        } else if self.renamed {
+            let similarity = self.similarity.unwrap_or_default();
+            diff.insert_moved(old_path, new_path, old, new, similarity, content);
+        } else if self.copied {
+            let similarity = self.similarity.unwrap_or_default();
+            diff.insert_copied(old_path, new_path, old, new, similarity, content);
+        } else {
+            diff.insert_modified(new_path, content, old, new);
+        }
+    }
+}
+
+/// Parses the headers and hunks of a single file.
+fn file(lines: &mut Lines) -> Result<File, error::Parse> {
+    let mut file = File::default();
+    let mut start = lines.number() + 1;
+
+    if let Some(line) = lines.peek().and_then(|l| l.strip_prefix(b"diff --git ")) {
+        lines.next();
+        start = lines.number();
+        if let Some((old, new)) = git_paths(trim(line)) {
+            file.old_path = Some(path(&old, start)?);
+            file.new_path = Some(path(&new, start)?);
+        }
+        extended_headers(lines, &mut file)?;
+    }
+
+    if is_unified_start(lines) {
+        let old = lines.next().unwrap_or_default();
+        side_path(old, lines.number(), &mut file.old_path, &mut file.added)?;
+        let new = lines.next().unwrap_or_default();
+        side_path(new, lines.number(), &mut file.new_path, &mut file.deleted)?;
+        file.content = Some(hunks(lines)?);
+    } else if let Some(line) = lines.peek() {
+        if line.starts_with(b"Binary files ") {
+            lines.next();
+            file.content = Some(DiffContent::Binary);
+        } else if line.starts_with(b"GIT binary patch") {
+            lines.next();
+            while lines.peek().is_some_and(|l| !l.starts_with(b"diff --git ")) {
+                lines.next();
+            }
+            file.content = Some(DiffContent::Binary);
+        }
+    }
+
+    if file.old_path.is_none() && file.new_path.is_none() {
+        return Err(error::Parse::MissingPath(start));
+    }
+    Ok(file)
+}
+
+/// Parses the extended headers of `git diff`, up to the first line that is
+/// not one.
+fn extended_headers(lines: &mut Lines, file: &mut File) -> Result<(), error::Parse> {
+    while let Some(line) = lines.peek() {
+        let n = lines.number() + 1;
+        let line = trim(line);
+        if let Some(mode) = line.strip_prefix(b"old mode ") {
+            file.old_mode = Some(file_mode(mode, n)?);
+        } else if let Some(mode) = line.strip_prefix(b"new mode ") {
+            file.new_mode = Some(file_mode(mode, n)?);
+        } else if let Some(mode) = line.strip_prefix(b"deleted file mode ") {
+            file.old_mode = Some(file_mode(mode, n)?);
+            file.deleted = true;
+        } else if let Some(mode) = line.strip_prefix(b"new file mode ") {
+            file.new_mode = Some(file_mode(mode, n)?);
+            file.added = true;
+        } else if let Some(index) = line.strip_prefix(b"index ") {
+            let (oids, mode) = match index.iter().position(|b| *b == b' ') {
+                Some(i) => (&index[..i], Some(&index[i + 1..])),
+                None => (index, None),
+            };
+            let Some(dots) = oids.windows(2).position(|w| w == b"..") else {
+                return Err(error::Parse::Oid {
+                    line: n,
+                    oid: String::from_utf8_lossy(oids).into_owned(),
+                });
+            };
+            file.old_oid = Some(oid(&oids[..dots], n)?);
+            file.new_oid = Some(oid(&oids[dots + 2..], n)?);
+            if let Some(mode) = mode {
+                let mode = file_mode(mode, n)?;
+                file.old_mode.get_or_insert(mode.clone());
+                file.new_mode.get_or_insert(mode);
+            }
+        } else if let Some(p) = line
+            .strip_prefix(b"rename from ")
+            .or_else(|| line.strip_prefix(b"copy from "))
+        {
+            file.old_path = Some(path(&unquote(p), n)?);
+        } else if let Some(p) = line.strip_prefix(b"rename to ") {
+            file.new_path = Some(path(&unquote(p), n)?);
+            file.renamed = true;
+        } else if let Some(p) = line.strip_prefix(b"copy to ") {
+            file.new_path = Some(path(&unquote(p), n)?);
+            file.copied = true;
+        } else if let Some(score) = line.strip_prefix(b"similarity index ") {
+            file.similarity = Some(similarity(score, n)?);
+        } else if !line.starts_with(b"dissimilarity index ") {
+            break;
+        }
+        lines.next();
+    }
+    Ok(())
+}
+
+/// Sets `path` from a `---` or `+++` line, or `dev_null` if the file does
+/// not exist on that side.
+fn side_path(
+    line: &[u8],
+    n: usize,
+    path: &mut Option<PathBuf>,
+    dev_null: &mut bool,
+) -> Result<(), error::Parse> {
+    let mut p = trim(&line[4..]);
+    // `diff -u` appends a timestamp, separated by a tab.
+    if !p.starts_with(b"\"") {
+        if let Some(i) = p.iter().position(|b| *b == b'\t') {
+            p = &p[..i];
+        }
+    }
+    let p = unquote(p);
+    if p == b"/dev/null" {
+        *dev_null = true;
+    } else {
+        *path = Some(self::path(strip_prefix(&p), n)?);
+    }
+    Ok(())
+}
+
+/// Parses the hunks of a file, up to the first line that is not part of
+/// one.
+fn hunks(lines: &mut Lines) -> Result<DiffContent, error::Parse> {
+    let mut hunks = Vec::new();
+    let mut stats = FileStats::default();
+    let (mut old_missing, mut new_missing) = (false, false);
+
+    while let Some(header) = lines.peek().filter(|l| l.starts_with(b"@@ ")) {
+        lines.next();
+        let (old, new) = hunk_header(header).ok_or(error::Parse::HunkHeader(lines.number()))?;
+        let (mut old_no, mut new_no) = (old.start, new.start);
+        let (mut old_left, mut new_left) = (old.end - old.start, new.end - new.start);
+        let mut modifications: Vec<Modification> = Vec::new();
+
+        while old_left > 0 || new_left > 0 {
+            let line = lines
+                .next()
+                .ok_or(error::Parse::HunkTruncated(lines.number()))?;
+            let content = line.get(1..).unwrap_or_default().to_vec();
+            match line.first() {
+                // Some tools strip the trailing space of empty context lines.
+                Some(b' ') | Some(b'\n') | Some(b'\r') if old_left > 0 && new_left > 0 => {
+                    let content = if line[0] == b' ' {
+                        content
+                    } else {
+                        line.to_vec()
+                    };
+                    modifications.push(Modification::context(content, old_no, new_no));
+                    (old_no, new_no) = (old_no + 1, new_no + 1);
+                    (old_left, new_left) = (old_left - 1, new_left - 1);
+                }
+                Some(b'-') if old_left > 0 => {
+                    modifications.push(Modification::deletion(content, old_no));
+                    stats.deletions += 1;
+                    old_no += 1;
+                    old_left -= 1;
+                }
+                Some(b'+') if new_left > 0 => {
+                    modifications.push(Modification::addition(content, new_no));
+                    stats.additions += 1;
+                    new_no += 1;
+                    new_left -= 1;
+                }
+                Some(b'\\') => no_newline(&mut modifications, &mut old_missing, &mut new_missing),
+                _ => return Err(error::Parse::HunkLine(lines.number())),
+            }
+        }
+        if lines.peek().is_some_and(|l| l.starts_with(b"\\")) {
+            lines.next();
+            no_newline(&mut modifications, &mut old_missing, &mut new_missing);
+        }
+
+        hunks.push(Hunk {
+            header: Line(header.to_vec()),
+            lines: modifications,
+            old,
+            new,
+        });
+    }
+
+    let eof = match (old_missing, new_missing) {
+        (true, true) => EofNewLine::BothMissing,
+        (true, false) => EofNewLine::OldMissing,
+        (false, true) => EofNewLine::NewMissing,
+        (false, false) => EofNewLine::NoneMissing,
+    };
+    Ok(DiffContent::Plain {
+        hunks: Hunks(hunks),
+        stats,
+        eof,
+    })
+}
+
+/// Applies a `\ No newline at end of file` marker to the last line.
+fn no_newline(modifications: &mut [Modification], old: &mut bool, new: &mut bool) {
+    let Some(last) = modifications.last_mut() else {
+        return;
+    };
+    let line = match last {
+        Modification::Addition(a) => {
+            *new = true;
+            &mut a.line
+        }
+        Modification::Deletion(d) => {
+            *old = true;
+            &mut d.line
+        }
+        Modification::Context { line, .. } => {
+            (*old, *new) = (true, true);
+            line
+        }
+    };
+    if line.0.ends_with(b"\n") {
+        line.0.pop();
+    }
+}
+
+/// Parses `@@ -a,b +c,d @@`, returning the old and new line ranges.
+fn hunk_header(line: &[u8]) -> Option<(std::ops::Range<u32>, std::ops::Range<u32>)> {
+    let line = std::str::from_utf8(line).ok()?;
+    let mut parts = line.strip_prefix("@@ -")?.splitn(2, " @@");
+    let (old, new) = parts.next()?.split_once(" +")?;
+    parts.next()?;
+
+    let range = |s: &str| -> Option<std::ops::Range<u32>> {
+        let (start, len) = match s.split_once(',') {
+            Some((start, len)) => (start.parse::<u32>().ok()?, len.parse::<u32>().ok()?),
+            None => (s.parse().ok()?, 1),
+        };
+        Some(start..start.checked_add(len)?)
+    };
+    Some((range(old)?, range(new)?))
+}
+
+/// Splits the paths of a `diff --git` line, without their `a/` and `b/`
+/// prefixes.
+///
+/// Unquoted paths may contain spaces, so they are only split if both are
+/// the same, which is always the case unless the file was renamed or
+/// copied, where the paths are given by the extended headers instead.
+fn git_paths(line: &[u8]) -> Option<(Vec<u8>, Vec<u8>)> {
+    if line.starts_with(b"\"") {
+        let end = quoted_len(line)?;
+        let old = unquote(&line[..end]);
+        let new = unquote(line.get(end + 1..)?);
+        return Some((strip_prefix(&old).to_vec(), strip_prefix(&new).to_vec()));
+    }
+    if line.ends_with(b"\"") {
+        let i = line.windows(2).rposition(|w| w == b" \"")?;
+        let new = unquote(&line[i + 1..]);
+        return Some((
+            strip_prefix(&line[..i]).to_vec(),
+            strip_prefix(&new).to_vec(),
+        ));
+    }
+
+    (0..line.len())
+        .filter(|i| line[*i] == b' ')
+        .map(|i| (strip_prefix(&line[..i]), strip_prefix(&line[i + 1..])))
+        .find(|(old, new)| old == new)
+        .map(|(old, new)| (old.to_vec(), new.to_vec()))
+}
+
+/// The length of the quoted string at the start of `s`, including quotes.
+fn quoted_len(s: &[u8]) -> Option<usize> {
+    let mut escaped = false;
+    for (i, b) in s.iter().enumerate().skip(1) {
+        match b {
+            _ if escaped => escaped = false,
+            b'\\' => escaped = true,
+            b'"' => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Reverses [`super::patch::quote`], returning `s` as is if it is not
+/// quoted.
+fn unquote(s: &[u8]) -> Vec<u8> {
+    let Some(inner) = s.strip_prefix(b"\"").and_then(|s| s.strip_suffix(b"\"")) else {
+        return s.to_vec();
+    };
+
+    let mut unquoted = Vec::with_capacity(inner.len());
+    let mut bytes = inner.iter().copied();
+    while let Some(b) = bytes.next() {
+        if b != b'\\' {
+            unquoted.push(b);
+            continue;
+        }
+        match bytes.next() {
+            Some(b'a') => unquoted.push(0x07),
+            Some(b'b') => unquoted.push(0x08),
+            Some(b'f') => unquoted.push(0x0c),
+            Some(b'n') => unquoted.push(b'\n'),
+            Some(b'r') => unquoted.push(b'\r'),
+            Some(b't') => unquoted.push(b'\t'),
+            Some(b'v') => unquoted.push(0x0b),
+            Some(d @ b'0'..=b'7') => {
+                let mut value = u32::from(d - b'0');
+                for _ in 0..2 {
+                    match bytes.clone().next() {
+                        Some(d @ b'0'..=b'7') => {
+                            value = value * 8 + u32::from(d - b'0');
+                            bytes.next();
+                        }
+                        _ => break,
+                    }
+                }
+                unquoted.push(value as u8);
+            }
+            Some(b) => unquoted.push(b),
+            None => unquoted.push(b'\\'),
+        }
+    }
+    unquoted
+}
+
+/// Strips the `a/` or `b/` prefix of a path.
+fn strip_prefix(path: &[u8]) -> &[u8] {
+    path.strip_prefix(b"a/")
+        .or_else(|| path.strip_prefix(b"b/"))
+        .unwrap_or(path)
+}
+
+/// Strips the line ending of `line`.
+fn trim(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+fn path(bytes: &[u8], line: usize) -> Result<PathBuf, error::Parse> {
+    match std::str::from_utf8(bytes) {
+        Ok(path) if !path.is_empty() => Ok(PathBuf::from(path)),
+        _ => Err(error::Parse::Path(line)),
+    }
+}
+
+fn oid(hex: &[u8], line: usize) -> Result<Oid, error::Parse> {
+    std::str::from_utf8(hex)
+        .ok()
+        .and_then(|hex| git2::Oid::from_str(hex).ok())
+        .map(Oid::from)
+        .ok_or_else(|| error::Parse::Oid {
+            line,
+            oid: String::from_utf8_lossy(hex).into_owned(),
+        })
+}
+
+/// Parses a percentage, such as `87%`.
+fn similarity(score: &[u8], line: usize) -> Result<u16, error::Parse> {
+    std::str::from_utf8(score)
+        .ok()
+        .and_then(|score| score.strip_suffix('%'))
+        .and_then(|score| score.parse().ok())
+        .filter(|score| *score <= 100)
+        .ok_or(error::Parse::Similarity(line))
+}
+
+fn file_mode(mode: &[u8], line: usize) -> Result<FileMode, error::Parse> {
+    let invalid = || error::Parse::FileMode {
+        line,
+        mode: String::from_utf8_lossy(mode).into_owned(),
+    };
+    let mode = std::str::from_utf8(mode).map_err(|_| invalid())?;
+    match u32::from_str_radix(mode, 8).map_err(|_| invalid())? {
+        0o100644 | 0o100664 => Ok(FileMode::Blob),
+        0o100755 => Ok(FileMode::BlobExecutable),
+        0o040000 => Ok(FileMode::Tree),
+        0o120000 => Ok(FileMode::Link),
+        0o160000 => Ok(FileMode::Commit),
+        _ => Err(invalid()),
+    }
+}
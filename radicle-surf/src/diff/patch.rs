@@ -0,0 +1,264 @@
+//! Rendering of a [`Diff`] as a unified patch, in the format of
+//! `git diff --full-index`.
+
+use std::{fmt, io, path::Path};
+
+use git_ext::Oid;
+
+use super::{
+    Added, Copied, Deleted, Diff, DiffContent, DiffFile, EofNewLine, FileDiff, Hunk, Modification,
+    Modified, Moved,
+};
+
+const NO_NEWLINE: &[u8] = b"\\ No newline at end of file\n";
+
+impl Diff {
+    /// Writes this diff to `w` as a unified patch, which can be applied
+    /// using `git apply`.
+    ///
+    /// A `Diff` does not carry the content of binary files, so binary
+    /// changes are written as `Binary files ... differ`. Like the output of
+    /// `git diff --full-index`, these only apply in a repository that has
+    /// the new blobs.
+    pub fn write_patch<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        for file in self.files() {
+            file.write_patch(w)?;
+        }
+        Ok(())
+    }
+}
+
+impl FileDiff {
+    /// Writes this file diff to `w` as a unified patch, see
+    /// [`Diff::write_patch`].
+    ///
+    /// A file whose content is [`DiffContent::TooLarge`] is written without
+    /// any hunks, so its changes are missing from the patch.
+    pub fn write_patch<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            FileDiff::Added(Added { path, diff, new }) => {
+                header(w, path, path)?;
+                writeln!(w, "new file mode {}", mode(new))?;
+                writeln!(w, "index {}..{}", null(&new.oid), new.oid)?;
+                content(w, None, Some(path), diff)
+            }
+            FileDiff::Deleted(Deleted { path, diff, old }) => {
+                header(w, path, path)?;
+                writeln!(w, "deleted file mode {}", mode(old))?;
+                writeln!(w, "index {}..{}", old.oid, null(&old.oid))?;
+                content(w, Some(path), None, diff)
+            }
+            FileDiff::Modified(Modified {
+                path,
+                diff,
+                old,
+                new,
+            }) => {
+                header(w, path, path)?;
+                index(w, old, new)?;
+                content(w, Some(path), Some(path), diff)
+            }
+            FileDiff::Moved(Moved {
+                old_path,
+                old,
+                new_path,
+                new,
+                similarity,
+                diff,
+            }) => {
+                header(w, old_path, new_path)?;
+                writeln!(w, "similarity index {similarity}%")?;
+                w.write_all(b"rename from ")?;
+                w.write_all(&quote(old_path))?;
+                w.write_all(b"\nrename to ")?;
+                w.write_all(&quote(new_path))?;
+                w.write_all(b"\n")?;
+                index(w, old, new)?;
+                content(w, Some(old_path), Some(new_path), diff)
+            }
+            FileDiff::Copied(Copied {
+                old_path,
+                new_path,
+                old,
+                new,
+                similarity,
+                diff,
+            }) => {
+                header(w, old_path, new_path)?;
+                writeln!(w, "similarity index {similarity}%")?;
+                w.write_all(b"copy from ")?;
+                w.write_all(&quote(old_path))?;
+                w.write_all(b"\ncopy to ")?;
+                w.write_all(&quote(new_path))?;
+                w.write_all(b"\n")?;
+                index(w, old, new)?;
+                content(w, Some(old_path), Some(new_path), diff)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = Vec::new();
+        self.write_patch(&mut buf).map_err(|_| fmt::Error)?;
+        f.write_str(&String::from_utf8_lossy(&buf))
+    }
+}
+
+impl fmt::Display for FileDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = Vec::new();
+        self.write_patch(&mut buf).map_err(|_| fmt::Error)?;
+        f.write_str(&String::from_utf8_lossy(&buf))
+    }
+}
+
+/// Writes the `diff --git` line.
+fn header<W: io::Write>(w: &mut W, old: &Path, new: &Path) -> io::Result<()> {
+    w.write_all(b"diff --git ")?;
+    w.write_all(&quote(&Path::new("a").join(old)))?;
+    w.write_all(b" ")?;
+    w.write_all(&quote(&Path::new("b").join(new)))?;
+    w.write_all(b"\n")
+}
+
+/// Writes the mode changes and `index` line of a file that exists on both
+/// sides, if they changed.
+fn index<W: io::Write>(w: &mut W, old: &DiffFile, new: &DiffFile) -> io::Result<()> {
+    if old.mode != new.mode {
+        writeln!(w, "old mode {}", mode(old))?;
+        writeln!(w, "new mode {}", mode(new))?;
+    }
+    if old.oid != new.oid {
+        write!(w, "index {}..{}", old.oid, new.oid)?;
+        if old.mode == new.mode {
+            write!(w, " {}", mode(new))?;
+        }
+        writeln!(w)?;
+    }
+    Ok(())
+}
+
+/// Writes the `---`/`+++` lines and the hunks of `diff`, where a missing
+/// path stands for `/dev/null`.
+fn content<W: io::Write>(
+    w: &mut W,
+    old: Option<&Path>,
+    new: Option<&Path>,
+    diff: &DiffContent,
+) -> io::Result<()> {
+    let side = |prefix: &str, path: Option<&Path>| match path {
+        Some(path) => quote(&Path::new(prefix).join(path)),
+        None => b"/dev/null".to_vec(),
+    };
+    match diff {
+        DiffContent::Empty | DiffContent::TooLarge => Ok(()),
+        DiffContent::Binary => {
+            w.write_all(b"Binary files ")?;
+            w.write_all(&side("a", old))?;
+            w.write_all(b" and ")?;
+            w.write_all(&side("b", new))?;
+            w.write_all(b" differ\n")
+        }
+        DiffContent::Plain { hunks, eof, .. } => {
+            if hunks.0.is_empty() {
+                return Ok(());
+            }
+            w.write_all(b"--- ")?;
+            w.write_all(&side("a", old))?;
+            w.write_all(b"\n+++ ")?;
+            w.write_all(&side("b", new))?;
+            w.write_all(b"\n")?;
+
+            let last = hunks.0.len() - 1;
+            for (i, hunk) in hunks.iter().enumerate() {
+                let eof = if i == last {
+                    eof.clone()
+                } else {
+                    EofNewLine::NoneMissing
+                };
+                write_hunk(w, hunk, eof)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn write_hunk<W: io::Write>(
+    w: &mut W,
+    hunk: &Hunk<Modification>,
+    eof: EofNewLine,
+) -> io::Result<()> {
+    w.write_all(hunk.header.as_bytes())?;
+    if !hunk.header.as_bytes().ends_with(b"\n") {
+        w.write_all(b"\n")?;
+    }
+
+    // The last line of either side is missing its newline, if the
+    // respective side is missing it at the end of the file.
+    let last_old = hunk
+        .lines
+        .iter()
+        .rposition(|l| !matches!(l, Modification::Addition(_)));
+    let last_new = hunk
+        .lines
+        .iter()
+        .rposition(|l| !matches!(l, Modification::Deletion(_)));
+    let (old_missing, new_missing) = match eof {
+        EofNewLine::OldMissing => (true, false),
+        EofNewLine::NewMissing => (false, true),
+        EofNewLine::BothMissing => (true, true),
+        EofNewLine::NoneMissing => (false, false),
+    };
+
+    for (i, line) in hunk.lines.iter().enumerate() {
+        let (origin, content) = match line {
+            Modification::Addition(a) => (b'+', a.line.as_bytes()),
+            Modification::Deletion(d) => (b'-', d.line.as_bytes()),
+            Modification::Context { line, .. } => (b' ', line.as_bytes()),
+        };
+        let missing = (old_missing && Some(i) == last_old) || (new_missing && Some(i) == last_new);
+        let content = content.strip_suffix(b"\n").unwrap_or(content);
+
+        w.write_all(&[origin])?;
+        w.write_all(content)?;
+        w.write_all(b"\n")?;
+        if missing {
+            w.write_all(NO_NEWLINE)?;
+        }
+    }
+    Ok(())
+}
+
+fn mode(file: &DiffFile) -> String {
+    format!("{:06o}", u32::from(file.mode.clone()))
+}
+
+/// The null object id, of the same length as `oid`.
+fn null(oid: &Oid) -> String {
+    "0".repeat(oid.to_string().len())
+}
+
+/// Quotes `path` the way git does, if it contains any special characters.
+pub(super) fn quote(path: &Path) -> Vec<u8> {
+    let bytes = path.as_os_str().as_encoded_bytes();
+    let special = |b: u8| b == b'"' || b == b'\\' || !(0x20..0x7f).contains(&b);
+    if !bytes.iter().copied().any(special) {
+        return bytes.to_vec();
+    }
+
+    let mut quoted = vec![b'"'];
+    for &b in bytes {
+        match b {
+            b'"' => quoted.extend_from_slice(b"\\\""),
+            b'\\' => quoted.extend_from_slice(b"\\\\"),
+            b'\t' => quoted.extend_from_slice(b"\\t"),
+            b'\n' => quoted.extend_from_slice(b"\\n"),
+            b if special(b) => quoted.extend_from_slice(format!("\\{b:03o}").as_bytes()),
+            b => quoted.push(b),
+        }
+    }
+    quoted.push(b'"');
+    quoted
+}
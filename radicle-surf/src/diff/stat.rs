@@ -0,0 +1,261 @@
+//! Summaries of a [`Diff`], i.e. `git diff --stat` and
+//! `git diff --dirstat`.
+
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use super::{patch::quote, Diff, DiffContent, FileDiff};
+
+/// The smallest width of the graph of [`Diff::write_stat`].
+const MIN_GRAPH_WIDTH: usize = 6;
+
+/// The number of lines changed within a directory of a [`Diff`].
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DirStat {
+    /// The path to this directory, relative to the repository root.
+    pub path: PathBuf,
+    /// The number of lines added and deleted within this directory,
+    /// including its sub-directories.
+    pub lines: usize,
+    /// The share of `lines` in all the lines changed by the diff, in
+    /// percent.
+    pub percent: f32,
+}
+
+impl Diff {
+    /// Returns the number of lines changed within each directory, ordered
+    /// by path, i.e. `git diff --dirstat=lines,cumulative,0`.
+    ///
+    /// The lines of a directory include those of its sub-directories, and
+    /// the lines of files at the root of the repository only count towards
+    /// the total. Binary and too large files are not counted.
+    pub fn dirstat(&self) -> Vec<DirStat> {
+        let mut dirs = BTreeMap::<&Path, usize>::new();
+        let mut total = 0;
+        for file in self.files() {
+            let lines = changes(file).map_or(0, |(added, deleted)| added + deleted);
+            if lines == 0 {
+                continue;
+            }
+            total += lines;
+            for dir in file.path().ancestors().skip(1) {
+                if dir.as_os_str().is_empty() {
+                    break;
+                }
+                *dirs.entry(dir).or_default() += lines;
+            }
+        }
+
+        dirs.into_iter()
+            .map(|(path, lines)| DirStat {
+                path: path.to_path_buf(),
+                lines,
+                percent: lines as f32 * 100.0 / total as f32,
+            })
+            .collect()
+    }
+
+    /// Writes a summary of the changed lines of each file to `w`, with a
+    /// graph of added and deleted lines, i.e. `git diff --stat=<width>`.
+    ///
+    /// Long paths are shortened and the graph is scaled down so that each
+    /// line fits into `width` columns, as far as possible.
+    ///
+    /// Binary files are shown as `Bin` and too large files as `...`.
+    pub fn write_stat<W: io::Write>(&self, w: &mut W, width: usize) -> io::Result<()> {
+        let files = self
+            .files()
+            .map(|file| (name(file), content(file), changes(file)))
+            .collect::<Vec<_>>();
+        if files.is_empty() {
+            return Ok(());
+        }
+
+        let max_len = files
+            .iter()
+            .map(|(name, _, _)| name.len())
+            .max()
+            .unwrap_or(0);
+        let max_change = files
+            .iter()
+            .filter_map(|(_, _, changes)| changes.map(|(a, d)| a + d))
+            .max()
+            .unwrap_or(0);
+        let has_binary = files.iter().any(|(_, _, changes)| changes.is_none());
+        let number_width = if has_binary { 3 } else { 0 }.max(decimal_width(max_change));
+
+        // The widths are assigned the same way as by git.
+        let width = width.max(16 + 6 + number_width);
+        let mut graph_width = max_change;
+        let mut name_width = max_len;
+        if name_width + number_width + 6 + graph_width > width {
+            let limit = (width * 3 / 8).saturating_sub(number_width + 6);
+            if graph_width > limit {
+                graph_width = limit.max(MIN_GRAPH_WIDTH);
+            }
+            let available = width.saturating_sub(number_width + 6 + graph_width);
+            if name_width > available {
+                name_width = available;
+            } else {
+                graph_width = width - number_width - 6 - name_width;
+            }
+        }
+
+        let (mut insertions, mut deletions) = (0, 0);
+        for (name, content, changes) in &files {
+            let name = truncate(name, name_width);
+            let padding = name_width.saturating_sub(name.len());
+            w.write_all(b" ")?;
+            w.write_all(&name)?;
+            write!(w, "{:padding$} |", "")?;
+            match changes {
+                None if matches!(content, DiffContent::TooLarge) => {
+                    writeln!(w, " {:>number_width$}", "...")?
+                }
+                None => writeln!(w, " {:>number_width$}", "Bin")?,
+                Some((added, deleted)) => {
+                    insertions += added;
+                    deletions += deleted;
+                    let total = added + deleted;
+                    let (mut plus, mut minus) = (*added, *deleted);
+                    if graph_width <= max_change {
+                        let mut total = scale(total, graph_width, max_change);
+                        if total < 2 && plus > 0 && minus > 0 {
+                            total = 2;
+                        }
+                        if plus < minus {
+                            plus = scale(plus, graph_width, max_change);
+                            minus = total - plus;
+                        } else {
+                            minus = scale(minus, graph_width, max_change);
+                            plus = total - minus;
+                        }
+                    }
+                    write!(w, " {total:>number_width$}")?;
+                    if total > 0 {
+                        write!(w, " {}{}", "+".repeat(plus), "-".repeat(minus))?;
+                    }
+                    writeln!(w)?;
+                }
+            }
+        }
+
+        let plural = |n: usize| if n == 1 { "" } else { "s" };
+        write!(w, " {} file{} changed", files.len(), plural(files.len()))?;
+        if insertions > 0 || deletions == 0 {
+            write!(w, ", {insertions} insertion{}(+)", plural(insertions))?;
+        }
+        if deletions > 0 || insertions == 0 {
+            write!(w, ", {deletions} deletion{}(-)", plural(deletions))?;
+        }
+        writeln!(w)
+    }
+}
+
+/// The changes of a file.
+fn content(file: &FileDiff) -> &DiffContent {
+    match file {
+        FileDiff::Added(x) => &x.diff,
+        FileDiff::Deleted(x) => &x.diff,
+        FileDiff::Modified(x) => &x.diff,
+        FileDiff::Moved(x) => &x.diff,
+        FileDiff::Copied(x) => &x.diff,
+    }
+}
+
+/// The added and deleted lines of a file, or `None` if it is binary or too
+/// large.
+fn changes(file: &FileDiff) -> Option<(usize, usize)> {
+    match content(file) {
+        DiffContent::Binary | DiffContent::TooLarge => None,
+        DiffContent::Empty => Some((0, 0)),
+        DiffContent::Plain { stats, .. } => Some((stats.additions, stats.deletions)),
+    }
+}
+
+/// The name of a file in the summary, where renames and copies are shown
+/// as `old => new`, sharing their common leading and trailing directories,
+/// e.g. `src/{old => new}/lib.rs`.
+fn name(file: &FileDiff) -> Vec<u8> {
+    let (old, new) = match file {
+        FileDiff::Moved(x) => (&x.old_path, &x.new_path),
+        FileDiff::Copied(x) => (&x.old_path, &x.new_path),
+        _ => return quote(file.path()),
+    };
+    let old = quote(old);
+    let new = quote(new);
+
+    // The common prefix ends with, and the common suffix starts with, a
+    // slash. Like git, the suffix may start with the last slash of the
+    // prefix, e.g. `src/{x => }/lib.rs`.
+    let prefix = old
+        .iter()
+        .zip(&new)
+        .take_while(|(a, b)| a == b)
+        .enumerate()
+        .filter(|(_, (b, _))| **b == b'/')
+        .last()
+        .map_or(0, |(i, _)| i + 1);
+    let start = prefix.saturating_sub(1);
+    let suffix = old[start..]
+        .iter()
+        .rev()
+        .zip(new[start..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .enumerate()
+        .filter(|(_, (b, _))| **b == b'/')
+        .last()
+        .map_or(0, |(i, _)| i + 1);
+
+    let mut name = Vec::new();
+    if prefix + suffix > 0 {
+        name.extend_from_slice(&old[..prefix]);
+        name.push(b'{');
+        name.extend_from_slice(&old[prefix..(old.len() - suffix).max(prefix)]);
+        name.extend_from_slice(b" => ");
+        name.extend_from_slice(&new[prefix..(new.len() - suffix).max(prefix)]);
+        name.push(b'}');
+        name.extend_from_slice(&old[old.len() - suffix..]);
+    } else {
+        name.extend_from_slice(&old);
+        name.extend_from_slice(b" => ");
+        name.extend_from_slice(&new);
+    }
+    name
+}
+
+/// Shortens `name` to `width` by replacing its start with `...`, preferably
+/// at a directory boundary.
+fn truncate(name: &[u8], width: usize) -> Vec<u8> {
+    if name.len() <= width {
+        return name.to_vec();
+    }
+    let mut tail = &name[name.len() - width.saturating_sub(3)..];
+    if let Some(slash) = tail.iter().position(|b| *b == b'/') {
+        tail = &tail[slash..];
+    }
+    let mut truncated = b"...".to_vec();
+    truncated.extend_from_slice(tail);
+    truncated
+}
+
+/// Scales `it` from the range up to `max` down to the range up to `width`,
+/// keeping any non-zero value non-zero.
+fn scale(it: usize, width: usize, max: usize) -> usize {
+    if it == 0 {
+        0
+    } else {
+        1 + it * width.saturating_sub(1) / max
+    }
+}
+
+fn decimal_width(n: usize) -> usize {
+    n.to_string().len()
+}
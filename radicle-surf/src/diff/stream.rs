@@ -0,0 +1,108 @@
+//! Streaming of the files of a diff, where the changes of each file are
+//! only loaded once the file is reached.
+//!
+//! A [`DiffStream`] can be retrieved by the following functions:
+//!    * [`crate::Repository::diff_stream`]
+//!    * [`crate::Repository::diff_commit_stream`]
+
+use std::path::Path;
+
+use super::{
+    git::{self, error},
+    DiffContent, FileDiff,
+};
+use crate::Error;
+
+/// An iterator over the [`FileDiff`]s of a diff.
+///
+/// Unlike a [`super::Diff`], which holds the changes of all its files at
+/// once, the changes of a file are loaded when the file is yielded and
+/// dropped along with it. Files exceeding the limits set by
+/// [`DiffStream::max_bytes`] and [`DiffStream::max_lines`] are yielded
+/// with [`DiffContent::TooLarge`] instead of their changes.
+pub struct DiffStream<'a> {
+    diff: git2::Diff<'a>,
+    odb: git2::Odb<'a>,
+    next: usize,
+    max_bytes: Option<usize>,
+    max_lines: Option<usize>,
+}
+
+impl<'a> DiffStream<'a> {
+    pub(crate) fn new(diff: git2::Diff<'a>, odb: git2::Odb<'a>) -> Self {
+        Self {
+            diff,
+            odb,
+            next: 0,
+            max_bytes: None,
+            max_lines: None,
+        }
+    }
+
+    /// Skips loading the changes of files where either side is larger than
+    /// `bytes`.
+    ///
+    /// The sizes are read from the object headers, so the content of such
+    /// files is never loaded.
+    pub fn max_bytes(mut self, bytes: usize) -> Self {
+        self.max_bytes = Some(bytes);
+        self
+    }
+
+    /// Skips the changes of files whose hunks span more than `lines` lines,
+    /// including context lines.
+    pub fn max_lines(mut self, lines: usize) -> Self {
+        self.max_lines = Some(lines);
+        self
+    }
+
+    /// Loads the changes of the file at `idx`, unless it exceeds the limits.
+    fn load(
+        &self,
+        idx: usize,
+        delta: &git2::DiffDelta<'_>,
+        path: &Path,
+    ) -> Result<DiffContent, error::Diff> {
+        if let Some(max) = self.max_bytes {
+            for file in [delta.old_file(), delta.new_file()] {
+                if file.id().is_zero() || file.mode() == git2::FileMode::Commit {
+                    continue;
+                }
+                let (size, _) = self.odb.read_header(file.id())?;
+                if size > max {
+                    return Ok(DiffContent::TooLarge);
+                }
+            }
+        }
+
+        let patch = git2::Patch::from_diff(&self.diff, idx)?;
+        let is_binary = patch
+            .as_ref()
+            .is_some_and(|patch| patch.delta().flags().is_binary());
+        if let (Some(max), Some(patch), false) = (self.max_lines, &patch, is_binary) {
+            let (context, additions, deletions) = patch.line_stats()?;
+            if context + additions + deletions > max {
+                return Ok(DiffContent::TooLarge);
+            }
+        }
+        git::content(delta, path, is_binary, patch)
+    }
+}
+
+impl Iterator for DiffStream<'_> {
+    type Item = Result<FileDiff, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.next;
+        let delta = self.diff.get_delta(idx)?;
+        self.next += 1;
+        Some(git::file_diff(&delta, |path| self.load(idx, &delta, path)).map_err(Error::from))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.diff.deltas().len() - self.next;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for DiffStream<'_> {}
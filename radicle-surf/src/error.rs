@@ -1,13 +1,17 @@
 //! Definition for a crate level error type, which wraps up module level
 //! error types transparently.
 
-use crate::{commit, diff, fs, glob, namespace, refs, repo};
+use crate::{archive, commit, diff, fs, glob, history, namespace, note, refs, repo, signature};
 use thiserror::Error;
 
 /// The crate level error type that wraps up module level error types.
 #[derive(Debug, Error)]
 #[non_exhaustive]
 pub enum Error {
+    #[error(transparent)]
+    Archive(#[from] archive::error::Archive),
+    #[error(transparent)]
+    AllowedSigners(#[from] signature::error::AllowedSigners),
     #[error(transparent)]
     Branches(#[from] refs::error::Branch),
     #[error(transparent)]
@@ -15,6 +19,8 @@ pub enum Error {
     #[error(transparent)]
     Commit(#[from] commit::Error),
     #[error(transparent)]
+    Cursor(#[from] history::error::Cursor),
+    #[error(transparent)]
     Diff(#[from] diff::git::error::Diff),
     #[error(transparent)]
     Directory(#[from] fs::error::Directory),
@@ -25,8 +31,18 @@ pub enum Error {
     #[error(transparent)]
     Glob(#[from] glob::Error),
     #[error(transparent)]
+    Keyring(#[from] signature::error::Keyring),
+    #[error(transparent)]
     Namespace(#[from] namespace::Error),
     #[error(transparent)]
+    Note(#[from] note::error::Note),
+    #[error(transparent)]
+    NoteRefs(#[from] refs::error::Note),
+    #[error(transparent)]
+    Patch(#[from] diff::parse::error::Parse),
+    #[error(transparent)]
+    Regex(#[from] regex::Error),
+    #[error(transparent)]
     RefFormat(#[from] git_ext::ref_format::Error),
     #[error(transparent)]
     Revision(Box<dyn std::error::Error + Send + Sync + 'static>),
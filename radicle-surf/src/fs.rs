@@ -5,7 +5,7 @@
 
 use std::{
     cmp::Ordering,
-    collections::BTreeMap,
+    collections::{BTreeMap, HashMap},
     convert::{Infallible, Into as _},
     path::{Path, PathBuf},
 };
@@ -13,9 +13,14 @@ use std::{
 use git2::Blob;
 use radicle_git_ext::{is_not_found_err, Oid};
 use radicle_std_ext::result::ResultExt as _;
+#[cfg(feature = "serde")]
+use serde::Serialize;
 use url::Url;
 
-use crate::{Repository, Revision};
+use crate::{
+    search::{self, PathMatch, SearchOptions},
+    Repository, Revision,
+};
 
 pub mod error {
     use std::path::PathBuf;
@@ -461,21 +466,56 @@ impl Directory {
         }
     }
 
-    // TODO(fintan): This is going to be a bit trickier so going to leave it out for
-    // now
-    #[allow(dead_code)]
-    fn fuzzy_find(_label: &Path) -> Vec<Self> {
-        unimplemented!()
+    /// Find the files under this `Directory` whose paths, relative to it,
+    /// fuzzily match `query`, best matches first.
+    ///
+    /// See [`crate::search`] for how matches are scored.
+    pub fn fuzzy_find(
+        &self,
+        query: &str,
+        repo: &Repository,
+    ) -> Result<Vec<PathMatch>, error::Directory> {
+        let tree = repo.find_tree(self.id)?;
+        Ok(search::paths(&tree, query, &SearchOptions::default())?)
     }
 
     /// Get the total size, in bytes, of a `Directory`. The size is
     /// the sum of all files that can be reached from this `Directory`.
+    ///
+    /// See [`Directory::disk_usage`].
     pub fn size(&self, repo: &Repository) -> Result<usize, error::Directory> {
-        self.traverse(repo, 0, &mut |size, entry| match entry {
-            Entry::File(file) => Ok(size + file.content(repo)?.size()),
-            Entry::Directory(dir) => Ok(size + dir.size(repo)?),
-            Entry::Submodule(_) => Ok(size),
-        })
+        Ok(self.disk_usage(repo)?.bytes)
+    }
+
+    /// Get the number of files that can be reached from this `Directory`.
+    ///
+    /// See [`Directory::disk_usage`].
+    pub fn file_count(&self, repo: &Repository) -> Result<usize, error::Directory> {
+        Ok(self.disk_usage(repo)?.files)
+    }
+
+    /// Get the total size and number of files that can be reached from
+    /// this `Directory`.
+    ///
+    /// The sizes are read from the object headers, so no file content is
+    /// loaded. Submodules are not counted.
+    pub fn disk_usage(&self, repo: &Repository) -> Result<DiskUsage, error::Directory> {
+        self.disk_usage_cached(repo, &mut DiskUsageCache::default())
+    }
+
+    /// Get the [`DiskUsage`] of this `Directory`, reusing and filling the
+    /// `cache`.
+    ///
+    /// Since the usage of a tree never changes, the same `cache` can be
+    /// used across revisions, where any unchanged sub-directories are
+    /// looked up rather than computed again.
+    pub fn disk_usage_cached(
+        &self,
+        repo: &Repository,
+        cache: &mut DiskUsageCache,
+    ) -> Result<DiskUsage, error::Directory> {
+        let odb = repo.odb()?;
+        Ok(disk_usage(repo, &odb, self.id, cache)?)
     }
 
     /// Traverse the entire `Directory` using the `initial`
@@ -510,6 +550,15 @@ impl Directory {
                 Entry::Submodule(_) => f(acc, entry),
             })
     }
+
+    /// Returns a [`Walk`] over all entries that can be reached from this
+    /// `Directory`, in pre-order by default.
+    ///
+    /// Unlike [`Directory::traverse`], the walk does not recurse, and the
+    /// entries of a sub-directory are only loaded once it is entered.
+    pub fn walk<'a>(&self, repo: &'a Repository) -> Walk<'a> {
+        Walk::new(self.clone(), repo)
+    }
 }
 
 impl Revision for Directory {
@@ -520,6 +569,265 @@ impl Revision for Directory {
     }
 }
 
+/// The total size and number of files within a [`Directory`].
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DiskUsage {
+    /// The sum of the sizes of all files, in bytes.
+    pub bytes: usize,
+    /// The number of files.
+    pub files: usize,
+}
+
+/// A cache of the [`DiskUsage`] of trees, keyed by their [`Oid`].
+///
+/// See [`Directory::disk_usage_cached`].
+#[derive(Clone, Debug, Default)]
+pub struct DiskUsageCache {
+    usage: HashMap<Oid, DiskUsage>,
+}
+
+impl DiskUsageCache {
+    /// Returns the cached usage of the tree `id`, if any.
+    pub fn get(&self, id: &Oid) -> Option<DiskUsage> {
+        self.usage.get(id).copied()
+    }
+
+    /// The number of cached trees.
+    pub fn len(&self) -> usize {
+        self.usage.len()
+    }
+
+    /// Whether no tree is cached.
+    pub fn is_empty(&self) -> bool {
+        self.usage.is_empty()
+    }
+
+    /// Removes all cached trees.
+    pub fn clear(&mut self) {
+        self.usage.clear()
+    }
+}
+
+/// Computes the [`DiskUsage`] of the tree `id`, using the object headers in
+/// `odb` for the sizes of blobs.
+fn disk_usage(
+    repo: &Repository,
+    odb: &git2::Odb,
+    id: Oid,
+    cache: &mut DiskUsageCache,
+) -> Result<DiskUsage, git2::Error> {
+    if let Some(usage) = cache.get(&id) {
+        return Ok(usage);
+    }
+    let mut usage = DiskUsage::default();
+    for entry in repo.find_tree(id)?.iter() {
+        match entry.kind() {
+            Some(git2::ObjectType::Blob) => {
+                let (size, _) = odb.read_header(entry.id())?;
+                usage.bytes += size;
+                usage.files += 1;
+            }
+            Some(git2::ObjectType::Tree) => {
+                let sub = disk_usage(repo, odb, entry.id().into(), cache)?;
+                usage.bytes += sub.bytes;
+                usage.files += sub.files;
+            }
+            _ => {}
+        }
+    }
+    cache.usage.insert(id, usage);
+    Ok(usage)
+}
+
+/// The order in which a [`Walk`] yields directories.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WalkOrder {
+    /// A directory is yielded before its entries.
+    #[default]
+    PreOrder,
+    /// A directory is yielded after its entries.
+    PostOrder,
+}
+
+/// An iterator over the [`Entry`]s reachable from a [`Directory`], which
+/// descends into sub-directories using an explicit stack.
+///
+/// The entries of each directory are yielded in the order of
+/// [`Directory::entries`], and their paths are relative to the repository
+/// root.
+///
+/// To retrieve a `Walk` use [`Directory::walk`].
+pub struct Walk<'a> {
+    repo: &'a Repository,
+    stack: Vec<Frame>,
+    order: WalkOrder,
+    max_depth: Option<usize>,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    pathspecs: Option<(Option<git2::Pathspec>, Option<git2::Pathspec>)>,
+    /// Whether the last yielded entry is a directory that is yet to be
+    /// entered.
+    entering: bool,
+}
+
+/// A directory on the stack of a [`Walk`].
+struct Frame {
+    /// The entry of the directory, which is `None` for the starting
+    /// directory.
+    entry: Option<Entry>,
+    directory: Directory,
+    /// The entries left to walk, loaded once the directory is entered.
+    entries: Option<std::vec::IntoIter<Entry>>,
+    /// The depth of the directory, where the starting directory is at `0`.
+    depth: usize,
+}
+
+impl<'a> Walk<'a> {
+    fn new(directory: Directory, repo: &'a Repository) -> Self {
+        Self {
+            repo,
+            stack: vec![Frame {
+                entry: None,
+                directory,
+                entries: None,
+                depth: 0,
+            }],
+            order: WalkOrder::default(),
+            max_depth: None,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            pathspecs: None,
+            entering: false,
+        }
+    }
+
+    /// Sets whether directories are yielded before or after their entries.
+    pub fn order(mut self, order: WalkOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Only descends `depth` levels below the starting directory, where the
+    /// entries of the starting directory are at depth `1`.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Only yields entries whose paths match the `glob`, using the
+    /// pathspec rules of git. Entries matching any of the included globs are
+    /// yielded.
+    ///
+    /// Directories that do not match are still descended into.
+    pub fn include(mut self, glob: impl Into<String>) -> Self {
+        self.include.push(glob.into());
+        self
+    }
+
+    /// Skips entries whose paths match the `glob`, using the pathspec rules
+    /// of git, along with anything below them.
+    pub fn exclude(mut self, glob: impl Into<String>) -> Self {
+        self.exclude.push(glob.into());
+        self
+    }
+
+    /// Skips the entries of the directory that was yielded last, so that
+    /// they are never loaded.
+    ///
+    /// This has no effect if the last entry was not a directory or if the
+    /// walk is in [`WalkOrder::PostOrder`].
+    pub fn skip_subtree(&mut self) {
+        if self.entering {
+            self.stack.pop();
+            self.entering = false;
+        }
+    }
+
+    /// Whether `entry` matches the included and excluded globs, compiling
+    /// them on first use.
+    fn matches(&mut self, entry: &Entry) -> Result<(bool, bool), error::Directory> {
+        let pathspec = |globs: &[String]| {
+            (!globs.is_empty())
+                .then(|| git2::Pathspec::new(globs))
+                .transpose()
+        };
+        let (include, exclude) = match &self.pathspecs {
+            Some(pathspecs) => pathspecs,
+            None => self
+                .pathspecs
+                .insert((pathspec(&self.include)?, pathspec(&self.exclude)?)),
+        };
+        let path = entry.path();
+        let matches =
+            |spec: &git2::Pathspec| spec.matches_path(&path, git2::PathspecFlags::DEFAULT);
+        let excluded = exclude.as_ref().is_some_and(matches);
+        let included = include.as_ref().map_or(true, matches);
+        Ok((included, excluded))
+    }
+}
+
+impl Iterator for Walk<'_> {
+    type Item = Result<Entry, error::Directory>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entering = false;
+        loop {
+            let frame = self.stack.last_mut()?;
+            let entries = match &mut frame.entries {
+                Some(entries) => entries,
+                None => match frame.directory.entries(self.repo) {
+                    Ok(entries) => frame
+                        .entries
+                        .insert(entries.collect::<Vec<_>>().into_iter()),
+                    Err(err) => {
+                        self.stack.pop();
+                        return Some(Err(err));
+                    }
+                },
+            };
+            let depth = frame.depth + 1;
+
+            let Some(entry) = entries.next() else {
+                let frame = self.stack.pop()?;
+                match (self.order, frame.entry) {
+                    (WalkOrder::PostOrder, Some(entry)) => match self.matches(&entry) {
+                        Ok((true, _)) => return Some(Ok(entry)),
+                        Ok((false, _)) => continue,
+                        Err(err) => return Some(Err(err)),
+                    },
+                    _ => continue,
+                }
+            };
+
+            let (included, excluded) = match self.matches(&entry) {
+                Ok(matches) => matches,
+                Err(err) => return Some(Err(err)),
+            };
+            if excluded {
+                continue;
+            }
+            let descend = self.max_depth.map_or(true, |max| depth < max);
+            match &entry {
+                Entry::Directory(directory) if descend => {
+                    self.stack.push(Frame {
+                        entry: (self.order == WalkOrder::PostOrder).then(|| entry.clone()),
+                        directory: directory.clone(),
+                        entries: None,
+                        depth,
+                    });
+                    if self.order == WalkOrder::PreOrder && included {
+                        self.entering = true;
+                        return Some(Ok(entry));
+                    }
+                }
+                _ if included => return Some(Ok(entry)),
+                _ => {}
+            }
+        }
+    }
+}
+
 /// A representation of a Git [submodule] when encountered in a Git
 /// repository.
 ///
@@ -7,7 +7,7 @@ use git_ext::ref_format::{
 };
 use thiserror::Error;
 
-use crate::{Branch, Local, Namespace, Remote, Tag};
+use crate::{Branch, Local, Namespace, Note, Remote, Tag};
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -147,6 +147,49 @@ impl Extend<PatternString> for Glob<Tag> {
     }
 }
 
+impl Glob<Note> {
+    /// Creates a `Glob` that matches all `refs/notes`.
+    pub fn all_notes() -> Self {
+        Self::notes(refspec::pattern!("*"))
+    }
+
+    /// Creates a `Glob` for `refs/notes`, starting with `glob`.
+    pub fn notes(glob: PatternString) -> Self {
+        let globs = vec![Self::qualify(glob)];
+        Self {
+            globs,
+            glob_type: PhantomData,
+        }
+    }
+
+    /// Adds a `refs/notes` pattern to this `Glob`.
+    pub fn insert(mut self, glob: PatternString) -> Self {
+        self.globs.push(Self::qualify(glob));
+        self
+    }
+
+    fn qualify(glob: PatternString) -> QualifiedPattern<'static> {
+        qualify(&refname!("refs/notes"), glob).expect("BUG: pattern is qualified")
+    }
+}
+
+impl FromIterator<PatternString> for Glob<Note> {
+    fn from_iter<T: IntoIterator<Item = PatternString>>(iter: T) -> Self {
+        let globs = iter.into_iter().map(Self::qualify).collect();
+
+        Self {
+            globs,
+            glob_type: PhantomData,
+        }
+    }
+}
+
+impl Extend<PatternString> for Glob<Note> {
+    fn extend<T: IntoIterator<Item = PatternString>>(&mut self, iter: T) {
+        self.globs.extend(iter.into_iter().map(Self::qualify))
+    }
+}
+
 impl Glob<Local> {
     /// Creates the `Glob` that matches all `refs/heads`.
     pub fn all_heads() -> Self {
@@ -1,23 +1,189 @@
 use std::{
+    collections::BTreeSet,
     convert::TryFrom,
+    fmt,
     path::{Path, PathBuf},
+    str::FromStr,
 };
 
-use crate::{Commit, Error, Repository, ToCommit};
+use radicle_git_ext::Oid;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer};
+
+use crate::{Commit, Error, Mailmap, Repository, Revision, Time, ToCommit};
+
+pub mod error {
+    use thiserror::Error;
+
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum Cursor {
+        #[error("cannot paginate a history with pages of zero commits")]
+        EmptyPage,
+        #[error("invalid history cursor '{0}'")]
+        Invalid(String),
+        #[error("cannot paginate a history in reverse order")]
+        Reversed,
+        #[error("cannot paginate a history that was iterated without topological order")]
+        Unsorted,
+    }
+}
 
 /// An iterator that produces the history of commits for a given `head`.
 ///
+/// The history can be narrowed down by chaining any of the filtering
+/// methods, e.g. [`History::by_path`] or [`History::by_author`]. When
+/// several filters are set, a commit has to satisfy all of them to be
+/// produced.
+///
+/// The authors and committers of the commits are kept as recorded, unless
+/// a mailmap is set using [`History::mailmap`].
+///
 /// The lifetime of this struct is attached to the underlying [`Repository`].
 pub struct History<'a> {
     repo: &'a Repository,
     head: Commit,
     revwalk: git2::Revwalk<'a>,
-    filter_by: Option<FilterBy>,
+    sort: git2::Sort,
+    first_parent: bool,
+    hidden: Vec<Oid>,
+    filter: Filter,
+    walk: Walk,
+    /// A commit that was walked ahead of the last [`Page`], to know whether
+    /// there is a next one, and which is produced next.
+    peeked: Option<Commit>,
+    mailmap: Option<Mailmap>,
+}
+
+/// The state of the walk, used for constructing a [`Cursor`].
+#[derive(Default)]
+struct Walk {
+    started: bool,
+    last: Option<Oid>,
+    /// The commits that were not walked yet, but that were pushed as heads
+    /// or whose children were walked.
+    pending: BTreeSet<Oid>,
+}
+
+/// An opaque position in a [`History`], used for resuming the walk
+/// where a previous [`Page`] ended.
+///
+/// A `Cursor` can be passed around as a string, using its `Display` and
+/// `FromStr` implementations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cursor {
+    last: Oid,
+    pending: Vec<Oid>,
+}
+
+impl Cursor {
+    /// The last commit that was produced before this `Cursor`.
+    pub fn last(&self) -> Oid {
+        self.last
+    }
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.last)?;
+        for oid in &self.pending {
+            write!(f, ".{oid}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = error::Cursor;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || error::Cursor::Invalid(s.to_string());
+        let mut oids = s
+            .split('.')
+            .map(|oid| Oid::from_str(oid).map_err(|_| invalid()));
+        let last = oids.next().ok_or_else(invalid)??;
+        let pending = oids.collect::<Result<_, _>>()?;
+        Ok(Self { last, pending })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Cursor {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// A page of commits in a [`History`], see [`History::page`].
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Page {
+    /// The commits of this page.
+    pub commits: Vec<Commit>,
+    /// The position to resume from for the next page, or `None` if this
+    /// was the last page.
+    pub next: Option<Cursor>,
 }
 
 /// Internal implementation, subject to refactoring.
-enum FilterBy {
-    File { path: PathBuf },
+#[derive(Default)]
+struct Filter {
+    paths: Vec<PathBuf>,
+    authors: Vec<String>,
+    committers: Vec<String>,
+    since: Option<Time>,
+    until: Option<Time>,
+    message: Option<Message>,
+    merges: Option<Merges>,
+}
+
+enum Message {
+    Contains(String),
+    Matches(regex::Regex),
+}
+
+enum Merges {
+    Only,
+    Exclude,
+}
+
+impl Filter {
+    /// Returns `true` if the metadata of `commit` satisfies the filter.
+    fn matches(&self, commit: &Commit) -> bool {
+        let is_email = |emails: &[String], email: &str| {
+            emails.is_empty() || emails.iter().any(|e| e.eq_ignore_ascii_case(email))
+        };
+        let time = commit.committer.time.seconds();
+
+        is_email(&self.authors, &commit.author.email)
+            && is_email(&self.committers, &commit.committer.email)
+            && self.since.as_ref().map_or(true, |t| t.seconds() <= time)
+            && self.until.as_ref().map_or(true, |t| time <= t.seconds())
+            && self.message.as_ref().map_or(true, |m| match m {
+                Message::Contains(s) => commit.message.contains(s.as_str()),
+                Message::Matches(r) => r.is_match(&commit.message),
+            })
+            && self.merges.as_ref().map_or(true, |m| match m {
+                Merges::Only => commit.parents.len() > 1,
+                Merges::Exclude => commit.parents.len() <= 1,
+            })
+    }
+
+    /// Returns the paths to filter by, if any.
+    ///
+    /// An empty path is the root of the repository, which every commit
+    /// touches, so there is nothing to filter by.
+    fn paths(&self) -> Option<&[PathBuf]> {
+        if self.paths.is_empty() || self.paths.iter().any(|p| p.as_os_str().is_empty()) {
+            None
+        } else {
+            Some(&self.paths)
+        }
+    }
 }
 
 impl<'a> History<'a> {
@@ -28,58 +194,396 @@ impl<'a> History<'a> {
             .map_err(|err| Error::ToCommit(err.into()))?;
         let mut revwalk = repo.revwalk()?;
         revwalk.push(head.id.into())?;
+        let walk = Walk {
+            pending: BTreeSet::from([head.id]),
+            ..Walk::default()
+        };
         let history = Self {
             repo,
             head,
             revwalk,
-            filter_by: None,
+            sort: git2::Sort::NONE,
+            first_parent: false,
+            hidden: Vec::new(),
+            filter: Filter::default(),
+            walk,
+            peeked: None,
+            mailmap: None,
         };
         Ok(history)
     }
 
+    /// Creates a new history of the commits reachable from `to`, but not
+    /// from `from`, i.e. `from..to`.
+    pub(crate) fn range<R, C>(repo: &'a Repository, from: R, to: C) -> Result<Self, Error>
+    where
+        R: Revision,
+        C: ToCommit,
+    {
+        Self::new(repo, to)?.exclude(from)
+    }
+
+    /// Creates a new history of the commits reachable from either `a` or
+    /// `b`, but not from both, i.e. `a...b`.
+    ///
+    /// The head of the history is `a`.
+    pub(crate) fn symmetric<C, R>(repo: &'a Repository, a: C, b: R) -> Result<Self, Error>
+    where
+        C: ToCommit,
+        R: Revision,
+    {
+        let history = Self::new(repo, a)?;
+        let b = repo.object_id(&b)?;
+        let bases = repo.merge_bases(history.head.id, b)?;
+        let mut history = history.include(b)?;
+        for base in bases {
+            history = history.exclude(base)?;
+        }
+        Ok(history)
+    }
+
     /// Returns the first commit (i.e. the head) in the history.
     pub fn head(&self) -> &Commit {
         &self.head
     }
 
+    /// Returns a modified `History` that also contains the commits
+    /// reachable from `rev`.
+    pub fn include<R: Revision>(mut self, rev: R) -> Result<Self, Error> {
+        let oid = self.repo.object_id(&rev)?;
+        self.revwalk.push(oid.into())?;
+        self.walk.pending.insert(oid);
+        Ok(self)
+    }
+
+    /// Returns a modified `History` that hides `rev` and all the commits
+    /// reachable from it.
+    ///
+    /// Note that it is possible that the History becomes empty, even though
+    /// calling `.head()` still returns the original head.
+    pub fn exclude<R: Revision>(mut self, rev: R) -> Result<Self, Error> {
+        let oid = self.repo.object_id(&rev)?;
+        self.revwalk.hide(oid.into())?;
+        self.hidden.push(oid);
+        Ok(self)
+    }
+
+    /// Returns a modified `History` where no parent is produced before all
+    /// of its children are.
+    ///
+    /// The sorting methods can be combined, and must be called before
+    /// iterating over the `History`.
+    pub fn topological(self) -> Result<Self, Error> {
+        self.sort_by(git2::Sort::TOPOLOGICAL)
+    }
+
+    /// Returns a modified `History` where commits are produced by their
+    /// commit time, newest first.
+    ///
+    /// The sorting methods can be combined, and must be called before
+    /// iterating over the `History`.
+    pub fn by_time(self) -> Result<Self, Error> {
+        self.sort_by(git2::Sort::TIME)
+    }
+
+    /// Returns a modified `History` where commits are produced in reverse
+    /// order, e.g. oldest first when combined with [`History::by_time`].
+    ///
+    /// Note that `.head()` still returns the original head.
+    ///
+    /// The sorting methods can be combined, and must be called before
+    /// iterating over the `History`.
+    pub fn reverse(self) -> Result<Self, Error> {
+        self.sort_by(git2::Sort::REVERSE)
+    }
+
+    /// Returns a modified `History` that only follows the first parent of
+    /// each commit, i.e. the mainline of merges.
+    pub fn first_parent(mut self) -> Result<Self, Error> {
+        self.revwalk.simplify_first_parent()?;
+        self.first_parent = true;
+        Ok(self)
+    }
+
+    /// Returns the next [`Page`] of at most `size` commits.
+    ///
+    /// Paginating requires a topological order, so that a [`Cursor`] can
+    /// resume the walk without producing a commit twice. The `History` is
+    /// switched to topological order if it was not iterated yet.
+    ///
+    /// # Errors
+    ///
+    /// * `EmptyPage` if `size` is `0`.
+    /// * `Reversed` if [`History::reverse`] was used.
+    /// * `Unsorted` if the `History` was iterated without
+    ///   topological order.
+    pub fn page(&mut self, size: usize) -> Result<Page, Error> {
+        if size == 0 {
+            return Err(error::Cursor::EmptyPage.into());
+        }
+        if self.sort.contains(git2::Sort::REVERSE) {
+            return Err(error::Cursor::Reversed.into());
+        }
+        if !self.sort.contains(git2::Sort::TOPOLOGICAL) {
+            if self.walk.started {
+                return Err(error::Cursor::Unsorted.into());
+            }
+            self.sort.insert(git2::Sort::TOPOLOGICAL);
+            self.revwalk.set_sorting(self.sort)?;
+        }
+
+        let commits = self.by_ref().take(size).collect::<Result<Vec<_>, _>>()?;
+        let cursor = match self.walk.last {
+            Some(last) if commits.len() == size => Cursor {
+                last,
+                pending: self.walk.pending.iter().copied().collect(),
+            },
+            _ => {
+                return Ok(Page {
+                    commits,
+                    next: None,
+                })
+            }
+        };
+        // Walk one commit ahead, so that a full page at the end of the
+        // history does not lead to an empty one.
+        let next = match self.next().transpose()? {
+            Some(commit) => {
+                self.peeked = Some(commit);
+                Some(cursor)
+            }
+            None => None,
+        };
+        Ok(Page { commits, next })
+    }
+
+    /// Returns a modified `History` that resumes the walk after `cursor`,
+    /// which was returned from a previous [`History::page`].
+    ///
+    /// The `History` must be constructed with the same heads, exclusions and
+    /// options as the one that produced the `cursor`.
+    pub fn resume(mut self, cursor: &Cursor) -> Result<Self, Error> {
+        if self.sort.contains(git2::Sort::REVERSE) {
+            return Err(error::Cursor::Reversed.into());
+        }
+        self.sort.insert(git2::Sort::TOPOLOGICAL);
+        self.revwalk.reset()?;
+        self.revwalk.set_sorting(self.sort)?;
+        if self.first_parent {
+            self.revwalk.simplify_first_parent()?;
+        }
+        for oid in &cursor.pending {
+            self.revwalk.push((*oid).into())?;
+        }
+        for oid in &self.hidden {
+            self.revwalk.hide((*oid).into())?;
+        }
+        self.walk = Walk {
+            started: false,
+            last: Some(cursor.last),
+            pending: cursor.pending.iter().copied().collect(),
+        };
+        self.peeked = None;
+        Ok(self)
+    }
+
     /// Returns a modified `History` filtered by `path`.
     ///
+    /// This replaces any paths set by [`History::by_paths`].
+    ///
     /// Note that it is possible that a filtered History becomes empty,
     /// even though calling `.head()` still returns the original head.
-    pub fn by_path<P>(mut self, path: &P) -> Self
+    pub fn by_path<P>(self, path: &P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        self.by_paths([path])
+    }
+
+    /// Returns a modified `History` filtered by commits that touch
+    /// any of the `paths`.
+    ///
+    /// This replaces any path set by [`History::by_path`].
+    pub fn by_paths<I, P>(mut self, paths: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        self.filter.paths = paths
+            .into_iter()
+            .map(|p| p.as_ref().to_path_buf())
+            .collect();
+        self
+    }
+
+    /// Returns a [`Follow`] iterator that produces the commits touching
+    /// `path`, tracking the file across renames and copies, similar to
+    /// `git log --follow`.
+    ///
+    /// This replaces any paths set by [`History::by_path`] or
+    /// [`History::by_paths`]. The other filters still apply.
+    pub fn follow<P>(mut self, path: &P) -> Follow<'a>
     where
         P: AsRef<Path>,
     {
-        self.filter_by = Some(FilterBy::File {
+        self.filter.paths.clear();
+        Follow {
+            history: self,
             path: path.as_ref().to_path_buf(),
-        });
+            source: None,
+        }
+    }
+
+    /// Returns a modified `History` filtered by commits authored by
+    /// `email`.
+    ///
+    /// Calling this multiple times keeps commits authored by any of the
+    /// given emails. Emails are compared ignoring ASCII case, after
+    /// resolving the mailmap, if any.
+    pub fn by_author(mut self, email: impl Into<String>) -> Self {
+        self.filter.authors.push(email.into());
+        self
+    }
+
+    /// Returns a modified `History` filtered by commits committed by
+    /// `email`.
+    ///
+    /// Calling this multiple times keeps commits committed by any of the
+    /// given emails. Emails are compared ignoring ASCII case, after
+    /// resolving the mailmap, if any.
+    pub fn by_committer(mut self, email: impl Into<String>) -> Self {
+        self.filter.committers.push(email.into());
+        self
+    }
+
+    /// Returns a modified `History` filtered by commits committed at, or
+    /// after, `time`.
+    pub fn since(mut self, time: Time) -> Self {
+        self.filter.since = Some(time);
+        self
+    }
+
+    /// Returns a modified `History` filtered by commits committed at, or
+    /// before, `time`.
+    pub fn until(mut self, time: Time) -> Self {
+        self.filter.until = Some(time);
+        self
+    }
+
+    /// Returns a modified `History` filtered by commits whose message
+    /// contains `text`.
+    ///
+    /// This replaces any pattern set by [`History::by_message_regex`].
+    pub fn by_message(mut self, text: impl Into<String>) -> Self {
+        self.filter.message = Some(Message::Contains(text.into()));
+        self
+    }
+
+    /// Returns a modified `History` filtered by commits whose message
+    /// matches the regular expression `pattern`.
+    ///
+    /// This replaces any text set by [`History::by_message`].
+    ///
+    /// # Errors
+    ///
+    /// * [`Error::Regex`]
+    pub fn by_message_regex(mut self, pattern: &str) -> Result<Self, Error> {
+        self.filter.message = Some(Message::Matches(regex::Regex::new(pattern)?));
+        Ok(self)
+    }
+
+    /// Returns a modified `History` that only contains merge commits,
+    /// i.e. commits with more than one parent.
+    pub fn merges_only(mut self) -> Self {
+        self.filter.merges = Some(Merges::Only);
         self
     }
+
+    /// Returns a modified `History` that does not contain any merge
+    /// commits.
+    pub fn no_merges(mut self) -> Self {
+        self.filter.merges = Some(Merges::Exclude);
+        self
+    }
+
+    /// Returns a modified `History` whose authors and committers are
+    /// resolved using `mailmap`, e.g. the one of the repository returned by
+    /// [`Repository::mailmap`].
+    pub fn mailmap(mut self, mailmap: Mailmap) -> Result<Self, Error> {
+        self.mailmap = Some(mailmap);
+        self.reload_head()
+    }
+
+    /// Returns a modified `History` whose authors and committers are kept
+    /// as they were recorded, undoing [`History::mailmap`].
+    pub fn no_mailmap(mut self) -> Result<Self, Error> {
+        self.mailmap = None;
+        self.reload_head()
+    }
+}
+
+impl Walk {
+    fn visit(&mut self, commit: &git2::Commit, first_parent: bool) {
+        let oid = commit.id().into();
+        self.started = true;
+        self.pending.remove(&oid);
+        let parents = commit.parent_ids().map(Oid::from);
+        if first_parent {
+            self.pending.extend(parents.take(1));
+        } else {
+            self.pending.extend(parents);
+        }
+    }
+}
+
+impl History<'_> {
+    /// Converts the head again, after the mailmap changed.
+    fn reload_head(mut self) -> Result<Self, Error> {
+        let head = Commit::try_from(self.repo.find_commit(self.head.id)?)?;
+        self.head = match &self.mailmap {
+            Some(mailmap) => mailmap.resolve_commit(head),
+            None => head,
+        };
+        Ok(self)
+    }
+
+    fn sort_by(mut self, sort: git2::Sort) -> Result<Self, Error> {
+        self.sort.insert(sort);
+        self.revwalk.set_sorting(self.sort)?;
+        Ok(self)
+    }
 }
 
 impl Iterator for History<'_> {
     type Item = Result<Commit, Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if let Some(commit) = self.peeked.take() {
+            return Some(Ok(commit));
+        }
         // Loop through the commits with the optional filtering.
         while let Some(oid) = self.revwalk.next() {
             let found = oid
                 .map_err(Error::Git)
                 .and_then(|oid| {
-                    let commit = self.repo.find_commit(oid.into())?;
-
-                    // Handles the optional filter_by.
-                    if let Some(FilterBy::File { path }) = &self.filter_by {
-                        // Only check the commit diff if the path is not empty.
-                        if !path.as_os_str().is_empty() {
-                            let path_opt = self.repo.diff_commit_and_parents(path, &commit)?;
-                            if path_opt.is_none() {
-                                return Ok(None); // Filter out this commit.
-                            }
+                    let git2_commit = self.repo.find_commit(oid.into())?;
+                    self.walk.visit(&git2_commit, self.first_parent);
+                    let mut commit = Commit::try_from(git2_commit.clone())?;
+                    if let Some(mailmap) = &self.mailmap {
+                        commit = mailmap.resolve_commit(commit);
+                    }
+                    if !self.filter.matches(&commit) {
+                        return Ok(None); // Filter out this commit.
+                    }
+
+                    // The path filter is checked last since it requires a diff.
+                    if let Some(paths) = self.filter.paths() {
+                        if !self.repo.diff_commit_and_parents(paths, &git2_commit)? {
+                            return Ok(None); // Filter out this commit.
                         }
                     }
 
-                    let commit = Commit::try_from(commit)?;
+                    self.walk.last = Some(commit.id);
                     Ok(Some(commit))
                 })
                 .transpose();
@@ -91,6 +595,68 @@ impl Iterator for History<'_> {
     }
 }
 
+/// An iterator that produces the commits of a [`History`] that touched a
+/// file, following it across renames and copies.
+///
+/// Each commit is paired with the path of the file in that commit.
+///
+/// See [`History::follow`].
+pub struct Follow<'a> {
+    history: History<'a>,
+    path: PathBuf,
+    /// The path the file was renamed or copied from in the last produced
+    /// commit, which is followed from the next commit on.
+    source: Option<PathBuf>,
+}
+
+impl Follow<'_> {
+    /// Returns the path of the file in the last produced commit, or the
+    /// path passed to [`History::follow`] if no commit was produced yet.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Iterator for Follow<'_> {
+    type Item = Result<(Commit, PathBuf), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let repo = self.history.repo;
+        // After a rename, the file is looked up at its old path, but `path`
+        // is only updated once a commit touching it is produced.
+        let path = self.source.clone().unwrap_or_else(|| self.path.clone());
+        for commit in self.history.by_ref() {
+            let found = commit.and_then(|commit| {
+                let git2_commit = repo.find_commit(commit.id)?;
+                let source = match repo.path_delta(&path, &git2_commit)? {
+                    None => return Ok(None), // Filter out this commit.
+                    // The file may have been renamed or copied from another
+                    // path, which is followed after this commit.
+                    Some(git2::Delta::Added) => repo.rename_source(&path, &git2_commit)?,
+                    Some(_) => None,
+                };
+                Ok(Some((commit, source)))
+            });
+            match found {
+                Ok(None) => continue,
+                Ok(Some((commit, source))) => {
+                    self.path = path.clone();
+                    self.source = source;
+                    return Some(Ok((commit, path)));
+                }
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        None
+    }
+}
+
+impl std::fmt::Debug for Follow<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} following {:?}", self.history, self.path)
+    }
+}
+
 impl std::fmt::Debug for History<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "History of {}", self.head.id)
@@ -0,0 +1,581 @@
+//! Detection of the programming languages of the files in a revision, in
+//! the manner of GitHub's [linguist].
+//!
+//! [`LanguageStats`] can be retrieved via
+//! [`crate::Repository::language_stats`].
+//!
+//! [linguist]: https://github.com/github-linguist/linguist
+
+use std::{
+    collections::BTreeMap,
+    iter::Peekable,
+    path::{Path, PathBuf},
+    str::Chars,
+};
+
+use radicle_git_ext::Oid;
+use regex::Regex;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::{Error, Repository};
+
+/// The name of the files that hold the attributes of paths.
+const GITATTRIBUTES: &str = ".gitattributes";
+
+/// The directories of third-party code, which are excluded by default.
+const VENDORED: &[&str] = &["vendor", "node_modules", "third_party", "bower_components"];
+
+/// The language statistics of a revision, i.e. how many files and bytes
+/// are written in each language.
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LanguageStats {
+    /// The total size of all counted files, in bytes.
+    pub bytes: usize,
+    /// The counted languages, ordered by their size, largest first.
+    pub languages: Vec<Language>,
+}
+
+/// The files of a language within [`LanguageStats`].
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Language {
+    /// The name of the language, e.g. `Rust`.
+    pub name: String,
+    /// The total size of the files in this language, in bytes.
+    pub bytes: usize,
+    /// The number of files in this language.
+    pub files: usize,
+    /// The share of `bytes` in the total size, in percent.
+    pub percent: f32,
+}
+
+/// The kind of a language, which decides whether it is counted by default.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    Programming,
+    Markup,
+    Data,
+    Prose,
+}
+
+/// A language and how to recognise its files.
+struct Definition {
+    name: &'static str,
+    kind: Kind,
+    extensions: &'static [&'static str],
+    filenames: &'static [&'static str],
+    interpreters: &'static [&'static str],
+}
+
+const fn lang(
+    name: &'static str,
+    kind: Kind,
+    extensions: &'static [&'static str],
+    filenames: &'static [&'static str],
+    interpreters: &'static [&'static str],
+) -> Definition {
+    Definition {
+        name,
+        kind,
+        extensions,
+        filenames,
+        interpreters,
+    }
+}
+
+/// The known languages, where extensions and file names are compared
+/// case-insensitively.
+const LANGUAGES: &[Definition] = &[
+    lang("C", Kind::Programming, &["c", "h"], &[], &["tcc"]),
+    lang("C#", Kind::Programming, &["cs", "csx"], &[], &[]),
+    lang(
+        "C++",
+        Kind::Programming,
+        &["cc", "cpp", "cxx", "c++", "hh", "hpp", "hxx"],
+        &[],
+        &[],
+    ),
+    lang(
+        "CMake",
+        Kind::Programming,
+        &["cmake"],
+        &["cmakelists.txt"],
+        &[],
+    ),
+    lang("CSS", Kind::Markup, &["css"], &[], &[]),
+    lang(
+        "Clojure",
+        Kind::Programming,
+        &["clj", "cljs", "cljc", "edn"],
+        &[],
+        &[],
+    ),
+    lang(
+        "CoffeeScript",
+        Kind::Programming,
+        &["coffee"],
+        &[],
+        &["coffee"],
+    ),
+    lang("Dart", Kind::Programming, &["dart"], &[], &[]),
+    lang(
+        "Dockerfile",
+        Kind::Programming,
+        &["dockerfile"],
+        &["dockerfile", "containerfile"],
+        &[],
+    ),
+    lang(
+        "Elixir",
+        Kind::Programming,
+        &["ex", "exs"],
+        &[],
+        &["elixir"],
+    ),
+    lang("Elm", Kind::Programming, &["elm"], &[], &[]),
+    lang("Emacs Lisp", Kind::Programming, &["el"], &[".emacs"], &[]),
+    lang(
+        "Erlang",
+        Kind::Programming,
+        &["erl", "hrl"],
+        &[],
+        &["escript"],
+    ),
+    lang("F#", Kind::Programming, &["fs", "fsi", "fsx"], &[], &[]),
+    lang("Go", Kind::Programming, &["go"], &[], &[]),
+    lang(
+        "Groovy",
+        Kind::Programming,
+        &["groovy", "gradle"],
+        &["jenkinsfile"],
+        &["groovy"],
+    ),
+    lang("HTML", Kind::Markup, &["html", "htm", "xhtml"], &[], &[]),
+    lang(
+        "Haskell",
+        Kind::Programming,
+        &["hs", "lhs", "hs-boot"],
+        &[],
+        &["runghc", "runhaskell"],
+    ),
+    lang(
+        "JSON",
+        Kind::Data,
+        &["json"],
+        &[".babelrc", ".eslintrc"],
+        &[],
+    ),
+    lang("Java", Kind::Programming, &["java"], &[], &[]),
+    lang(
+        "JavaScript",
+        Kind::Programming,
+        &["js", "cjs", "mjs", "jsx"],
+        &[],
+        &["node", "nodejs"],
+    ),
+    lang("Julia", Kind::Programming, &["jl"], &[], &["julia"]),
+    lang("Kotlin", Kind::Programming, &["kt", "kts"], &[], &[]),
+    lang("Lua", Kind::Programming, &["lua"], &[], &["lua"]),
+    lang(
+        "Makefile",
+        Kind::Programming,
+        &["mk", "mak"],
+        &["makefile", "gnumakefile"],
+        &["make"],
+    ),
+    lang("Markdown", Kind::Prose, &["md", "markdown"], &[], &[]),
+    lang("Nix", Kind::Programming, &["nix"], &[], &[]),
+    lang("OCaml", Kind::Programming, &["ml", "mli"], &[], &["ocaml"]),
+    lang("Objective-C", Kind::Programming, &["m"], &[], &[]),
+    lang("PHP", Kind::Programming, &["php"], &[], &["php"]),
+    lang("Perl", Kind::Programming, &["pl", "pm"], &[], &["perl"]),
+    lang(
+        "PowerShell",
+        Kind::Programming,
+        &["ps1", "psm1"],
+        &[],
+        &["pwsh"],
+    ),
+    lang(
+        "Python",
+        Kind::Programming,
+        &["py", "pyi", "pyw"],
+        &[],
+        &["python"],
+    ),
+    lang("R", Kind::Programming, &["r"], &[], &["rscript"]),
+    lang(
+        "Ruby",
+        Kind::Programming,
+        &["rb", "rake", "gemspec"],
+        &["gemfile", "rakefile"],
+        &["ruby"],
+    ),
+    lang("Rust", Kind::Programming, &["rs"], &[], &[]),
+    lang("SCSS", Kind::Markup, &["scss"], &[], &[]),
+    lang("SQL", Kind::Data, &["sql"], &[], &[]),
+    lang(
+        "Scala",
+        Kind::Programming,
+        &["scala", "sc"],
+        &[],
+        &["scala"],
+    ),
+    lang(
+        "Shell",
+        Kind::Programming,
+        &["sh", "bash", "zsh"],
+        &[".bashrc", ".zshrc"],
+        &["sh", "bash", "zsh", "dash", "ksh"],
+    ),
+    lang("Svelte", Kind::Markup, &["svelte"], &[], &[]),
+    lang("Swift", Kind::Programming, &["swift"], &[], &[]),
+    lang("TOML", Kind::Data, &["toml"], &["cargo.lock"], &[]),
+    lang("TeX", Kind::Markup, &["tex", "sty", "cls"], &[], &[]),
+    lang("Text", Kind::Prose, &["txt"], &["license", "copying"], &[]),
+    lang(
+        "TypeScript",
+        Kind::Programming,
+        &["ts", "cts", "mts", "tsx"],
+        &[],
+        &["deno", "ts-node"],
+    ),
+    lang("Vue", Kind::Markup, &["vue"], &[], &[]),
+    lang("XML", Kind::Data, &["xml", "xsd", "xsl", "svg"], &[], &[]),
+    lang("YAML", Kind::Data, &["yml", "yaml"], &[], &[]),
+    lang("Zig", Kind::Programming, &["zig"], &[], &[]),
+];
+
+/// Computes the [`LanguageStats`] of `tree`.
+pub(crate) fn stats(repo: &Repository, tree: &git2::Tree) -> Result<LanguageStats, Error> {
+    let mut files = Vec::new();
+    tree.walk(git2::TreeWalkMode::PreOrder, |parent, entry| {
+        let is_file = entry.kind() == Some(git2::ObjectType::Blob)
+            && entry.filemode() != i32::from(git2::FileMode::Link);
+        if let (true, Ok(name)) = (is_file, entry.name()) {
+            files.push((Path::new(parent).join(name), Oid::from(entry.id())));
+        }
+        git2::TreeWalkResult::Ok
+    })?;
+    let attributes = Attributes::load(repo, &files, "linguist-")?;
+    let odb = repo.odb()?;
+
+    let mut languages = BTreeMap::<String, (usize, usize)>::new();
+    for (path, oid) in &files {
+        let excluded = [
+            "linguist-vendored",
+            "linguist-generated",
+            "linguist-documentation",
+        ]
+        .into_iter()
+        .any(|attr| attributes.get(path, attr) == Some(true));
+        let vendored = attributes.get(path, "linguist-vendored").is_none()
+            && path
+                .parent()
+                .is_some_and(|dir| dir.iter().any(|c| VENDORED.iter().any(|v| c == *v)));
+        if excluded || vendored {
+            continue;
+        }
+
+        let name = match attributes.value(path, "linguist-language") {
+            Some(name) => Some(name.replace('_', " ")),
+            None => detect(repo, path, *oid)?.map(|def| def.name.to_string()),
+        };
+        let Some(name) = name else {
+            continue;
+        };
+        let counted = match attributes.get(path, "linguist-detectable") {
+            Some(detectable) => detectable,
+            None => definition(&name).map_or(true, |def| {
+                matches!(def.kind, Kind::Programming | Kind::Markup)
+            }),
+        };
+        if !counted {
+            continue;
+        }
+
+        let (size, _) = odb.read_header((*oid).into())?;
+        let name = definition(&name).map_or(name, |def| def.name.to_string());
+        let entry = languages.entry(name).or_default();
+        entry.0 += size;
+        entry.1 += 1;
+    }
+
+    let bytes = languages.values().map(|(bytes, _)| bytes).sum::<usize>();
+    let mut languages = languages
+        .into_iter()
+        .map(|(name, (size, files))| Language {
+            name,
+            bytes: size,
+            files,
+            percent: size as f32 * 100.0 / bytes.max(1) as f32,
+        })
+        .collect::<Vec<_>>();
+    languages.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+    Ok(LanguageStats { bytes, languages })
+}
+
+/// Looks up a language by its name, ignoring case.
+fn definition(name: &str) -> Option<&'static Definition> {
+    LANGUAGES
+        .iter()
+        .find(|def| def.name.eq_ignore_ascii_case(name))
+}
+
+/// Detects the language of the file at `path` by its name, its extension,
+/// or, failing both, the interpreter of its shebang line.
+fn detect(repo: &Repository, path: &Path, oid: Oid) -> Result<Option<&'static Definition>, Error> {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if let Some(def) = LANGUAGES
+        .iter()
+        .find(|def| def.filenames.contains(&name.as_str()))
+    {
+        return Ok(Some(def));
+    }
+    if let Some((_, ext)) = name.rsplit_once('.').filter(|(stem, _)| !stem.is_empty()) {
+        return Ok(LANGUAGES.iter().find(|def| def.extensions.contains(&ext)));
+    }
+
+    let blob = repo.find_blob(oid)?;
+    Ok(interpreter(blob.content()).and_then(|interpreter| {
+        LANGUAGES
+            .iter()
+            .find(|def| def.interpreters.contains(&interpreter.as_str()))
+    }))
+}
+
+/// The interpreter of a shebang line, without any version, e.g. `python`
+/// for `#!/usr/bin/env python3`.
+fn interpreter(content: &[u8]) -> Option<String> {
+    let line = content.strip_prefix(b"#!")?;
+    let line = &line[..line.iter().position(|b| *b == b'\n').unwrap_or(line.len())];
+    let line = String::from_utf8_lossy(line);
+    let mut args = line.split_whitespace();
+    let mut program = args.next()?.rsplit('/').next()?;
+    if program == "env" {
+        program = args.find(|arg| !arg.starts_with('-') && !arg.contains('='))?;
+    }
+    let program = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    Some(program.to_string())
+}
+
+/// The value of an attribute in a `.gitattributes` file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Value {
+    /// `attr`
+    Set,
+    /// `-attr`
+    Unset,
+    /// `attr=value`
+    String(String),
+    /// `!attr`
+    Unspecified,
+}
+
+/// A line of a `.gitattributes` file.
+struct Rule {
+    /// Matches paths relative to the repository root.
+    pattern: Regex,
+    attributes: Vec<(String, Value)>,
+}
+
+/// The attributes of the `.gitattributes` files of a tree that start with a
+/// given prefix, e.g. `linguist-`.
+pub(crate) struct Attributes {
+    /// The rules ordered by precedence, lowest first.
+    rules: Vec<Rule>,
+}
+
+impl Attributes {
+    /// Parses the attributes starting with `prefix` of the `.gitattributes`
+    /// among `files`, where the files of sub-directories take precedence
+    /// over those of their parents.
+    pub(crate) fn load(
+        repo: &Repository,
+        files: &[(PathBuf, Oid)],
+        prefix: &str,
+    ) -> Result<Self, Error> {
+        let mut sources = files
+            .iter()
+            .filter(|(path, _)| path.file_name().is_some_and(|name| name == GITATTRIBUTES))
+            .collect::<Vec<_>>();
+        sources.sort_by_key(|(path, _)| path.components().count());
+
+        let mut rules = Vec::new();
+        for (path, oid) in sources {
+            let dir = path.parent().unwrap_or(Path::new(""));
+            let blob = repo.find_blob(*oid)?;
+            for line in String::from_utf8_lossy(blob.content()).lines() {
+                let mut fields = line.split_whitespace();
+                let Some(pattern) = fields.next().filter(|p| !p.starts_with('#')) else {
+                    continue;
+                };
+                let attributes = fields
+                    .filter(|attr| attr.trim_start_matches(['-', '!']).starts_with(prefix))
+                    .map(|attr| match attr.split_once('=') {
+                        Some((name, value)) => (name.to_string(), Value::String(value.to_string())),
+                        None => match attr.strip_prefix('-') {
+                            Some(name) => (name.to_string(), Value::Unset),
+                            None => match attr.strip_prefix('!') {
+                                Some(name) => (name.to_string(), Value::Unspecified),
+                                None => (attr.to_string(), Value::Set),
+                            },
+                        },
+                    })
+                    .collect::<Vec<_>>();
+                if attributes.is_empty() {
+                    continue;
+                }
+                // Like git, patterns that cannot be matched are ignored.
+                let Ok(pattern) = glob(dir, pattern) else {
+                    continue;
+                };
+                rules.push(Rule {
+                    pattern,
+                    attributes,
+                });
+            }
+        }
+        Ok(Self { rules })
+    }
+
+    /// The value of the attribute `name` for `path`, if specified.
+    fn lookup(&self, path: &Path, name: &str) -> Option<&Value> {
+        let path = path.to_string_lossy();
+        self.rules
+            .iter()
+            .rev()
+            .filter(|rule| rule.pattern.is_match(&path))
+            .find_map(|rule| {
+                rule.attributes
+                    .iter()
+                    .rev()
+                    .find(|(attr, _)| attr == name)
+                    .map(|(_, value)| value)
+            })
+            .filter(|value| **value != Value::Unspecified)
+    }
+
+    /// The attribute `name` for `path` as a boolean, if specified.
+    pub(crate) fn get(&self, path: &Path, name: &str) -> Option<bool> {
+        match self.lookup(path, name)? {
+            Value::Set => Some(true),
+            Value::Unset => Some(false),
+            Value::String(value) => Some(value != "false"),
+            Value::Unspecified => None,
+        }
+    }
+
+    /// The attribute `name` for `path` as a string, if it has a value.
+    fn value(&self, path: &Path, name: &str) -> Option<&str> {
+        match self.lookup(path, name)? {
+            Value::String(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Translates the gitattributes `pattern` of a file in `dir` into a regular
+/// expression matching paths relative to the repository root.
+///
+/// A pattern without a slash matches the file name at any depth, while any
+/// other pattern is relative to `dir`. A `[` without a closing `]` matches
+/// itself. This is shared with the `.gitignore` style patterns of
+/// [`crate::codeowners`].
+///
+/// # Errors
+///
+/// Fails for patterns that match nothing, e.g. the reversed range `[z-a]`.
+pub(crate) fn glob(dir: &Path, pattern: &str) -> Result<Regex, regex::Error> {
+    let mut re = String::from("^");
+    let dir = dir.to_string_lossy();
+    if !dir.is_empty() {
+        re.push_str(&regex::escape(&dir));
+        re.push('/');
+    }
+    let pattern = match pattern.strip_prefix('/') {
+        Some(pattern) => pattern,
+        None if !pattern.trim_end_matches('/').contains('/') => {
+            re.push_str("(?:.*/)?");
+            pattern
+        }
+        None => pattern,
+    };
+
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    re.push_str("(?:.*/)?");
+                } else {
+                    re.push_str(".*");
+                }
+            }
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            '[' => {
+                let mut rest = chars.clone();
+                match class(&mut rest) {
+                    Some(class) => {
+                        re.push_str(&class);
+                        chars = rest;
+                    }
+                    None => re.push_str(r"\["),
+                }
+            }
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push('$');
+    Regex::new(&re)
+}
+
+/// Translates the character class following a `[` of a pattern, or returns
+/// `None` if the class is not closed.
+///
+/// Every character is escaped, so that the set operators of regular
+/// expressions, e.g. `&&` or `--`, are taken literally.
+fn class(chars: &mut Peekable<Chars>) -> Option<String> {
+    fn literal(c: char, chars: &mut Peekable<Chars>) -> Option<String> {
+        let c = if c == '\\' { chars.next()? } else { c };
+        Some(regex::escape(&c.to_string()))
+    }
+
+    let mut re = String::from("[");
+    if chars.next_if(|c| *c == '!' || *c == '^').is_some() {
+        re.push('^');
+    }
+    // A `]` right after the opening is part of the class.
+    let mut first = true;
+    loop {
+        let c = chars.next()?;
+        if c == ']' && !first {
+            break;
+        }
+        first = false;
+        re.push_str(&literal(c, chars)?);
+
+        let mut range = chars.clone();
+        if range.next() == Some('-') {
+            match range.next() {
+                None | Some(']') => {}
+                Some(end) => {
+                    re.push('-');
+                    re.push_str(&literal(end, &mut range)?);
+                    *chars = range;
+                }
+            }
+        }
+    }
+    re.push(']');
+    Some(re)
+}
@@ -23,9 +23,14 @@ pub use radicle_git_ext::ref_format;
 /// Represents an object id in Git. Re-exported from `radicle-git-ext`.
 pub type Oid = radicle_git_ext::Oid;
 
+pub mod archive;
+pub mod blame;
 pub mod blob;
+pub mod codeowners;
 pub mod diff;
 pub mod fs;
+pub mod search;
+pub mod signature;
 pub mod tree;
 
 /// Private modules with their public types.
@@ -36,7 +41,16 @@ mod glob;
 pub use glob::Glob;
 
 mod history;
-pub use history::History;
+pub use history::{Cursor, Follow, History, Page};
+
+mod mailmap;
+pub use mailmap::Mailmap;
+
+mod merge;
+pub use merge::MergePreview;
+
+mod metadata;
+pub use metadata::{License, Metadata, MetadataFile};
 
 mod branch;
 pub use branch::{Branch, Local, Remote};
@@ -47,11 +61,17 @@ pub use tag::Tag;
 mod commit;
 pub use commit::{Author, Commit, Time};
 
+mod note;
+pub use note::Note;
+
 mod namespace;
 pub use namespace::Namespace;
 
 mod stats;
-pub use stats::Stats;
+pub use stats::{Contributor, Stats};
+
+mod language;
+pub use language::{Language, LanguageStats};
 
 mod revision;
 pub use revision::{Revision, Signature, ToCommit};
@@ -0,0 +1,53 @@
+//! Resolution of the canonical names and emails of authors, using the
+//! [mailmap] of a repository.
+//!
+//! A [`Mailmap`] can be retrieved via [`crate::Repository::mailmap`].
+//!
+//! [mailmap]: https://git-scm.com/docs/gitmailmap
+
+use std::fmt;
+
+use crate::{Author, Commit, Error};
+
+/// A mapping from the names and emails used in commits to canonical ones,
+/// e.g. for a person who committed with more than one email.
+pub struct Mailmap {
+    inner: git2::Mailmap,
+}
+
+impl Mailmap {
+    pub(crate) fn new(inner: git2::Mailmap) -> Self {
+        Self { inner }
+    }
+
+    /// Parses a mailmap in the format of a `.mailmap` file.
+    pub fn from_buffer(buf: &str) -> Result<Self, Error> {
+        Ok(Self::new(git2::Mailmap::from_buffer(buf)?))
+    }
+
+    /// Returns the canonical identity of `author`, keeping its time.
+    ///
+    /// An author that is not mapped, or cannot be represented as a git
+    /// signature, is returned as is.
+    pub fn resolve(&self, author: &Author) -> Author {
+        let time = git2::Time::new(author.time.seconds(), author.time.offset_minutes());
+        git2::Signature::new(&author.name, &author.email, &time)
+            .and_then(|sig| self.inner.resolve_signature(&sig))
+            .ok()
+            .and_then(|sig| Author::try_from(sig).ok())
+            .unwrap_or_else(|| author.clone())
+    }
+
+    /// Resolves the author and committer of `commit`.
+    pub(crate) fn resolve_commit(&self, mut commit: Commit) -> Commit {
+        commit.author = self.resolve(&commit.author);
+        commit.committer = self.resolve(&commit.committer);
+        commit
+    }
+}
+
+impl fmt::Debug for Mailmap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Mailmap")
+    }
+}
@@ -0,0 +1,179 @@
+//! Represents the outcome of merging two commits without touching the
+//! index or working directory, i.e. a dry-run of `git merge`.
+//!
+//! A [`MergePreview`] can be retrieved via
+//! [`crate::Repository::merge_preview`].
+
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::{
+    diff::{git::error, Diff, DiffContent, DiffFile, FileMode},
+    Error, Repository,
+};
+
+/// The labels of the conflict markers, e.g. `<<<<<<< ours`.
+const OURS: &str = "ours";
+const THEIRS: &str = "theirs";
+
+/// The changes that merging a commit into another would make, including
+/// the files that would conflict.
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergePreview {
+    /// The diff from our side to the merge result.
+    diff: Diff,
+    /// The paths of the files that could not be merged cleanly.
+    conflicts: Vec<PathBuf>,
+}
+
+impl MergePreview {
+    /// Builds a `MergePreview` from the `diff` of the cleanly merged files
+    /// and the `conflicts` of the merge, whose files are added to the diff
+    /// with conflict markers.
+    pub(crate) fn new(
+        repo: &Repository,
+        mut diff: Diff,
+        conflicts: Vec<git2::IndexConflict>,
+    ) -> Result<Self, Error> {
+        let mut paths = Vec::with_capacity(conflicts.len());
+        for conflict in conflicts {
+            let Some(path) = conflict_path(&conflict) else {
+                continue;
+            };
+            insert_conflict(repo, &mut diff, &path, &conflict)?;
+            paths.push(path);
+        }
+        Ok(Self {
+            diff,
+            conflicts: paths,
+        })
+    }
+
+    /// The diff from our side to the merge result.
+    ///
+    /// Conflicting files are listed after the cleanly merged ones. Files
+    /// that were changed on both sides contain the conflict markers, and
+    /// files that were deleted on one side are kept as of the other side.
+    pub fn diff(&self) -> &Diff {
+        &self.diff
+    }
+
+    /// Returns the owned diff of this preview.
+    pub fn into_diff(self) -> Diff {
+        self.diff
+    }
+
+    /// Returns an iterator of the paths of the conflicting files.
+    pub fn conflicts(&self) -> impl Iterator<Item = &Path> {
+        self.conflicts.iter().map(PathBuf::as_path)
+    }
+
+    /// Whether the merge would succeed without any conflicts.
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// The path of a conflicting file, preferring our side.
+pub(crate) fn conflict_path(conflict: &git2::IndexConflict) -> Option<PathBuf> {
+    [&conflict.our, &conflict.their, &conflict.ancestor]
+        .into_iter()
+        .flatten()
+        .next()
+        .map(|entry| PathBuf::from(String::from_utf8_lossy(&entry.path).into_owned()))
+}
+
+/// Inserts the merge result of a conflicting file into `diff`.
+///
+/// Binary files are skipped, since they cannot carry conflict markers.
+fn insert_conflict(
+    repo: &Repository,
+    diff: &mut Diff,
+    path: &Path,
+    conflict: &git2::IndexConflict,
+) -> Result<(), Error> {
+    let blob = |entry: &Option<git2::IndexEntry>| {
+        entry
+            .as_ref()
+            .map(|entry| repo.find_blob(entry.id.into()))
+            .transpose()
+    };
+    let ancestor = blob(&conflict.ancestor)?;
+    let ours = blob(&conflict.our)?;
+    let theirs = blob(&conflict.their)?;
+    if [&ancestor, &ours, &theirs]
+        .into_iter()
+        .flatten()
+        .any(|blob| blob.is_binary())
+    {
+        return Ok(());
+    }
+
+    match (&conflict.our, ours, &conflict.their, theirs) {
+        // Changed on both sides, so the result has conflict markers.
+        (Some(our), Some(ours), Some(their), Some(theirs)) => {
+            let input = |content, mode| {
+                let mut input = git2::MergeFileInput::new();
+                input.content(content).path(path).mode(mode);
+                input
+            };
+            let mut opts = git2::MergeFileOptions::new();
+            opts.our_label(OURS).their_label(THEIRS);
+            let merged = git2::merge_file(
+                &input(ancestor.as_ref().map_or(&[], |a| a.content()), None),
+                &input(ours.content(), Some(file_mode(our.mode)?.into())),
+                &input(theirs.content(), Some(file_mode(their.mode)?.into())),
+                Some(&mut opts),
+            )?;
+
+            let patch = git2::Patch::from_blob_and_buffer(
+                &ours,
+                Some(path),
+                merged.content(),
+                Some(path),
+                None,
+            )?;
+            let old = DiffFile {
+                oid: our.id.into(),
+                mode: file_mode(our.mode)?,
+            };
+            let new = DiffFile {
+                oid: git2::Oid::hash_object(git2::ObjectType::Blob, merged.content())?.into(),
+                mode: file_mode(merged.mode())?,
+            };
+            let content = DiffContent::try_from(patch).map_err(error::Diff::from)?;
+            diff.insert_modified(path.to_path_buf(), content, old, new);
+        }
+        // Deleted on our side, so their side is kept.
+        (None, None, Some(their), Some(theirs)) => {
+            let patch = git2::Patch::from_buffers(&[], None, theirs.content(), Some(path), None)?;
+            let new = DiffFile {
+                oid: their.id.into(),
+                mode: file_mode(their.mode)?,
+            };
+            let content = DiffContent::try_from(patch).map_err(error::Diff::from)?;
+            diff.insert_added(path.to_path_buf(), content, new);
+        }
+        // Deleted on their side, so our side is kept as is.
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Converts the raw `mode` of an index entry or merge result.
+fn file_mode(mode: u32) -> Result<FileMode, error::Diff> {
+    let mode = [
+        git2::FileMode::Blob,
+        git2::FileMode::BlobExecutable,
+        git2::FileMode::Link,
+        git2::FileMode::Commit,
+        git2::FileMode::Tree,
+    ]
+    .into_iter()
+    .find(|m| u32::from(*m) == mode)
+    .unwrap_or(git2::FileMode::Unreadable);
+    Ok(FileMode::try_from(mode)?)
+}
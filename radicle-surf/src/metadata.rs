@@ -0,0 +1,282 @@
+//! Discovery of the files describing a repository, i.e. its README,
+//! licenses, contribution guidelines and code owners, in the manner of the
+//! landing pages of code forges.
+//!
+//! [`Metadata`] can be retrieved via [`crate::Repository::metadata`].
+
+use std::path::{Path, PathBuf};
+
+use radicle_git_ext::Oid;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::{Error, Repository};
+
+/// The extensions of README files, in order of preference. Files with other
+/// extensions come last.
+const README_EXTENSIONS: &[&str] = &[
+    "md", "markdown", "mdown", "mkdn", "rst", "adoc", "asciidoc", "org", "txt", "",
+];
+
+/// The names of license files, without extension. Names starting with
+/// `LICENSE-` or `LICENCE-`, e.g. `LICENSE-MIT`, are license files as well.
+const LICENSE_NAMES: &[&str] = &["license", "licence", "copying", "unlicense"];
+
+/// The extensions of license and contribution guideline files.
+const TEXT_EXTENSIONS: &[&str] = &["", "md", "markdown", "rst", "adoc", "txt"];
+
+/// The directories, relative to the repository root, that are searched for
+/// files missing from the root directory, in order.
+const ROOT_DIRECTORIES: &[&str] = &[".github", "docs"];
+
+/// The locations of the `CODEOWNERS` file, in order of precedence.
+pub(crate) const CODEOWNERS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// The licenses that are detected from their text, along with phrases that
+/// all occur in the normalized text of the license. Licenses that contain
+/// the phrases of others come first.
+const LICENSES: &[(&str, &[&str])] = &[
+    ("Apache-2.0", &["apache license", "version 2 0"]),
+    ("AGPL-3.0", &["gnu affero general public license version 3"]),
+    ("LGPL-3.0", &["gnu lesser general public license version 3"]),
+    ("LGPL-2.1", &["gnu lesser general public license version 2 1"]),
+    ("GPL-3.0", &["gnu general public license version 3"]),
+    ("GPL-2.0", &["gnu general public license version 2"]),
+    ("MPL-2.0", &["mozilla public license version 2 0"]),
+    ("EPL-2.0", &["eclipse public license v 2 0"]),
+    ("BSL-1.0", &["boost software license version 1 0"]),
+    ("BlueOak-1.0.0", &["blue oak model license", "version 1 0 0"]),
+    ("CC0-1.0", &["cc0 1 0 universal"]),
+    (
+        "Unlicense",
+        &["this is free and unencumbered software released into the public domain"],
+    ),
+    (
+        "BSD-3-Clause",
+        &[
+            "redistribution and use in source and binary forms",
+            "neither the name of",
+        ],
+    ),
+    (
+        "BSD-2-Clause",
+        &["redistribution and use in source and binary forms"],
+    ),
+    (
+        "ISC",
+        &[
+            "permission to use copy modify and or distribute this software for any purpose with or without fee is hereby granted",
+            "provided that the above copyright notice and this permission notice appear in all copies",
+        ],
+    ),
+    (
+        "0BSD",
+        &["permission to use copy modify and or distribute this software for any purpose with or without fee is hereby granted"],
+    ),
+    (
+        "MIT",
+        &["permission is hereby granted free of charge to any person obtaining a copy"],
+    ),
+    (
+        "Zlib",
+        &[
+            "this software is provided as is without any express or implied warranty",
+            "altered source versions must be plainly marked as such",
+        ],
+    ),
+];
+
+/// The files describing a directory of a repository.
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Metadata {
+    /// The README of the directory.
+    pub readme: Option<MetadataFile>,
+    /// The license files of the directory, ordered by path.
+    pub licenses: Vec<License>,
+    /// The contribution guidelines of the directory.
+    pub contributing: Option<MetadataFile>,
+    /// The `CODEOWNERS` file of the repository.
+    pub codeowners: Option<MetadataFile>,
+}
+
+/// A file describing a repository.
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MetadataFile {
+    /// The path of the file, relative to the repository root.
+    pub path: PathBuf,
+    /// The blob id of the file.
+    pub id: Oid,
+}
+
+/// A license file.
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct License {
+    /// The path of the file, relative to the repository root.
+    pub path: PathBuf,
+    /// The blob id of the file.
+    pub id: Oid,
+    /// The SPDX identifier of the license, if it was recognized, e.g.
+    /// `MIT`.
+    ///
+    /// Identifiers given by an `SPDX-License-Identifier:` line are taken as
+    /// is. Otherwise, the identifiers of GNU licenses are reported without
+    /// an `-only` or `-or-later` suffix, since their text is the same.
+    pub spdx: Option<String>,
+}
+
+/// Returns the [`Metadata`] of the directory at `path` of the tree `root`,
+/// with the tree `dir`.
+pub(crate) fn discover(
+    repo: &Repository,
+    root: &git2::Tree,
+    dir: &git2::Tree,
+    path: &Path,
+) -> Result<Metadata, Error> {
+    let mut files = blobs(dir, path);
+    // The files of the root directory may also be in one of the
+    // `ROOT_DIRECTORIES`.
+    if path.as_os_str().is_empty() {
+        for name in ROOT_DIRECTORIES {
+            let entry = match root.get_path(Path::new(name)) {
+                Ok(entry) if entry.kind() == Some(git2::ObjectType::Tree) => entry,
+                _ => continue,
+            };
+            files.extend(blobs(&repo.find_tree(entry.id().into())?, Path::new(name)));
+        }
+    }
+
+    let readme = files
+        .iter()
+        .filter_map(|file| readme_rank(&file.path).map(|rank| (rank, file)))
+        .min_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, file)| file.clone());
+    let contributing = files
+        .iter()
+        .find(|file| matches_name(&file.path, |stem| stem == "contributing"))
+        .cloned();
+
+    let mut licenses = Vec::new();
+    for file in files.iter().filter(|file| file.path.parent() == Some(path)) {
+        if !matches_name(&file.path, |stem| {
+            LICENSE_NAMES.contains(&stem)
+                || stem.starts_with("license-")
+                || stem.starts_with("licence-")
+        }) {
+            continue;
+        }
+        let blob = repo.find_blob(file.id)?;
+        licenses.push(License {
+            path: file.path.clone(),
+            id: file.id,
+            spdx: spdx(&String::from_utf8_lossy(blob.content())),
+        });
+    }
+    licenses.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let codeowners = CODEOWNERS.iter().find_map(|path| {
+        let entry = root.get_path(Path::new(path)).ok()?;
+        (entry.kind() == Some(git2::ObjectType::Blob)).then(|| MetadataFile {
+            path: PathBuf::from(path),
+            id: entry.id().into(),
+        })
+    });
+
+    Ok(Metadata {
+        readme,
+        licenses,
+        contributing,
+        codeowners,
+    })
+}
+
+/// Returns the files directly within `tree`, whose path is `path`, in the
+/// order of the tree.
+fn blobs(tree: &git2::Tree, path: &Path) -> Vec<MetadataFile> {
+    tree.iter()
+        .filter(|entry| entry.kind() == Some(git2::ObjectType::Blob))
+        .filter_map(|entry| {
+            Some(MetadataFile {
+                path: path.join(entry.name().ok()?),
+                id: entry.id().into(),
+            })
+        })
+        .collect()
+}
+
+/// Splits the file name of `path` into its lower case stem and extension,
+/// where the extension is empty if there is none.
+fn split_name(path: &Path) -> Option<(String, String)> {
+    let name = path.file_name()?.to_str()?.to_lowercase();
+    Some(match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem.to_owned(), ext.to_owned()),
+        _ => (name, String::new()),
+    })
+}
+
+/// Whether `path` has one of the `TEXT_EXTENSIONS` and a stem matching
+/// `stem`.
+fn matches_name(path: &Path, stem: impl Fn(&str) -> bool) -> bool {
+    split_name(path).is_some_and(|(s, ext)| stem(&s) && TEXT_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// The rank of a README file, where lower is preferred, or `None` if `path`
+/// is not a README.
+///
+/// READMEs are ranked by location, i.e. the directory itself first, then by
+/// their extension, preferring upper case names.
+fn readme_rank(path: &Path) -> Option<(usize, usize, bool, &Path)> {
+    let (stem, ext) = split_name(path)?;
+    if stem != "readme" {
+        return None;
+    }
+    let location = ROOT_DIRECTORIES
+        .iter()
+        .position(|dir| path.parent() == Some(Path::new(dir)))
+        .map_or(0, |i| i + 1);
+    let ext = README_EXTENSIONS
+        .iter()
+        .position(|e| *e == ext)
+        .unwrap_or(README_EXTENSIONS.len());
+    let upper = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with("README"));
+    Some((location, ext, !upper, path))
+}
+
+/// Returns the SPDX identifier of the license `text`, if it is recognized.
+fn spdx(text: &str) -> Option<String> {
+    const IDENTIFIER: &str = "SPDX-License-Identifier:";
+    if let Some(id) = text.lines().find_map(|line| {
+        let (_, id) = line.split_once(IDENTIFIER)?;
+        // The identifier may be followed by the end of a comment.
+        let id = id.trim().trim_end_matches("*/").trim_end_matches("-->");
+        Some(id.trim())
+    }) {
+        return Some(id.to_owned());
+    }
+
+    let text = normalize(text);
+    LICENSES
+        .iter()
+        .find(|(_, phrases)| phrases.iter().all(|phrase| text.contains(phrase)))
+        .map(|(id, _)| (*id).to_owned())
+}
+
+/// Lower cases `text` and replaces each run of non-alphanumeric characters
+/// by a single space, so that formatting does not affect matching.
+fn normalize(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            normalized.extend(c.to_lowercase());
+        } else if !normalized.ends_with(' ') {
+            normalized.push(' ');
+        }
+    }
+    normalized
+}
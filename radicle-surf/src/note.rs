@@ -0,0 +1,104 @@
+use std::{convert::TryFrom, str};
+
+use git_ext::{
+    ref_format::{component, lit, Qualified, RefStr, RefString},
+    Oid,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{refs::refstr_join, Author};
+
+/// A [`Git note`][git-notes] attached to an object, e.g. a review
+/// annotation of a commit.
+///
+/// [git-notes]: https://git-scm.com/docs/git-notes
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Note {
+    /// The Object ID of the blob holding the note.
+    pub id: Oid,
+    /// The Object ID of the object the note is attached to.
+    pub target: Oid,
+    /// The short name of the notes reference holding the note, e.g.
+    /// `commits` for `refs/notes/commits`.
+    pub name: RefString,
+    /// The author of the commit that last changed the note.
+    pub author: Author,
+    /// The committer of the commit that last changed the note.
+    pub committer: Author,
+    /// The message of the note.
+    pub message: String,
+}
+
+impl Note {
+    /// Return the fully qualified refname of the notes reference holding
+    /// this `Note`, e.g. `refs/notes/commits`.
+    pub fn refname<'a>(&'a self) -> Qualified<'a> {
+        lit::refs_notes(&self.name).into()
+    }
+
+    pub(crate) fn new(
+        name: RefString,
+        target: Oid,
+        note: &git2::Note,
+    ) -> Result<Self, error::Note> {
+        Ok(Self {
+            id: note.id().into(),
+            target,
+            name,
+            author: Author::try_from(note.author())?,
+            committer: Author::try_from(note.committer())?,
+            message: str::from_utf8(note.message_bytes())?.into(),
+        })
+    }
+}
+
+pub mod error {
+    use std::str;
+
+    use radicle_git_ext::ref_format::{self, RefString};
+    use thiserror::Error;
+
+    #[derive(Debug, Error)]
+    pub enum Note {
+        #[error(transparent)]
+        Git(#[from] git2::Error),
+        #[error("the refname '{0}' did not begin with 'refs/notes'")]
+        NotNotes(RefString),
+        #[error(transparent)]
+        Utf8(#[from] str::Utf8Error),
+    }
+
+    #[derive(Debug, Error)]
+    pub enum FromReference {
+        #[error(transparent)]
+        Git(#[from] git2::Error),
+        #[error("the refname '{0}' is not a qualified refname")]
+        NotQualified(String),
+        #[error("the refname '{0}' did not begin with 'refs/notes'")]
+        NotNotes(RefString),
+        #[error(transparent)]
+        RefFormat(#[from] ref_format::Error),
+        #[error(transparent)]
+        Utf8(#[from] str::Utf8Error),
+    }
+}
+
+/// Returns the short name of the notes reference `name`, e.g. `commits` for
+/// `refs/notes/commits`.
+pub(crate) fn short_name(name: &Qualified) -> Option<RefString> {
+    let (_refs, notes, c, cs) = name.non_empty_components();
+    (notes == component::NOTES).then(|| refstr_join(c, cs))
+}
+
+pub(crate) fn reference_name(
+    reference: &git2::Reference,
+) -> Result<RefString, error::FromReference> {
+    let name = str::from_utf8(reference.name_bytes())?;
+    let name = RefStr::try_from_str(name)?
+        .qualified()
+        .ok_or_else(|| error::FromReference::NotQualified(name.to_string()))?;
+    short_name(&name).ok_or_else(|| error::FromReference::NotNotes(name.into()))
+}
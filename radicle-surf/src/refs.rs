@@ -8,7 +8,7 @@ use std::{
 
 use git_ext::ref_format::{self, lit, name::Components, Component, Qualified, RefString};
 
-use crate::{tag, Branch, Namespace, Tag};
+use crate::{note, tag, Branch, Namespace, Tag};
 
 /// Iterator over [`Tag`]s.
 #[derive(Default)]
@@ -77,6 +77,43 @@ impl Iterator for TagNames<'_> {
     }
 }
 
+/// Iterator over the [`Qualified`] names of notes references, e.g.
+/// `refs/notes/commits`.
+#[derive(Default)]
+pub struct NoteRefs<'a> {
+    references: Vec<git2::References<'a>>,
+    current: usize,
+}
+
+impl<'a> NoteRefs<'a> {
+    pub(super) fn push(&mut self, references: git2::References<'a>) {
+        self.references.push(references)
+    }
+}
+
+impl Iterator for NoteRefs<'_> {
+    type Item = Result<Qualified<'static>, error::Note>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.current < self.references.len() {
+            match self.references.get_mut(self.current) {
+                Some(refs) => match refs.next() {
+                    Some(res) => {
+                        return Some(res.map_err(error::Note::from).and_then(|r| {
+                            note::reference_name(&r)
+                                .map(|name| lit::refs_notes(name).into())
+                                .map_err(error::Note::from)
+                        }))
+                    }
+                    None => self.current += 1,
+                },
+                None => break,
+            }
+        }
+        None
+    }
+}
+
 /// Iterator over [`Branch`]es.
 #[derive(Default)]
 pub struct Branches<'a> {
@@ -210,7 +247,7 @@ pub mod error {
     use radicle_git_ext::ref_format::{self, RefString};
     use thiserror::Error;
 
-    use crate::{branch, tag};
+    use crate::{branch, note, tag};
 
     #[derive(Debug, Error)]
     pub enum Branch {
@@ -232,6 +269,14 @@ pub mod error {
         Utf8(#[from] str::Utf8Error),
     }
 
+    #[derive(Debug, Error)]
+    pub enum Note {
+        #[error(transparent)]
+        Git(#[from] git2::Error),
+        #[error(transparent)]
+        Note(#[from] note::error::FromReference),
+    }
+
     #[derive(Debug, Error)]
     pub enum Tag {
         #[error(transparent)]
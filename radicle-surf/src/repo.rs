@@ -1,32 +1,51 @@
 use std::{
     collections::BTreeSet,
     convert::TryFrom,
+    io,
     path::{Path, PathBuf},
     str,
 };
 
 use git_ext::{
+    is_not_found_err,
     ref_format::{refspec::QualifiedPattern, Qualified, RefStr, RefString},
     Oid,
 };
+use radicle_std_ext::result::ResultExt as _;
 
 use crate::{
+    archive::{self, ArchiveOptions, Format},
+    blame::Blame,
     blob::{Blob, BlobRef},
-    diff::{Diff, FileDiff},
+    codeowners::CodeOwners,
+    diff::{combined::CombinedDiff, Diff, DiffOptions, DiffStream, FileDiff},
     fs::{Directory, File, FileContent},
-    refs::{BranchNames, Branches, Categories, Namespaces, TagNames, Tags},
+    language::{self, LanguageStats},
+    merge::{self, MergePreview},
+    metadata::{self, Metadata},
+    note,
+    refs::{BranchNames, Branches, Categories, Namespaces, NoteRefs, TagNames, Tags},
+    search::{self, Match, PathMatch, SearchOptions},
+    signature::{Verification, Verifier},
+    stats::{self, Contributor},
     tree::{Entry, Tree},
-    Branch, Commit, Error, Glob, History, Namespace, Revision, Signature, Stats, Tag, ToCommit,
+    Branch, Commit, Error, Glob, History, Mailmap, Namespace, Note, Revision, Signature, Stats,
+    Tag, ToCommit,
 };
 
 /// Enumeration of errors that can occur in repo operations.
 pub mod error {
+    use git_ext::Oid;
     use std::path::PathBuf;
     use thiserror::Error;
 
     #[derive(Debug, Error)]
     #[non_exhaustive]
     pub enum Repo {
+        #[error("no merge base found for {0} and {1}")]
+        NoMergeBase(Oid, Oid),
+        #[error("commit {0} has no parent at index {1}")]
+        ParentNotFound(Oid, usize),
         #[error("path not found for: {0}")]
         PathNotFound(PathBuf),
     }
@@ -125,6 +144,49 @@ impl Repository {
         Ok(self.tags(filter)?.names())
     }
 
+    /// Returns an iterator of the names of the notes references that match
+    /// `pattern`, e.g. `refs/notes/commits`.
+    pub fn note_refs<'a>(&'a self, pattern: &Glob<Note>) -> Result<NoteRefs<'a>, Error> {
+        let mut refs = NoteRefs::default();
+        for glob in pattern.globs() {
+            let namespaced = self.namespaced_pattern(glob)?;
+            let references = self.inner.references_glob(&namespaced)?;
+            refs.push(references);
+        }
+        Ok(refs)
+    }
+
+    /// Returns the [`Note`] attached to `rev` in the notes reference
+    /// `refname`, e.g. `refs/notes/commits`, or `None` if there is none.
+    pub fn note<R: Revision>(&self, rev: R, refname: &Qualified) -> Result<Option<Note>, Error> {
+        let name = note::short_name(refname)
+            .ok_or_else(|| note::error::Note::NotNotes(refname.to_ref_string()))?;
+        let target = self.object_id(&rev)?;
+        let namespaced = self.namespaced_refname(refname)?;
+        match self
+            .inner
+            .find_note(Some(namespaced.as_str()), target.into())
+        {
+            Ok(note) => Ok(Some(Note::new(name, target, &note)?)),
+            Err(err) if is_not_found_err(&err) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Returns the [`Note`]s attached to `rev` in the notes references that
+    /// match `pattern`, in the order of [`Repository::note_refs`].
+    ///
+    /// The notes can be included in the serialization of a [`Commit`] by
+    /// assigning them to [`Commit::notes`].
+    pub fn notes<R: Revision>(&self, rev: R, pattern: &Glob<Note>) -> Result<Vec<Note>, Error> {
+        let target = self.object_id(&rev)?;
+        let mut notes = Vec::new();
+        for refname in self.note_refs(pattern)? {
+            notes.extend(self.note(target, &refname?)?);
+        }
+        Ok(notes)
+    }
+
     pub fn categories<'a>(
         &'a self,
         pattern: &Glob<Qualified<'_>>,
@@ -158,9 +220,19 @@ impl Repository {
 
     /// Get the [`Diff`] between two commits.
     pub fn diff(&self, from: impl Revision, to: impl Revision) -> Result<Diff, Error> {
+        self.diff_with(from, to, &DiffOptions::default())
+    }
+
+    /// Get the [`Diff`] between two commits, computed with `options`.
+    pub fn diff_with(
+        &self,
+        from: impl Revision,
+        to: impl Revision,
+        options: &DiffOptions,
+    ) -> Result<Diff, Error> {
         let from_commit = self.find_commit(self.object_id(&from)?)?;
         let to_commit = self.find_commit(self.object_id(&to)?)?;
-        self.diff_commits(None, Some(&from_commit), &to_commit)
+        self.diff_commits(&[], Some(&from_commit), &to_commit, options)
             .and_then(|diff| Diff::try_from(diff).map_err(Error::from))
     }
 
@@ -169,16 +241,136 @@ impl Repository {
     /// If the `commit` has a parent, then it the diff will be a
     /// comparison between itself and that parent. Otherwise, the left
     /// hand side of the diff will pass nothing.
+    ///
+    /// A merge commit is compared to its first parent, see
+    /// [`Repository::diff_commit_parent`] and [`Repository::diff_combined`]
+    /// for the other parents.
     pub fn diff_commit(&self, commit: impl ToCommit) -> Result<Diff, Error> {
+        self.diff_commit_with(commit, &DiffOptions::default())
+    }
+
+    /// Get the [`Diff`] of a `commit`, computed with `options`.
+    ///
+    /// See [`Repository::diff_commit`].
+    pub fn diff_commit_with(
+        &self,
+        commit: impl ToCommit,
+        options: &DiffOptions,
+    ) -> Result<Diff, Error> {
         let commit = commit
             .to_commit(self)
             .map_err(|err| Error::ToCommit(err.into()))?;
         match commit.parents.first() {
-            Some(parent) => self.diff(*parent, commit.id),
-            None => self.initial_diff(commit.id),
+            Some(parent) => self.diff_with(*parent, commit.id, options),
+            None => self.initial_diff(commit.id, options),
         }
     }
 
+    /// Get the [`Diff`] of a `commit` against its parent at `index`, in the
+    /// order of [`crate::Commit::parents`].
+    ///
+    /// This allows reviewing a merge commit against any of its parents,
+    /// while [`Repository::diff_commit`] always uses the first one.
+    ///
+    /// # Errors
+    ///
+    /// * `ParentNotFound` if the `commit` has no parent at `index`.
+    pub fn diff_commit_parent(&self, commit: impl ToCommit, index: usize) -> Result<Diff, Error> {
+        self.diff_commit_parent_with(commit, index, &DiffOptions::default())
+    }
+
+    /// Get the [`Diff`] of a `commit` against its parent at `index`,
+    /// computed with `options`.
+    ///
+    /// See [`Repository::diff_commit_parent`].
+    pub fn diff_commit_parent_with(
+        &self,
+        commit: impl ToCommit,
+        index: usize,
+        options: &DiffOptions,
+    ) -> Result<Diff, Error> {
+        let commit = commit
+            .to_commit(self)
+            .map_err(|err| Error::ToCommit(err.into()))?;
+        let parent = commit
+            .parents
+            .get(index)
+            .ok_or(error::Repo::ParentNotFound(commit.id, index))?;
+        self.diff_with(*parent, commit.id, options)
+    }
+
+    /// Get the [`CombinedDiff`] of a `commit` against all of its parents,
+    /// i.e. `git diff --cc`.
+    ///
+    /// For a merge commit, this only contains the changes that differ from
+    /// every parent, such as conflict resolutions. For other commits, it is
+    /// equivalent to [`Repository::diff_commit`] with renames disabled.
+    pub fn diff_combined(&self, commit: impl ToCommit) -> Result<CombinedDiff, Error> {
+        let commit = commit
+            .to_commit(self)
+            .map_err(|err| Error::ToCommit(err.into()))?;
+        CombinedDiff::new(self, &self.find_commit(commit.id)?)
+    }
+
+    /// Get a [`DiffStream`] over the files changed between two commits.
+    ///
+    /// In contrast to [`Repository::diff`], the changes of each file are
+    /// only loaded when it is yielded, which keeps memory usage low for
+    /// large changesets.
+    pub fn diff_stream(
+        &self,
+        from: impl Revision,
+        to: impl Revision,
+    ) -> Result<DiffStream<'_>, Error> {
+        self.diff_stream_with(from, to, &DiffOptions::default())
+    }
+
+    /// Get a [`DiffStream`] over the files changed between two commits,
+    /// computed with `options`.
+    ///
+    /// See [`Repository::diff_stream`].
+    pub fn diff_stream_with(
+        &self,
+        from: impl Revision,
+        to: impl Revision,
+        options: &DiffOptions,
+    ) -> Result<DiffStream<'_>, Error> {
+        let from_commit = self.find_commit(self.object_id(&from)?)?;
+        let to_commit = self.find_commit(self.object_id(&to)?)?;
+        let diff = self.diff_commits(&[], Some(&from_commit), &to_commit, options)?;
+        Ok(DiffStream::new(diff, self.odb()?))
+    }
+
+    /// Get a [`DiffStream`] over the files changed by a `commit`, compared
+    /// to its first parent, if any.
+    ///
+    /// See [`Repository::diff_commit`] and [`Repository::diff_stream`].
+    pub fn diff_commit_stream(&self, commit: impl ToCommit) -> Result<DiffStream<'_>, Error> {
+        self.diff_commit_stream_with(commit, &DiffOptions::default())
+    }
+
+    /// Get a [`DiffStream`] over the files changed by a `commit`, computed
+    /// with `options`.
+    ///
+    /// See [`Repository::diff_commit_stream`].
+    pub fn diff_commit_stream_with(
+        &self,
+        commit: impl ToCommit,
+        options: &DiffOptions,
+    ) -> Result<DiffStream<'_>, Error> {
+        let commit = commit
+            .to_commit(self)
+            .map_err(|err| Error::ToCommit(err.into()))?;
+        let parent = commit
+            .parents
+            .first()
+            .map(|parent| self.find_commit(*parent))
+            .transpose()?;
+        let diff =
+            self.diff_commits(&[], parent.as_ref(), &self.find_commit(commit.id)?, options)?;
+        Ok(DiffStream::new(diff, self.odb()?))
+    }
+
     /// Get the [`FileDiff`] between two revisions for a file at `path`.
     ///
     /// If `path` is only a directory name, not a file, returns
@@ -188,11 +380,25 @@ impl Repository {
         path: &P,
         from: R,
         to: R,
+    ) -> Result<FileDiff, Error> {
+        self.diff_file_with(path, from, to, &DiffOptions::default())
+    }
+
+    /// Get the [`FileDiff`] between two revisions for a file at `path`,
+    /// computed with `options`.
+    ///
+    /// See [`Repository::diff_file`].
+    pub fn diff_file_with<P: AsRef<Path>, R: Revision>(
+        &self,
+        path: &P,
+        from: R,
+        to: R,
+        options: &DiffOptions,
     ) -> Result<FileDiff, Error> {
         let from_commit = self.find_commit(self.object_id(&from)?)?;
         let to_commit = self.find_commit(self.object_id(&to)?)?;
         let diff = self
-            .diff_commits(Some(path.as_ref()), Some(&from_commit), &to_commit)
+            .diff_commits(&[path.as_ref()], Some(&from_commit), &to_commit, options)
             .and_then(|diff| Diff::try_from(diff).map_err(Error::from))?;
         let file_diff = diff
             .into_files()
@@ -201,6 +407,141 @@ impl Repository {
         Ok(file_diff)
     }
 
+    /// Get the [`Diff`] of the changes staged in the index, compared to
+    /// `HEAD`, i.e. `git diff --cached`.
+    ///
+    /// If `HEAD` is unborn, all staged files are added.
+    pub fn diff_index(&self) -> Result<Diff, Error> {
+        self.diff_index_with(&DiffOptions::default())
+    }
+
+    /// Get the [`Diff`] of the changes staged in the index, computed with
+    /// `options`.
+    ///
+    /// See [`Repository::diff_index`].
+    pub fn diff_index_with(&self, options: &DiffOptions) -> Result<Diff, Error> {
+        let head = self.head_tree()?;
+        let mut opts = git2::DiffOptions::new();
+        options.apply(&mut opts);
+
+        let diff = self
+            .inner
+            .diff_tree_to_index(head.as_ref(), None, Some(&mut opts))?;
+        self.find_similar(diff, options)
+    }
+
+    /// Get the [`Diff`] of the changes in the working directory that are
+    /// not staged in the index, i.e. `git diff`.
+    ///
+    /// Untracked files are included as [`FileDiff::Added`], while ignored
+    /// files are not.
+    pub fn diff_workdir(&self) -> Result<Diff, Error> {
+        self.diff_workdir_with(&DiffOptions::default())
+    }
+
+    /// Get the [`Diff`] of the changes in the working directory that are
+    /// not staged in the index, computed with `options`.
+    ///
+    /// See [`Repository::diff_workdir`].
+    pub fn diff_workdir_with(&self, options: &DiffOptions) -> Result<Diff, Error> {
+        let diff = self
+            .inner
+            .diff_index_to_workdir(None, Some(&mut workdir_options(options)))?;
+        self.find_similar(diff, options)
+    }
+
+    /// Get the [`Diff`] between `rev` and the working directory, including
+    /// the changes staged in the index, i.e. `git diff <rev>`.
+    ///
+    /// Untracked files are included as [`FileDiff::Added`], while ignored
+    /// files are not.
+    pub fn diff_rev_to_workdir(&self, rev: impl Revision) -> Result<Diff, Error> {
+        self.diff_rev_to_workdir_with(rev, &DiffOptions::default())
+    }
+
+    /// Get the [`Diff`] between `rev` and the working directory, computed
+    /// with `options`.
+    ///
+    /// See [`Repository::diff_rev_to_workdir`].
+    pub fn diff_rev_to_workdir_with(
+        &self,
+        rev: impl Revision,
+        options: &DiffOptions,
+    ) -> Result<Diff, Error> {
+        let tree = self.find_commit(self.object_id(&rev)?)?.tree()?;
+        let diff = self
+            .inner
+            .diff_tree_to_workdir_with_index(Some(&tree), Some(&mut workdir_options(options)))?;
+        self.find_similar(diff, options)
+    }
+
+    /// Returns the best common ancestor of `a` and `b`, or `None` if they do
+    /// not share any history.
+    pub fn merge_base(&self, a: impl Revision, b: impl Revision) -> Result<Option<Oid>, Error> {
+        let a = self.object_id(&a)?;
+        let b = self.object_id(&b)?;
+        match self.inner.merge_base(a.into(), b.into()) {
+            Ok(base) => Ok(Some(base.into())),
+            Err(err) if is_not_found_err(&err) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Get the [`Diff`] between the merge base of `a` and `b`, and `b`,
+    /// i.e. `git diff a...b`. These are the changes that merging `b` into
+    /// `a` would bring in.
+    ///
+    /// # Errors
+    ///
+    /// * `NoMergeBase` if `a` and `b` do not share any history.
+    pub fn diff_merge_base(&self, a: impl Revision, b: impl Revision) -> Result<Diff, Error> {
+        let a = self.object_id(&a)?;
+        let b = self.object_id(&b)?;
+        let base = self
+            .merge_base(a, b)?
+            .ok_or(error::Repo::NoMergeBase(a, b))?;
+        self.diff(base, b)
+    }
+
+    /// Returns what merging `theirs` into `ours` would change, without
+    /// touching the index or working directory.
+    ///
+    /// The [`MergePreview`] holds the [`Diff`] from `ours` to the merge
+    /// result, where conflicting files contain conflict markers, and lists
+    /// the conflicting paths.
+    pub fn merge_preview(
+        &self,
+        ours: impl Revision,
+        theirs: impl Revision,
+    ) -> Result<MergePreview, Error> {
+        let ours = self.find_commit(self.object_id(&ours)?)?;
+        let theirs = self.find_commit(self.object_id(&theirs)?)?;
+        let mut index = self.inner.merge_commits(&ours, &theirs, None)?;
+
+        // Conflicting files are reset to our side, so that only the clean
+        // changes are diffed here. The conflicts are added by `MergePreview`.
+        let mut conflicts = index.conflicts()?.collect::<Result<Vec<_>, _>>()?;
+        for conflict in &mut conflicts {
+            let Some(path) = merge::conflict_path(conflict) else {
+                continue;
+            };
+            index.conflict_remove(&path)?;
+            if let Some(our) = &mut conflict.our {
+                our.flags &= !INDEX_STAGE_MASK;
+                index.add(our)?;
+            }
+        }
+
+        let options = DiffOptions::default();
+        let mut opts = git2::DiffOptions::new();
+        options.apply(&mut opts);
+        let diff =
+            self.inner
+                .diff_tree_to_index(Some(&ours.tree()?), Some(&index), Some(&mut opts))?;
+        let diff = self.find_similar(diff, &options)?;
+        MergePreview::new(self, diff, conflicts)
+    }
+
     /// Parse an [`Oid`] from the given string.
     pub fn oid(&self, oid: &str) -> Result<Oid, Error> {
         Ok(self.inner.revparse_single(oid)?.id().into())
@@ -259,6 +600,43 @@ impl Repository {
         ))
     }
 
+    /// Returns the [`Metadata`] of the directory at `path` in `commit`,
+    /// i.e. its README, licenses and contribution guidelines, along with
+    /// the `CODEOWNERS` file of the repository.
+    ///
+    /// For the root directory, i.e. an empty `path`, a README or
+    /// contribution guidelines missing from the directory are looked up in
+    /// the `.github` and `docs` directories, in this order. Among several
+    /// READMEs, Markdown is preferred over reStructuredText, AsciiDoc, Org,
+    /// plain text and other formats, in this order. The `CODEOWNERS` file
+    /// is looked up in `.github`, the root directory and `docs`.
+    pub fn metadata<C: ToCommit, P: AsRef<Path>>(
+        &self,
+        commit: C,
+        path: &P,
+    ) -> Result<Metadata, Error> {
+        let commit = commit
+            .to_commit(self)
+            .map_err(|e| Error::ToCommit(e.into()))?;
+        let root = self.find_commit(commit.id)?.tree()?;
+        let dir = self.find_tree(self.directory(commit.id, path)?.id())?;
+        metadata::discover(self, &root, &dir, path.as_ref())
+    }
+
+    /// Returns the [`CodeOwners`] of `rev`, parsed from the first
+    /// `CODEOWNERS` file found in `.github`, the root directory or `docs`,
+    /// or `None` if there is none.
+    pub fn codeowners(&self, rev: impl Revision) -> Result<Option<CodeOwners>, Error> {
+        let metadata = self.metadata(self.object_id(&rev)?, &"")?;
+        let Some(file) = metadata.codeowners else {
+            return Ok(None);
+        };
+        let blob = self.find_blob(file.id)?;
+        Ok(Some(CodeOwners::parse(&String::from_utf8_lossy(
+            blob.content(),
+        ))))
+    }
+
     /// Returns a [`Blob`] for `path` in `commit`.
     pub fn blob<'a, C: ToCommit, P: AsRef<Path>>(
         &'a self,
@@ -282,6 +660,77 @@ impl Repository {
         })
     }
 
+    /// Returns the [`Blame`] of the file at `path` in the history of `rev`.
+    ///
+    /// Each line of the file is attributed to the commit that last
+    /// changed it, following the file through renames. The authors and
+    /// committers are resolved using the mailmap of the repository.
+    pub fn blame<R, P>(&self, rev: R, path: &P) -> Result<Blame, Error>
+    where
+        R: Revision,
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let mut opts = git2::BlameOptions::new();
+        opts.newest_commit(self.object_id(&rev)?.into());
+        let blame = self
+            .inner
+            .blame_file(path, Some(&mut opts))
+            .or_matches::<Error, _, _>(is_not_found_err, || {
+                Err(error::Repo::PathNotFound(path.to_path_buf()).into())
+            })?;
+        Blame::new(path.to_path_buf(), blame, self, &self.mailmap()?)
+    }
+
+    /// Returns the files at `rev` whose paths fuzzily match `query`, best
+    /// matches first.
+    ///
+    /// See [`crate::search`] for how matches are scored.
+    pub fn search_paths(&self, rev: impl Revision, query: &str) -> Result<Vec<PathMatch>, Error> {
+        self.search_paths_with(rev, query, &SearchOptions::default())
+    }
+
+    /// Returns the files at `rev` whose paths fuzzily match `query`,
+    /// restricted by `options`.
+    ///
+    /// See [`Repository::search_paths`].
+    pub fn search_paths_with(
+        &self,
+        rev: impl Revision,
+        query: &str,
+        options: &SearchOptions,
+    ) -> Result<Vec<PathMatch>, Error> {
+        let tree = self.find_commit(self.object_id(&rev)?)?.tree()?;
+        Ok(search::paths(&tree, query, options)?)
+    }
+
+    /// Returns the lines of the files at `rev` that contain `pattern`,
+    /// ordered by path and line number, i.e. `git grep -F`.
+    ///
+    /// Binary files are skipped.
+    pub fn grep(&self, rev: impl Revision, pattern: &str) -> Result<Vec<Match>, Error> {
+        self.grep_with(rev, pattern, &SearchOptions::default())
+    }
+
+    /// Returns the lines of the files at `rev` that match `pattern`,
+    /// searched with `options`.
+    ///
+    /// See [`Repository::grep`].
+    ///
+    /// # Errors
+    ///
+    /// * `Regex` if the `options` enable regular expressions and `pattern`
+    ///   is not a valid one.
+    pub fn grep_with(
+        &self,
+        rev: impl Revision,
+        pattern: &str,
+        options: &SearchOptions,
+    ) -> Result<Vec<Match>, Error> {
+        let tree = self.find_commit(self.object_id(&rev)?)?.tree()?;
+        search::grep(self, &tree, pattern, options)
+    }
+
     /// Returns the last commit, if exists, for a `path` in the history of
     /// `rev`.
     pub fn last_commit<P, C>(&self, path: &P, rev: C) -> Result<Option<Commit>, Error>
@@ -306,12 +755,15 @@ impl Repository {
 
     /// Gets the [`Stats`] of this repository starting from the given
     /// `rev`.
+    ///
+    /// Contributors are counted by their name and email after resolving
+    /// the mailmap of the repository.
     pub fn stats_from<R>(&self, rev: &R) -> Result<Stats, Error>
     where
         R: Revision,
     {
         let branches = self.branches(Glob::all_heads())?.count();
-        let mut history = self.history(rev)?;
+        let mut history = self.history(rev)?.mailmap(self.mailmap()?)?;
         let (commits, contributors) = history.try_fold(
             (0, BTreeSet::new()),
             |(commits, mut contributors), commit| {
@@ -327,6 +779,66 @@ impl Repository {
         })
     }
 
+    /// Returns the [`Contributor`]s to the history of `rev`, i.e. the
+    /// distinct authors of its commits, ordered by their number of commits
+    /// and then by name.
+    ///
+    /// Authors are identified by their name and email after resolving the
+    /// mailmap of the repository, see [`Repository::mailmap`].
+    pub fn contributors(&self, rev: impl Revision) -> Result<Vec<Contributor>, Error> {
+        stats::contributors(self.history(&rev)?.mailmap(self.mailmap()?)?)
+    }
+
+    /// Returns the [`Mailmap`] of this repository, read from the
+    /// `.mailmap` file of the working directory, or of `HEAD` in a bare
+    /// repository, and the files set by the `mailmap.file` and
+    /// `mailmap.blob` configuration.
+    pub fn mailmap(&self) -> Result<Mailmap, Error> {
+        Ok(Mailmap::new(self.inner.mailmap()?))
+    }
+
+    /// Writes an archive of the tree of `rev` in the given `format` to
+    /// `writer`, where `prefix` is prepended to the path of every entry,
+    /// e.g. `project-1.0/`.
+    ///
+    /// See [`archive`] for the contents of the archive.
+    pub fn archive(
+        &self,
+        rev: impl Revision,
+        format: Format,
+        prefix: &str,
+        writer: impl io::Write,
+    ) -> Result<(), Error> {
+        self.archive_with(rev, &ArchiveOptions::new(format).prefix(prefix), writer)
+    }
+
+    /// Writes an archive of the tree of `rev` to `writer`, according to
+    /// `options`.
+    pub fn archive_with(
+        &self,
+        rev: impl Revision,
+        options: &ArchiveOptions,
+        writer: impl io::Write,
+    ) -> Result<(), Error> {
+        let commit = self.find_commit(self.object_id(&rev)?)?;
+        archive::write(self, &commit, options, writer)
+    }
+
+    /// Gets the [`LanguageStats`] of the files at `rev`, i.e. how many
+    /// files and bytes are written in each language.
+    ///
+    /// Languages are detected by file name, extension and shebang line,
+    /// unless overridden by the `linguist-language` attribute in a
+    /// `.gitattributes` file. Like GitHub's linguist, only programming and
+    /// markup languages are counted, and vendored, generated and
+    /// documentation files are skipped. The `linguist-vendored`,
+    /// `linguist-generated`, `linguist-documentation` and
+    /// `linguist-detectable` attributes override these rules.
+    pub fn language_stats(&self, rev: impl Revision) -> Result<LanguageStats, Error> {
+        let tree = self.find_commit(self.object_id(&rev)?)?.tree()?;
+        language::stats(self, &tree)
+    }
+
     // TODO(finto): I think this can be removed in favour of using
     // `source::Blob::new`
     /// Retrieves the file with `path` in this commit.
@@ -385,11 +897,68 @@ impl Repository {
         }
     }
 
+    /// Verifies the signature of `commit` against the keys of the
+    /// `verifier`, i.e. `git verify-commit`.
+    ///
+    /// Returns `None` if the commit is not signed.
+    pub fn verify_commit(
+        &self,
+        commit: impl ToCommit,
+        verifier: &Verifier,
+    ) -> Result<Option<Verification>, Error> {
+        let commit = commit
+            .to_commit(self)
+            .map_err(|e| Error::ToCommit(e.into()))?;
+        match self.inner.extract_signature(&commit.id, None) {
+            Err(error) if error.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+            Ok((signature, payload)) => Ok(Some(verifier.verify(
+                &signature,
+                &payload,
+                &commit.committer.time,
+            ))),
+        }
+    }
+
     /// Returns the history with the `head` commit.
     pub fn history<'a, C: ToCommit>(&'a self, head: C) -> Result<History<'a>, Error> {
         History::new(self, head)
     }
 
+    /// Returns the history of the commits reachable from `to`, but not from
+    /// `from`, i.e. `from..to`.
+    pub fn history_range<'a, R, C>(&'a self, from: R, to: C) -> Result<History<'a>, Error>
+    where
+        R: Revision,
+        C: ToCommit,
+    {
+        History::range(self, from, to)
+    }
+
+    /// Returns the history of the commits reachable from either `a` or
+    /// `b`, but not from both, i.e. `a...b`.
+    ///
+    /// The head of the returned history is `a`.
+    pub fn history_symmetric<'a, C, R>(&'a self, a: C, b: R) -> Result<History<'a>, Error>
+    where
+        C: ToCommit,
+        R: Revision,
+    {
+        History::symmetric(self, a, b)
+    }
+
+    /// Returns the number of commits reachable from `to`, but not from
+    /// `from`, i.e. the length of the history of `from..to`.
+    ///
+    /// This is cheaper than counting the commits of
+    /// [`Repository::history_range`], since no commit is loaded.
+    pub fn count_commits(&self, from: impl Revision, to: impl Revision) -> Result<usize, Error> {
+        let from = self.object_id(&from)?;
+        let to = self.object_id(&to)?;
+        let (ahead, _behind) = self.inner.graph_ahead_behind(to.into(), from.into())?;
+        Ok(ahead)
+    }
+
     /// Lists branches that are reachable from `rev`.
     pub fn revision_branches(
         &self,
@@ -438,6 +1007,10 @@ impl Repository {
         self.inner.find_commit(oid.into())
     }
 
+    pub(crate) fn odb(&self) -> Result<git2::Odb<'_>, git2::Error> {
+        self.inner.odb()
+    }
+
     pub(crate) fn find_tree(&self, oid: Oid) -> Result<git2::Tree<'_>, git2::Error> {
         self.inner.find_tree(oid.into())
     }
@@ -455,14 +1028,24 @@ impl Repository {
         self.inner.revwalk()
     }
 
+    /// Returns all the merge bases of `a` and `b`, which is empty if they
+    /// do not share any history.
+    pub(crate) fn merge_bases(&self, a: Oid, b: Oid) -> Result<Vec<Oid>, Error> {
+        match self.inner.merge_bases(a.into(), b.into()) {
+            Ok(bases) => Ok(bases.iter().map(|oid| Oid::from(*oid)).collect()),
+            Err(err) if is_not_found_err(&err) => Ok(vec![]),
+            Err(err) => Err(err.into()),
+        }
+    }
+
     pub(super) fn object_id<R: Revision>(&self, r: &R) -> Result<Oid, Error> {
         r.object_id(self).map_err(|err| Error::Revision(err.into()))
     }
 
     /// Get the [`Diff`] of a commit with no parents.
-    fn initial_diff<R: Revision>(&self, rev: R) -> Result<Diff, Error> {
+    fn initial_diff<R: Revision>(&self, rev: R, options: &DiffOptions) -> Result<Diff, Error> {
         let commit = self.find_commit(self.object_id(&rev)?)?;
-        self.diff_commits(None, None, &commit)
+        self.diff_commits(&[], None, &commit, options)
             .and_then(|diff| Diff::try_from(diff).map_err(Error::from))
     }
 
@@ -474,22 +1057,56 @@ impl Repository {
         Ok(other == git2_oid || is_descendant)
     }
 
+    /// Returns `true` if `commit` touches any of the `paths`, compared to
+    /// its first parent.
     pub(crate) fn diff_commit_and_parents<P>(
         &self,
-        path: &P,
+        paths: &[P],
         commit: &git2::Commit,
-    ) -> Result<Option<PathBuf>, Error>
+    ) -> Result<bool, Error>
     where
         P: AsRef<Path>,
     {
         let mut parents = commit.parents();
+        let paths = paths.iter().map(|p| p.as_ref()).collect::<Vec<_>>();
 
-        let diff = self.diff_commits(Some(path.as_ref()), parents.next().as_ref(), commit)?;
-        if let Some(_delta) = diff.deltas().next() {
-            Ok(Some(path.as_ref().to_path_buf()))
-        } else {
-            Ok(None)
-        }
+        let diff = self.diff_commits(
+            &paths,
+            parents.next().as_ref(),
+            commit,
+            &DiffOptions::default(),
+        )?;
+        Ok(diff.deltas().next().is_some())
+    }
+
+    /// Returns how `path` was changed in `commit`, compared to its first
+    /// parent, or `None` if it was not changed.
+    pub(crate) fn path_delta(
+        &self,
+        path: &Path,
+        commit: &git2::Commit,
+    ) -> Result<Option<git2::Delta>, Error> {
+        let parent = commit.parents().next();
+        let diff = self.diff_commits(&[path], parent.as_ref(), commit, &DiffOptions::default())?;
+        let delta = diff.deltas().next().map(|delta| delta.status());
+        Ok(delta)
+    }
+
+    /// Returns the path that `path` was renamed or copied from in `commit`,
+    /// compared to its first parent, if any.
+    pub(crate) fn rename_source(
+        &self,
+        path: &Path,
+        commit: &git2::Commit,
+    ) -> Result<Option<PathBuf>, Error> {
+        let parent = commit.parents().next();
+        let diff = self.diff_commits(&[], parent.as_ref(), commit, &DiffOptions::default())?;
+        let source = diff
+            .deltas()
+            .filter(|delta| matches!(delta.status(), git2::Delta::Renamed | git2::Delta::Copied))
+            .find(|delta| delta.new_file().path() == Some(path))
+            .and_then(|delta| delta.old_file().path().map(Path::to_path_buf));
+        Ok(source)
     }
 
     /// Create a diff with the difference between two tree objects.
@@ -502,18 +1119,22 @@ impl Repository {
     /// expected.
     ///
     /// Reference: <https://github.com/libgit2/libgit2/issues/6637>
-    fn diff_commits<'a>(
+    pub(crate) fn diff_commits<'a>(
         &'a self,
-        path: Option<&Path>,
+        paths: &[&Path],
         from: Option<&git2::Commit>,
         to: &git2::Commit,
+        options: &DiffOptions,
     ) -> Result<git2::Diff<'a>, Error> {
         let new_tree = to.tree()?;
         let old_tree = from.map_or(Ok(None), |c| c.tree().map(Some))?;
 
         let mut opts = git2::DiffOptions::new();
-        if let Some(path) = path {
-            opts.pathspec(path.to_string_lossy().to_string());
+        options.apply(&mut opts);
+        if !paths.is_empty() {
+            for path in paths {
+                opts.pathspec(path.to_string_lossy().to_string());
+            }
             opts.disable_pathspec_match(true);
             opts.skip_binary_check(false);
         }
@@ -522,15 +1143,34 @@ impl Repository {
             self.inner
                 .diff_tree_to_tree(old_tree.as_ref(), Some(&new_tree), Some(&mut opts))?;
 
-        // Detect renames by default.
-        let mut find_opts = git2::DiffFindOptions::new();
-        find_opts.renames(true);
-        find_opts.copies(true);
-        diff.find_similar(Some(&mut find_opts))?;
+        // Detect renames and copies, unless disabled by the `options`.
+        if let Some(mut find_opts) = options.find_options() {
+            diff.find_similar(Some(&mut find_opts))?;
+        }
 
         Ok(diff)
     }
 
+    /// Returns the tree of `HEAD`, or `None` if `HEAD` is unborn.
+    fn head_tree(&self) -> Result<Option<git2::Tree<'_>>, Error> {
+        match self.inner.head() {
+            Ok(head) => Ok(Some(head.peel_to_tree()?)),
+            Err(err) if err.code() == git2::ErrorCode::UnbornBranch || is_not_found_err(&err) => {
+                Ok(None)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Detects renames and copies in `diff`, unless disabled by the
+    /// `options`, and converts it into a [`Diff`].
+    fn find_similar(&self, mut diff: git2::Diff<'_>, options: &DiffOptions) -> Result<Diff, Error> {
+        if let Some(mut find_opts) = options.find_options() {
+            diff.find_similar(Some(&mut find_opts))?;
+        }
+        Ok(Diff::try_from(diff)?)
+    }
+
     /// Returns a full reference name with namespace(s) included.
     pub(crate) fn namespaced_refname<'a>(
         &'a self,
@@ -556,6 +1196,21 @@ impl Repository {
     }
 }
 
+/// The bits of the flags of an index entry that hold its stage, i.e.
+/// `GIT_INDEX_ENTRY_STAGEMASK` of libgit2.
+const INDEX_STAGE_MASK: u16 = 0x3000;
+
+/// The `git2` options for diffs against the working directory, which
+/// include the content of untracked files.
+fn workdir_options(options: &DiffOptions) -> git2::DiffOptions {
+    let mut opts = git2::DiffOptions::new();
+    options.apply(&mut opts);
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
+    opts
+}
+
 impl From<git2::Repository> for Repository {
     fn from(repo: git2::Repository) -> Self {
         Repository { inner: repo }
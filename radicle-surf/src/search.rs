@@ -0,0 +1,343 @@
+//! Searching the files of a revision by their paths or their content, i.e.
+//! fuzzy file finding and `git grep`.
+//!
+//! [`PathMatch`]es can be retrieved via [`crate::Repository::search_paths`]
+//! and [`Match`]es via [`crate::Repository::grep`].
+
+use std::{
+    borrow::Cow,
+    cmp::Reverse,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use radicle_git_ext::Oid;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::{Error, Repository};
+
+/// The score of each matched character of a fuzzy search.
+const SCORE_MATCH: i64 = 16;
+/// The bonus of a character that directly follows the previous match.
+const BONUS_CONSECUTIVE: i64 = 8;
+/// The bonus of a character at the start of a path component.
+const BONUS_COMPONENT: i64 = 10;
+/// The bonus of a character at the start of a word, e.g. after `_`, or in
+/// camel case.
+const BONUS_WORD: i64 = 7;
+/// The bonus of a match that lies entirely within the file name.
+const BONUS_FILE_NAME: i64 = 16;
+/// The penalty of a gap between two matched characters, and of each
+/// character skipped by the gap.
+const PENALTY_GAP_START: i64 = 3;
+const PENALTY_GAP: i64 = 1;
+
+/// Options for searching the files of a revision.
+///
+/// By default, all files are searched, binary files are skipped by
+/// [`crate::Repository::grep`], and the number of results is unlimited:
+///
+/// ```no_run
+/// use radicle_surf::search::SearchOptions;
+///
+/// let options = SearchOptions::default()
+///     .glob("src/**/*.rs")
+///     .regex(true)
+///     .context_lines(2);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    globs: Vec<String>,
+    regex: bool,
+    ignore_case: bool,
+    context_lines: usize,
+    binary: bool,
+    limit: Option<usize>,
+}
+
+impl SearchOptions {
+    /// Restricts the search to files matching the `glob`, e.g.
+    /// `src/*.rs`, using the pathspec rules of git. Files matching any of
+    /// the globs are searched.
+    pub fn glob(mut self, glob: impl Into<String>) -> Self {
+        self.globs.push(glob.into());
+        self
+    }
+
+    /// Interprets the pattern of a content search as a regular expression,
+    /// rather than an exact string. The default is `false`.
+    pub fn regex(mut self, regex: bool) -> Self {
+        self.regex = regex;
+        self
+    }
+
+    /// Ignores case when searching content. The default is `false`.
+    ///
+    /// Path searches always ignore case.
+    pub fn ignore_case(mut self, ignore: bool) -> Self {
+        self.ignore_case = ignore;
+        self
+    }
+
+    /// Sets the number of lines before and after each content match that
+    /// are included in the [`Match`]. The default is `0`.
+    pub fn context_lines(mut self, lines: usize) -> Self {
+        self.context_lines = lines;
+        self
+    }
+
+    /// Includes binary files in content searches. The default is `false`.
+    pub fn binary(mut self, binary: bool) -> Self {
+        self.binary = binary;
+        self
+    }
+
+    /// Stops the search after `limit` results.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+}
+
+/// A file whose path fuzzily matches a query.
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PathMatch {
+    /// The path of the file, relative to the searched tree.
+    pub path: PathBuf,
+    /// How well the path matches the query, where higher is better.
+    pub score: i64,
+    /// The indices of the characters of the path matching those of the
+    /// query, e.g. for highlighting.
+    pub indices: Vec<usize>,
+}
+
+/// A line of a file that matches a content search.
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Match {
+    /// The path of the file, relative to the searched tree.
+    pub path: PathBuf,
+    /// The line number of the matching line, starting at `1`.
+    pub line_no: usize,
+    /// The matching line, without its line ending.
+    pub line: String,
+    /// The byte ranges of the matches within `line`.
+    pub ranges: Vec<Range<usize>>,
+    /// The lines preceding `line`, as many as requested by
+    /// [`SearchOptions::context_lines`].
+    pub before: Vec<String>,
+    /// The lines following `line`, as many as requested by
+    /// [`SearchOptions::context_lines`].
+    pub after: Vec<String>,
+}
+
+/// Returns the files of `tree` whose paths fuzzily match `query`, best
+/// matches first.
+///
+/// A path matches if it contains all characters of the query in order,
+/// ignoring case and whitespace in the query. Matches at the start of path
+/// components or words, consecutive matches, and matches within the file
+/// name score higher.
+pub(crate) fn paths(
+    tree: &git2::Tree,
+    query: &str,
+    options: &SearchOptions,
+) -> Result<Vec<PathMatch>, git2::Error> {
+    let query = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<Vec<_>>();
+    let mut matches = files(tree, options)?
+        .into_iter()
+        .filter_map(|(path, _)| {
+            let (score, indices) = fuzzy(&query, &path.to_string_lossy())?;
+            Some(PathMatch {
+                path,
+                score,
+                indices,
+            })
+        })
+        .collect::<Vec<_>>();
+    matches.sort_by(|a, b| {
+        (Reverse(a.score), a.path.as_os_str().len(), &a.path).cmp(&(
+            Reverse(b.score),
+            b.path.as_os_str().len(),
+            &b.path,
+        ))
+    });
+    if let Some(limit) = options.limit {
+        matches.truncate(limit);
+    }
+    Ok(matches)
+}
+
+/// Returns the lines of the files of `tree` that match `pattern`, ordered by
+/// path and line number.
+pub(crate) fn grep(
+    repo: &Repository,
+    tree: &git2::Tree,
+    pattern: &str,
+    options: &SearchOptions,
+) -> Result<Vec<Match>, Error> {
+    let pattern: Cow<str> = if options.regex {
+        pattern.into()
+    } else {
+        regex::escape(pattern).into()
+    };
+    let regex = regex::RegexBuilder::new(&pattern)
+        .case_insensitive(options.ignore_case)
+        .build()?;
+    let limit = options.limit.unwrap_or(usize::MAX);
+
+    let mut matches = Vec::new();
+    for (path, oid) in files(tree, options)? {
+        if matches.len() >= limit {
+            break;
+        }
+        let blob = repo.find_blob(oid)?;
+        if blob.is_binary() && !options.binary {
+            continue;
+        }
+        let content = blob.content();
+        if content.is_empty() {
+            continue;
+        }
+        let content = content.strip_suffix(b"\n").unwrap_or(content);
+        let lines = content
+            .split(|b| *b == b'\n')
+            .map(String::from_utf8_lossy)
+            .collect::<Vec<_>>();
+
+        for (i, line) in lines.iter().enumerate() {
+            let ranges = regex.find_iter(line).map(|m| m.range()).collect::<Vec<_>>();
+            if ranges.is_empty() {
+                continue;
+            }
+            let context = |range: Range<usize>| {
+                lines[range]
+                    .iter()
+                    .map(|line| line.to_string())
+                    .collect::<Vec<_>>()
+            };
+            matches.push(Match {
+                path: path.clone(),
+                line_no: i + 1,
+                line: line.to_string(),
+                ranges,
+                before: context(i.saturating_sub(options.context_lines)..i),
+                after: context(i + 1..lines.len().min(i + 1 + options.context_lines)),
+            });
+            if matches.len() >= limit {
+                break;
+            }
+        }
+    }
+    Ok(matches)
+}
+
+/// Returns the paths and ids of all files in `tree` matching the globs of
+/// the `options`, in the order of the tree. Submodules are skipped.
+fn files(tree: &git2::Tree, options: &SearchOptions) -> Result<Vec<(PathBuf, Oid)>, git2::Error> {
+    let pathspec = if options.globs.is_empty() {
+        None
+    } else {
+        Some(git2::Pathspec::new(&options.globs)?)
+    };
+    let mut files = Vec::new();
+    tree.walk(git2::TreeWalkMode::PreOrder, |parent, entry| {
+        if entry.kind() != Some(git2::ObjectType::Blob) {
+            return git2::TreeWalkResult::Ok;
+        }
+        let Ok(name) = entry.name() else {
+            return git2::TreeWalkResult::Ok;
+        };
+        let path = Path::new(parent).join(name);
+        if pathspec.as_ref().map_or(true, |spec| {
+            spec.matches_path(&path, git2::PathspecFlags::DEFAULT)
+        }) {
+            files.push((path, entry.id().into()));
+        }
+        git2::TreeWalkResult::Ok
+    })?;
+    Ok(files)
+}
+
+/// Scores how well `path` matches `query`, returning the score and the
+/// indices of the matched characters, or `None` if the path does not
+/// contain all characters of the query in order.
+///
+/// Among all ways of matching the query, the one with the highest score is
+/// chosen.
+fn fuzzy(query: &[char], path: &str) -> Option<(i64, Vec<usize>)> {
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+    let chars = path.chars().collect::<Vec<_>>();
+    let eq = |a: char, b: char| a.to_lowercase().eq(b.to_lowercase());
+    let bonus = |i: usize| match i.checked_sub(1).map(|prev| (chars[prev], chars[i])) {
+        None | Some(('/', _)) => BONUS_COMPONENT,
+        Some(('_' | '-' | '.' | ' ', _)) => BONUS_WORD,
+        Some((prev, c)) if prev.is_lowercase() && c.is_uppercase() => BONUS_WORD,
+        Some(_) => 0,
+    };
+
+    // `scores[q][i]` is the best score of matching the query up to `q`,
+    // with `q` matched at `i`, and `from[q][i]` is where `q - 1` was
+    // matched in that case.
+    let mut scores = vec![vec![None; chars.len()]; query.len()];
+    let mut from = vec![vec![0; chars.len()]; query.len()];
+    for (i, c) in chars.iter().enumerate() {
+        if eq(*c, query[0]) {
+            scores[0][i] = Some(SCORE_MATCH + bonus(i));
+        }
+    }
+    for q in 1..query.len() {
+        // The best previous match that leaves a gap, adjusted so that the
+        // gap penalty only depends on the current index.
+        let mut gapped: Option<(i64, usize)> = None;
+        for (i, c) in chars.iter().enumerate().skip(1) {
+            if i >= 2 {
+                if let Some(score) = scores[q - 1][i - 2] {
+                    let score = score + PENALTY_GAP * (i - 2) as i64;
+                    if gapped.map_or(true, |(best, _)| score > best) {
+                        gapped = Some((score, i - 2));
+                    }
+                }
+            }
+            if !eq(*c, query[q]) {
+                continue;
+            }
+            let consecutive = scores[q - 1][i - 1].map(|score| (score + BONUS_CONSECUTIVE, i - 1));
+            let gapped = gapped
+                .map(|(score, k)| (score - PENALTY_GAP * (i - 1) as i64 - PENALTY_GAP_START, k));
+            let best = match (consecutive, gapped) {
+                (Some(a), Some(b)) => Some(if b.0 > a.0 { b } else { a }),
+                (a, b) => a.or(b),
+            };
+            if let Some((score, k)) = best {
+                scores[q][i] = Some(score + SCORE_MATCH + bonus(i));
+                from[q][i] = k;
+            }
+        }
+    }
+
+    let last = query.len() - 1;
+    let (end, mut score) = scores[last]
+        .iter()
+        .enumerate()
+        .filter_map(|(i, score)| score.map(|score| (i, score)))
+        .max_by_key(|(_, score)| *score)?;
+    let mut indices = vec![end; query.len()];
+    for q in (1..query.len()).rev() {
+        indices[q - 1] = from[q][indices[q]];
+    }
+
+    let file_name = chars.iter().rposition(|c| *c == '/').map_or(0, |i| i + 1);
+    if indices[0] >= file_name {
+        score += BONUS_FILE_NAME;
+    }
+    Some((score, indices))
+}
@@ -0,0 +1,166 @@
+//! Verification of commit signatures made with SSH keys, i.e.
+//! `ssh-keygen -Y sign`, or with OpenPGP keys, i.e. `gpg --detach-sign`.
+//!
+//! A [`Verification`] can be retrieved via
+//! [`crate::Repository::verify_commit`], checking the signature against
+//! the keys of a [`Verifier`]:
+//!
+//! ```no_run
+//! use std::str::FromStr;
+//!
+//! use radicle_surf::{
+//!     signature::{AllowedSigners, Keyring, Status, Verifier},
+//!     Repository,
+//! };
+//!
+//! let repo = Repository::open("/path/to/repo")?;
+//! let verifier = Verifier::default()
+//!     .allowed_signers(AllowedSigners::from_str(
+//!         "alice@example.com ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAA...",
+//!     )?)
+//!     .keyring(Keyring::from_bytes(&std::fs::read("keyring.asc").unwrap())?);
+//! let verification = repo.verify_commit("e24124b7538658220b5aaf3b6ef53758f0a106dc", &verifier)?;
+//! if let Some(verification) = verification {
+//!     assert_eq!(verification.status, Status::Good);
+//! }
+//! # Ok::<(), radicle_surf::Error>(())
+//! ```
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::Time;
+
+mod pgp;
+pub use pgp::Keyring;
+
+mod ssh;
+pub use ssh::AllowedSigners;
+
+pub mod error {
+    use thiserror::Error;
+
+    /// An error parsing an `allowed_signers` file.
+    #[derive(Debug, Error, PartialEq)]
+    #[non_exhaustive]
+    pub enum AllowedSigners {
+        #[error("invalid allowed signer at line {line}: {reason}")]
+        Invalid { line: usize, reason: &'static str },
+        #[error("invalid key of allowed signer at line {line}: {source}")]
+        Key { line: usize, source: ssh_key::Error },
+    }
+
+    /// An error parsing an OpenPGP keyring.
+    #[derive(Debug, Error, PartialEq)]
+    #[non_exhaustive]
+    pub enum Keyring {
+        #[error("invalid ASCII armor: {0}")]
+        Armor(&'static str),
+        #[error(transparent)]
+        Base64(#[from] base64::DecodeError),
+        #[error("malformed OpenPGP packet")]
+        Malformed,
+    }
+}
+
+/// The kind of a signature.
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// A signature made with an SSH key.
+    Ssh,
+    /// A signature made with an OpenPGP key.
+    Pgp,
+    /// A signature in any other format, e.g. X.509.
+    Other,
+}
+
+/// The outcome of verifying a signature.
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// The signature is valid and made by a known key.
+    Good,
+    /// The signature is malformed or does not match the signed content.
+    Bad,
+    /// The signature was made by a key that is not known to the
+    /// [`Verifier`], or by an SSH key that is not allowed to sign at the
+    /// time of the commit.
+    UnknownKey,
+    /// The signature is made with a format or algorithm that is not
+    /// supported.
+    Unsupported,
+    /// The signature is valid, but it has expired.
+    ExpiredSignature,
+    /// The signature is valid, but it was made after the key expired.
+    ExpiredKey,
+    /// The signature is valid, but the key has been revoked.
+    RevokedKey,
+}
+
+/// The verdict on a signature.
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Verification {
+    /// The kind of the signature.
+    pub kind: Kind,
+    /// Whether the signature is valid.
+    pub status: Status,
+    /// The identity of the signer, if the key is known, i.e. the principals
+    /// of an allowed SSH signer, or the first user id of an OpenPGP key.
+    pub signer: Option<String>,
+    /// The fingerprint of the signing key, if it could be determined, e.g.
+    /// `SHA256:...` for SSH keys and the upper case hex fingerprint, or key
+    /// id, for OpenPGP keys.
+    pub fingerprint: Option<String>,
+}
+
+/// The keys that signatures are verified against.
+///
+/// By default, no keys are known, so valid signatures are
+/// [`Status::UnknownKey`].
+#[derive(Clone, Debug, Default)]
+pub struct Verifier {
+    allowed_signers: AllowedSigners,
+    keyring: Keyring,
+}
+
+impl Verifier {
+    /// Verifies SSH signatures against the `signers`.
+    pub fn allowed_signers(mut self, signers: AllowedSigners) -> Self {
+        self.allowed_signers = signers;
+        self
+    }
+
+    /// Verifies OpenPGP signatures against the keys of the `keyring`.
+    pub fn keyring(mut self, keyring: Keyring) -> Self {
+        self.keyring = keyring;
+        self
+    }
+
+    /// Verifies the armored `signature` of `payload`, made at `time`.
+    ///
+    /// The `time` is checked against the validity period of allowed SSH
+    /// signers, and of OpenPGP keys for signatures without a creation time.
+    /// The expiration of OpenPGP signatures is checked against the current
+    /// time.
+    pub fn verify(&self, signature: &[u8], payload: &[u8], time: &Time) -> Verification {
+        let start = signature
+            .iter()
+            .position(|b| !b.is_ascii_whitespace())
+            .unwrap_or(signature.len());
+        let signature = &signature[start..];
+        if signature.starts_with(b"-----BEGIN SSH SIGNATURE-----") {
+            ssh::verify(&self.allowed_signers, signature, payload, time.seconds())
+        } else if signature.starts_with(b"-----BEGIN PGP SIGNATURE-----") {
+            pgp::verify(&self.keyring, signature, payload, time.seconds())
+        } else {
+            Verification {
+                kind: Kind::Other,
+                status: Status::Unsupported,
+                signer: None,
+                fingerprint: None,
+            }
+        }
+    }
+}
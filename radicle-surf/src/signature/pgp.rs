@@ -0,0 +1,827 @@
+//! Verification of OpenPGP signatures against a keyring, as done by
+//! `gpg --verify`.
+//!
+//! Only the subset of [RFC 4880] needed for the detached signatures made
+//! by git is supported: version 4 signatures and keys, using RSA or
+//! Ed25519 keys and SHA-2 hashes.
+//!
+//! [RFC 4880]: https://www.rfc-editor.org/rfc/rfc4880
+
+use std::{str, time::SystemTime};
+
+use base64::Engine as _;
+use ed25519_dalek::Verifier as _;
+use rsa::{traits::PublicKeyParts as _, BigUint, Pkcs1v15Sign, RsaPublicKey};
+use sha1::Sha1;
+use sha2::{Digest, Sha224, Sha256, Sha384, Sha512};
+
+use super::{error, Kind, Status, Verification};
+
+/// The verdict on a cryptographically valid signature made at `time`,
+/// checked at `now`.
+fn status(key: &Key, signature: &Signature, time: i64, now: i64) -> Status {
+    if key.revoked {
+        Status::RevokedKey
+    } else if time < key.created {
+        // The key cannot have made the signature.
+        Status::Bad
+    } else if key.expires.is_some_and(|expires| time >= expires) {
+        Status::ExpiredKey
+    } else if signature.expires().is_some_and(|expires| now >= expires) {
+        Status::ExpiredSignature
+    } else {
+        Status::Good
+    }
+}
+
+const TAG_SIGNATURE: u8 = 2;
+const TAG_PUBLIC_KEY: u8 = 6;
+const TAG_TRUST: u8 = 12;
+const TAG_USER_ID: u8 = 13;
+const TAG_PUBLIC_SUBKEY: u8 = 14;
+
+const ALGO_RSA: u8 = 1;
+const ALGO_RSA_SIGN: u8 = 3;
+const ALGO_EDDSA: u8 = 22;
+const ALGO_ED25519: u8 = 27;
+
+const HASH_SHA256: u8 = 8;
+const HASH_SHA384: u8 = 9;
+const HASH_SHA512: u8 = 10;
+const HASH_SHA224: u8 = 11;
+
+const SIG_BINARY: u8 = 0x00;
+const SIG_TEXT: u8 = 0x01;
+const SIG_CERTIFICATIONS: std::ops::RangeInclusive<u8> = 0x10..=0x13;
+const SIG_SUBKEY_BINDING: u8 = 0x18;
+const SIG_PRIMARY_KEY_BINDING: u8 = 0x19;
+const SIG_DIRECT_KEY: u8 = 0x1f;
+const SIG_KEY_REVOCATION: u8 = 0x20;
+const SIG_SUBKEY_REVOCATION: u8 = 0x28;
+const SIG_CERTIFICATION_REVOCATION: u8 = 0x30;
+
+const SUBPACKET_CREATION_TIME: u8 = 2;
+const SUBPACKET_SIGNATURE_EXPIRATION_TIME: u8 = 3;
+const SUBPACKET_KEY_EXPIRATION_TIME: u8 = 9;
+const SUBPACKET_ISSUER: u8 = 16;
+const SUBPACKET_KEY_FLAGS: u8 = 27;
+const SUBPACKET_EMBEDDED_SIGNATURE: u8 = 32;
+const SUBPACKET_ISSUER_FINGERPRINT: u8 = 33;
+
+/// The subpackets that are understood, so that signatures may mark them as
+/// critical.
+const SUBPACKETS_KNOWN: &[u8] = &[
+    SUBPACKET_CREATION_TIME,
+    SUBPACKET_SIGNATURE_EXPIRATION_TIME,
+    SUBPACKET_KEY_EXPIRATION_TIME,
+    SUBPACKET_ISSUER,
+    SUBPACKET_KEY_FLAGS,
+    SUBPACKET_EMBEDDED_SIGNATURE,
+    SUBPACKET_ISSUER_FINGERPRINT,
+];
+
+/// The key flag of keys that may sign data.
+const FLAG_SIGN: u8 = 0x02;
+
+/// The curve OID of Ed25519 in EdDSA keys.
+const OID_ED25519: &[u8] = &[0x2b, 0x06, 0x01, 0x04, 0x01, 0xda, 0x47, 0x0f, 0x01];
+
+/// A set of OpenPGP public keys, e.g. as exported by
+/// `gpg --armor --export`.
+///
+/// Like `gpg`, only the parts of a key that are bound to it by its primary
+/// key are used: user ids need a self-certification, and signing subkeys a
+/// binding signature along with a back signature made by the subkey.
+/// Revoked user ids are left out. Signatures of revoked keys and subkeys are
+/// reported as [`Status::RevokedKey`], where every revocation is taken as
+/// effective from the creation of the key, and signatures made after a key
+/// expired as [`Status::ExpiredKey`].
+///
+/// Signatures carrying a critical subpacket that is not understood are
+/// rejected, as required by RFC 4880.
+///
+/// Only version 4 RSA and Ed25519 keys can verify signatures, including
+/// the signatures binding the parts of a key, which must use SHA-2 hashes.
+#[derive(Clone, Debug, Default)]
+pub struct Keyring {
+    certs: Vec<Cert>,
+}
+
+/// A primary key along with its subkeys and user ids, where only the valid
+/// user ids and the keys that may sign are kept.
+#[derive(Clone, Debug, Default)]
+struct Cert {
+    user_ids: Vec<String>,
+    keys: Vec<Key>,
+}
+
+#[derive(Clone, Debug)]
+struct Key {
+    fingerprint: [u8; 20],
+    material: Material,
+    /// The creation time of the key, in seconds since the UNIX epoch.
+    created: i64,
+    /// The time the key expires, if it does.
+    expires: Option<i64>,
+    /// Whether the key, or its primary key, is revoked.
+    revoked: bool,
+}
+
+/// The packets of a certificate, i.e. a primary key followed by its user
+/// ids and subkeys, each along with their signatures.
+#[derive(Default)]
+struct Packets<'a> {
+    primary: &'a [u8],
+    signatures: Vec<&'a [u8]>,
+    user_ids: Vec<(&'a [u8], Vec<&'a [u8]>)>,
+    subkeys: Vec<(&'a [u8], Vec<&'a [u8]>)>,
+}
+
+/// The packet that signature packets belong to.
+enum Target {
+    Primary,
+    UserId,
+    Subkey,
+    /// A packet whose signatures are ignored, e.g. a user attribute.
+    Other,
+}
+
+#[derive(Clone, Debug)]
+enum Material {
+    Rsa(RsaPublicKey),
+    Ed25519(ed25519_dalek::VerifyingKey),
+    Unsupported,
+}
+
+impl Keyring {
+    /// Parses the keys of an armored or binary keyring. An armored keyring
+    /// may contain several key blocks.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, error::Keyring> {
+        let mut keyring = Self::default();
+        match str::from_utf8(bytes) {
+            Ok(text) if text.contains("-----BEGIN PGP PUBLIC KEY BLOCK-----") => {
+                for block in dearmor(text, "PUBLIC KEY BLOCK")? {
+                    keyring.add(&block)?;
+                }
+            }
+            _ => keyring.add(bytes)?,
+        }
+        Ok(keyring)
+    }
+
+    /// The number of primary keys in the keyring.
+    pub fn len(&self) -> usize {
+        self.certs.len()
+    }
+
+    /// Whether the keyring has no keys.
+    pub fn is_empty(&self) -> bool {
+        self.certs.is_empty()
+    }
+
+    fn add(&mut self, data: &[u8]) -> Result<(), error::Keyring> {
+        let mut certs = Vec::<Packets>::new();
+        let mut target = Target::Other;
+        for (tag, body) in packets(data).ok_or(error::Keyring::Malformed)? {
+            if tag == TAG_PUBLIC_KEY {
+                certs.push(Packets {
+                    primary: body,
+                    ..Packets::default()
+                });
+                target = Target::Primary;
+                continue;
+            }
+            let packets = certs.last_mut().ok_or(error::Keyring::Malformed)?;
+            match tag {
+                TAG_USER_ID => {
+                    packets.user_ids.push((body, Vec::new()));
+                    target = Target::UserId;
+                }
+                TAG_PUBLIC_SUBKEY => {
+                    packets.subkeys.push((body, Vec::new()));
+                    target = Target::Subkey;
+                }
+                TAG_SIGNATURE => {
+                    let signatures = match target {
+                        Target::Primary => Some(&mut packets.signatures),
+                        Target::UserId => packets.user_ids.last_mut().map(|(_, sigs)| sigs),
+                        Target::Subkey => packets.subkeys.last_mut().map(|(_, sigs)| sigs),
+                        Target::Other => None,
+                    };
+                    signatures.into_iter().for_each(|sigs| sigs.push(body));
+                }
+                TAG_TRUST => {}
+                _ => target = Target::Other,
+            }
+        }
+        for packets in certs {
+            self.certs.push(cert(&packets)?);
+        }
+        Ok(())
+    }
+
+    /// Finds the key matching the `issuer` of a signature, along with the
+    /// first user id of its primary key.
+    fn find(&self, issuer: &Issuer) -> Option<(&Key, Option<&String>)> {
+        self.certs.iter().find_map(|cert| {
+            let key = cert.keys.iter().find(|key| match issuer {
+                Issuer::Fingerprint(fingerprint) => key.fingerprint[..] == fingerprint[..],
+                Issuer::KeyId(id) => key.fingerprint[12..] == id[..],
+            })?;
+            Some((key, cert.user_ids.first()))
+        })
+    }
+}
+
+/// Builds the [`Cert`] of `packets`, keeping the user ids and signing keys
+/// that are validly bound to the primary key.
+fn cert(packets: &Packets) -> Result<Cert, error::Keyring> {
+    let mut cert = Cert::default();
+    let Some(mut primary) = key(packets.primary)? else {
+        return Ok(cert);
+    };
+    let primary_header = key_header(packets.primary)?;
+    let primary_parts: [&[u8]; 2] = [&primary_header, packets.primary];
+    primary.revoked = !valid(
+        &packets.signatures,
+        |kind| kind == SIG_KEY_REVOCATION,
+        &primary.material,
+        &primary_parts,
+    )
+    .is_empty();
+
+    let mut self_signatures = valid(
+        &packets.signatures,
+        |kind| kind == SIG_DIRECT_KEY,
+        &primary.material,
+        &primary_parts,
+    );
+    for (user_id, signatures) in &packets.user_ids {
+        let mut header = vec![0xb4];
+        header.extend_from_slice(&(user_id.len() as u32).to_be_bytes());
+        let parts: [&[u8]; 4] = [&primary_header, packets.primary, &header, user_id];
+        let certifications = valid(
+            signatures,
+            |kind| SIG_CERTIFICATIONS.contains(&kind),
+            &primary.material,
+            &parts,
+        );
+        let revoked = !valid(
+            signatures,
+            |kind| kind == SIG_CERTIFICATION_REVOCATION,
+            &primary.material,
+            &parts,
+        )
+        .is_empty();
+        if !certifications.is_empty() && !revoked {
+            cert.user_ids
+                .push(String::from_utf8_lossy(user_id).into_owned());
+            self_signatures.extend(certifications);
+        }
+    }
+    // The most recent self-signature determines the expiration and the
+    // flags of the primary key.
+    let Some(latest) = self_signatures.iter().max_by_key(|sig| sig.created) else {
+        cert.user_ids.clear();
+        return Ok(cert);
+    };
+    primary.expires = earliest(latest.key_expires(primary.created), latest.expires());
+    if latest.can_sign() {
+        cert.keys.push(primary.clone());
+    }
+
+    for (subkey, signatures) in &packets.subkeys {
+        let Some(mut key) = key(subkey)? else {
+            continue;
+        };
+        let header = key_header(subkey)?;
+        let parts: [&[u8]; 4] = [&primary_header, packets.primary, &header, subkey];
+        key.revoked = primary.revoked
+            || !valid(
+                signatures,
+                |kind| kind == SIG_SUBKEY_REVOCATION,
+                &primary.material,
+                &parts,
+            )
+            .is_empty();
+        let bindings = valid(
+            signatures,
+            |kind| kind == SIG_SUBKEY_BINDING,
+            &primary.material,
+            &parts,
+        );
+        let Some(binding) = bindings.iter().max_by_key(|sig| sig.created) else {
+            continue;
+        };
+        // A signing subkey must also sign the primary key, so that the
+        // subkey of another key cannot be claimed.
+        let back_signed = binding
+            .embedded
+            .as_deref()
+            .and_then(Signature::from_packet)
+            .filter(|sig| sig.kind == SIG_PRIMARY_KEY_BINDING)
+            .and_then(|sig| sig.check(&key.material, &parts))
+            == Some(true);
+        if !binding.can_sign() || !back_signed {
+            continue;
+        }
+        key.expires = earliest(
+            earliest(binding.key_expires(key.created), binding.expires()),
+            primary.expires,
+        );
+        cert.keys.push(key);
+    }
+    Ok(cert)
+}
+
+/// The earliest of two expiration times, where `None` never expires.
+fn earliest(a: Option<i64>, b: Option<i64>) -> Option<i64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Returns the signatures among `signatures` of a `kind`, that are made by
+/// `key` over the `parts`.
+fn valid(
+    signatures: &[&[u8]],
+    kind: impl Fn(u8) -> bool,
+    key: &Material,
+    parts: &[&[u8]],
+) -> Vec<Signature> {
+    signatures
+        .iter()
+        .filter_map(|body| Signature::from_packet(body))
+        .filter(|sig| kind(sig.kind) && sig.check(key, parts) == Some(true))
+        .collect()
+}
+
+/// The header of a public key packet, as hashed by signatures over keys.
+fn key_header(body: &[u8]) -> Result<[u8; 3], error::Keyring> {
+    let len = u16::try_from(body.len())
+        .map_err(|_| error::Keyring::Malformed)?
+        .to_be_bytes();
+    Ok([0x99, len[0], len[1]])
+}
+
+/// Parses a public key or subkey packet, returning `None` for key versions
+/// other than 4.
+fn key(body: &[u8]) -> Result<Option<Key>, error::Keyring> {
+    let mut r = Reader(body);
+    if r.u8() != Some(4) {
+        return Ok(None);
+    }
+    let (created, material) = (|| {
+        let created = r.u32()?;
+        let material = match r.u8()? {
+            ALGO_RSA | ALGO_RSA_SIGN => {
+                let n = BigUint::from_bytes_be(r.mpi()?);
+                let e = BigUint::from_bytes_be(r.mpi()?);
+                RsaPublicKey::new(n, e).map_or(Material::Unsupported, Material::Rsa)
+            }
+            ALGO_EDDSA => {
+                let len = r.u8()?;
+                let oid = r.take(len as usize)?;
+                match r.mpi()?.split_first() {
+                    Some((0x40, point)) if oid == OID_ED25519 => ed25519(point),
+                    _ => Material::Unsupported,
+                }
+            }
+            ALGO_ED25519 => ed25519(r.take(32)?),
+            _ => Material::Unsupported,
+        };
+        Some((created, material))
+    })()
+    .ok_or(error::Keyring::Malformed)?;
+
+    let mut hasher = Sha1::new();
+    hasher.update(key_header(body)?);
+    hasher.update(body);
+    Ok(Some(Key {
+        fingerprint: hasher.finalize().into(),
+        material,
+        created: created.into(),
+        expires: None,
+        revoked: false,
+    }))
+}
+
+fn ed25519(point: &[u8]) -> Material {
+    <[u8; 32]>::try_from(point)
+        .ok()
+        .and_then(|point| ed25519_dalek::VerifyingKey::from_bytes(&point).ok())
+        .map_or(Material::Unsupported, Material::Ed25519)
+}
+
+/// The key that made a signature.
+enum Issuer {
+    Fingerprint(Vec<u8>),
+    KeyId(Vec<u8>),
+}
+
+/// A version 4 signature packet.
+struct Signature {
+    kind: u8,
+    algo: u8,
+    hash: u8,
+    /// The hashed part of the packet, from the version up to and
+    /// including the hashed subpackets.
+    hashed: Vec<u8>,
+    issuer: Option<Issuer>,
+    /// The creation time of the signature.
+    created: Option<i64>,
+    /// The validity period of the signature after its creation, in seconds,
+    /// where `0` means that it does not expire.
+    expiration: Option<i64>,
+    /// The validity period of the signed key after its creation, in
+    /// seconds, where `0` means that it does not expire.
+    key_expiration: Option<i64>,
+    /// The flags of the signed key.
+    key_flags: Option<u8>,
+    /// The body of a signature packet embedded in this one.
+    embedded: Option<Vec<u8>>,
+    /// The left 16 bits of the signed hash.
+    left: [u8; 2],
+    /// The algorithm-specific signature values.
+    values: Vec<u8>,
+}
+
+impl Signature {
+    fn parse(armored: &[u8]) -> Option<Self> {
+        let text = str::from_utf8(armored).ok()?;
+        let data = dearmor(text, "SIGNATURE").ok()?.into_iter().next()?;
+        let (_, body) = packets(&data)?
+            .into_iter()
+            .find(|(tag, _)| *tag == TAG_SIGNATURE)?;
+        Self::from_packet(body)
+    }
+
+    fn from_packet(body: &[u8]) -> Option<Self> {
+        let mut r = Reader(body);
+        if r.u8()? != 4 {
+            return None;
+        }
+        let kind = r.u8()?;
+        let algo = r.u8()?;
+        let hash = r.u8()?;
+        let len = r.u16()?;
+        let hashed_subpackets = r.take(len as usize)?;
+        let len = r.u16()?;
+        let unhashed_subpackets = r.take(len as usize)?;
+        let left = r.take(2)?;
+
+        let hashed = subpackets(hashed_subpackets)?;
+        let unhashed = subpackets(unhashed_subpackets)?;
+        if hashed
+            .iter()
+            .chain(&unhashed)
+            .any(|(tag, critical, _)| *critical && !SUBPACKETS_KNOWN.contains(tag))
+        {
+            return None;
+        }
+
+        let mut created = None;
+        let mut expiration = None;
+        let mut key_expiration = None;
+        let mut key_flags = None;
+        // Only the hashed subpackets are covered by the signature.
+        for (tag, _, data) in &hashed {
+            let time = || Some(i64::from(Reader(data).u32()?));
+            match *tag {
+                SUBPACKET_CREATION_TIME => created = time(),
+                SUBPACKET_SIGNATURE_EXPIRATION_TIME => expiration = time(),
+                SUBPACKET_KEY_EXPIRATION_TIME => key_expiration = time(),
+                SUBPACKET_KEY_FLAGS => key_flags = data.first().copied(),
+                _ => {}
+            }
+        }
+
+        let mut issuer = None;
+        let mut embedded = None;
+        for (tag, _, data) in hashed.into_iter().chain(unhashed) {
+            match (tag, data.split_first()) {
+                (SUBPACKET_ISSUER_FINGERPRINT, Some((4, fingerprint)))
+                    if !matches!(issuer, Some(Issuer::Fingerprint(_))) =>
+                {
+                    issuer = Some(Issuer::Fingerprint(fingerprint.to_vec()));
+                }
+                (SUBPACKET_ISSUER, _) if issuer.is_none() => {
+                    issuer = Some(Issuer::KeyId(data.to_vec()))
+                }
+                // The embedded signature is verified on its own.
+                (SUBPACKET_EMBEDDED_SIGNATURE, _) if embedded.is_none() => {
+                    embedded = Some(data.to_vec())
+                }
+                _ => {}
+            }
+        }
+
+        Some(Self {
+            kind,
+            algo,
+            hash,
+            hashed: body[..6 + hashed_subpackets.len()].to_vec(),
+            issuer,
+            created,
+            expiration,
+            key_expiration,
+            key_flags,
+            embedded,
+            left: [left[0], left[1]],
+            values: r.0.to_vec(),
+        })
+    }
+
+    /// Whether the key signed by this self-signature may sign data.
+    fn can_sign(&self) -> bool {
+        self.key_flags.map_or(true, |flags| flags & FLAG_SIGN != 0)
+    }
+
+    /// The expiration time of this signature, if it expires.
+    fn expires(&self) -> Option<i64> {
+        let created = self.created?;
+        self.expiration
+            .filter(|seconds| *seconds > 0)
+            .map(|seconds| created + seconds)
+    }
+
+    /// The expiration time of the key created at `created`, signed by this
+    /// self-signature.
+    fn key_expires(&self, created: i64) -> Option<i64> {
+        self.key_expiration
+            .filter(|seconds| *seconds > 0)
+            .map(|seconds| created + seconds)
+    }
+
+    /// Checks that this signature is made by `key` over the concatenated
+    /// `parts`, returning `None` if the algorithms are not supported.
+    fn check(&self, key: &Material, parts: &[&[u8]]) -> Option<bool> {
+        let digest = self.digest(parts)?;
+        if digest[..2] != self.left {
+            return Some(false);
+        }
+        self.verify(key, &digest)
+    }
+
+    /// The hash signed by this signature over the concatenated `parts`, or
+    /// `None` if the hash algorithm is not supported.
+    fn digest(&self, parts: &[&[u8]]) -> Option<Vec<u8>> {
+        fn hash<D: Digest>(parts: &[&[u8]]) -> Vec<u8> {
+            let mut hasher = D::new();
+            for part in parts {
+                hasher.update(part);
+            }
+            hasher.finalize().to_vec()
+        }
+
+        let mut trailer = vec![4, 0xff];
+        trailer.extend_from_slice(&(self.hashed.len() as u32).to_be_bytes());
+        let parts = [parts, &[&self.hashed, &trailer]].concat();
+        match self.hash {
+            HASH_SHA256 => Some(hash::<Sha256>(&parts)),
+            HASH_SHA384 => Some(hash::<Sha384>(&parts)),
+            HASH_SHA512 => Some(hash::<Sha512>(&parts)),
+            HASH_SHA224 => Some(hash::<Sha224>(&parts)),
+            _ => None,
+        }
+    }
+
+    /// Verifies `digest` with `key`, returning `None` if the algorithms of
+    /// the signature and key do not match, or are not supported.
+    fn verify(&self, key: &Material, digest: &[u8]) -> Option<bool> {
+        let mut r = Reader(&self.values);
+        match (key, self.algo) {
+            (Material::Rsa(key), ALGO_RSA | ALGO_RSA_SIGN) => {
+                let scheme = match self.hash {
+                    HASH_SHA256 => Pkcs1v15Sign::new::<Sha256>(),
+                    HASH_SHA384 => Pkcs1v15Sign::new::<Sha384>(),
+                    HASH_SHA512 => Pkcs1v15Sign::new::<Sha512>(),
+                    HASH_SHA224 => Pkcs1v15Sign::new::<Sha224>(),
+                    _ => return None,
+                };
+                let signature = pad(r.mpi()?, key.size())?;
+                Some(key.verify(scheme, digest, &signature).is_ok())
+            }
+            (Material::Ed25519(key), ALGO_EDDSA | ALGO_ED25519) => {
+                let mut bytes = [0; 64];
+                if self.algo == ALGO_EDDSA {
+                    bytes[..32].copy_from_slice(&pad(r.mpi()?, 32)?);
+                    bytes[32..].copy_from_slice(&pad(r.mpi()?, 32)?);
+                } else {
+                    bytes.copy_from_slice(r.take(64)?);
+                }
+                let signature = ed25519_dalek::Signature::from_bytes(&bytes);
+                Some(key.verify(digest, &signature).is_ok())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Verifies the armored OpenPGP `signature` of `payload`, made at `time`
+/// unless the signature has a creation time.
+pub(super) fn verify(
+    keyring: &Keyring,
+    signature: &[u8],
+    payload: &[u8],
+    time: i64,
+) -> Verification {
+    let verdict = |status, signer: Option<&String>, fingerprint: Option<&[u8]>| Verification {
+        kind: Kind::Pgp,
+        status,
+        signer: signer.cloned(),
+        fingerprint: fingerprint.map(hex),
+    };
+    let Some(signature) = Signature::parse(signature) else {
+        return verdict(Status::Bad, None, None);
+    };
+    let issuer = match &signature.issuer {
+        Some(Issuer::Fingerprint(id) | Issuer::KeyId(id)) => Some(&id[..]),
+        None => None,
+    };
+    let Some((key, signer)) = signature.issuer.as_ref().and_then(|i| keyring.find(i)) else {
+        return verdict(Status::UnknownKey, None, issuer);
+    };
+    let fingerprint = Some(&key.fingerprint[..]);
+
+    let payload = match signature.kind {
+        SIG_BINARY => payload.to_vec(),
+        SIG_TEXT => canonicalize(payload),
+        _ => return verdict(Status::Bad, signer, fingerprint),
+    };
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs() as i64);
+    match signature.check(&key.material, &[&payload]) {
+        Some(true) => {
+            let time = signature.created.unwrap_or(time);
+            verdict(status(key, &signature, time, now), signer, fingerprint)
+        }
+        Some(false) => verdict(Status::Bad, signer, fingerprint),
+        None => verdict(Status::Unsupported, signer, fingerprint),
+    }
+}
+
+/// Decodes the ASCII armored blocks with the given `label` in `text`,
+/// checking their checksums.
+fn dearmor(text: &str, label: &str) -> Result<Vec<Vec<u8>>, error::Keyring> {
+    let begin = format!("-----BEGIN PGP {label}-----");
+    let end = format!("-----END PGP {label}-----");
+    let mut blocks = Vec::new();
+    let mut lines = text.lines().map(str::trim);
+    while lines.by_ref().any(|line| line == begin) {
+        let mut body = String::new();
+        let mut checksum = None;
+        loop {
+            let line = lines
+                .next()
+                .ok_or(error::Keyring::Armor("missing end line"))?;
+            if line == end {
+                break;
+            }
+            // The armor headers, e.g. `Comment: ...`, precede the body.
+            if line.is_empty() || (body.is_empty() && line.contains(':')) {
+                continue;
+            }
+            match line.strip_prefix('=') {
+                Some(crc) => checksum = Some(crc.to_owned()),
+                None => body.push_str(line),
+            }
+        }
+
+        let data = base64::prelude::BASE64_STANDARD.decode(body)?;
+        if let Some(checksum) = checksum {
+            let checksum = base64::prelude::BASE64_STANDARD.decode(checksum)?;
+            if checksum[..] != crc24(&data).to_be_bytes()[1..] {
+                return Err(error::Keyring::Armor("checksum mismatch"));
+            }
+        }
+        blocks.push(data);
+    }
+    Ok(blocks)
+}
+
+/// The CRC-24 checksum of the ASCII armor.
+fn crc24(data: &[u8]) -> u32 {
+    let mut crc = 0xb704ce;
+    for byte in data {
+        crc ^= (*byte as u32) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x1000000 != 0 {
+                crc ^= 0x1864cfb;
+            }
+        }
+    }
+    crc & 0xffffff
+}
+
+/// Splits `data` into its packets, as pairs of tag and body.
+fn packets(data: &[u8]) -> Option<Vec<(u8, &[u8])>> {
+    let mut r = Reader(data);
+    let mut packets = Vec::new();
+    while !r.0.is_empty() {
+        let header = r.u8()?;
+        if header & 0x80 == 0 {
+            return None;
+        }
+        let (tag, len) = if header & 0x40 != 0 {
+            // Partial body lengths are only used by data packets, which are
+            // not expected here.
+            let len = match r.u8()? {
+                len @ 0..=191 => len as usize,
+                len @ 192..=223 => ((len as usize - 192) << 8) + r.u8()? as usize + 192,
+                255 => r.u32()? as usize,
+                _ => return None,
+            };
+            (header & 0x3f, len)
+        } else {
+            let len = match header & 0x03 {
+                0 => r.u8()? as usize,
+                1 => r.u16()? as usize,
+                2 => r.u32()? as usize,
+                _ => r.0.len(),
+            };
+            ((header >> 2) & 0x0f, len)
+        };
+        packets.push((tag, r.take(len)?));
+    }
+    Some(packets)
+}
+
+/// Splits the subpackets of a signature into their type, whether they are
+/// critical, and data.
+fn subpackets(data: &[u8]) -> Option<Vec<(u8, bool, &[u8])>> {
+    let mut r = Reader(data);
+    let mut subpackets = Vec::new();
+    while !r.0.is_empty() {
+        let len = match r.u8()? {
+            len @ 0..=191 => len as usize,
+            len @ 192..=254 => ((len as usize - 192) << 8) + r.u8()? as usize + 192,
+            255 => r.u32()? as usize,
+        };
+        let body = r.take(len)?;
+        let (tag, data) = body.split_first()?;
+        subpackets.push((tag & 0x7f, tag & 0x80 != 0, data));
+    }
+    Some(subpackets)
+}
+
+/// Converts the line endings of a text signature's payload to `\r\n`.
+fn canonicalize(payload: &[u8]) -> Vec<u8> {
+    let mut text = Vec::with_capacity(payload.len());
+    for (i, byte) in payload.iter().enumerate() {
+        if *byte == b'\n' && (i == 0 || payload[i - 1] != b'\r') {
+            text.push(b'\r');
+        }
+        text.push(*byte);
+    }
+    text
+}
+
+/// Left-pads `value` with zeros to `len` bytes, since multiprecision
+/// integers are stored without leading zeros.
+fn pad(value: &[u8], len: usize) -> Option<Vec<u8>> {
+    let mut padded = vec![0; len.checked_sub(value.len())?];
+    padded.extend_from_slice(value);
+    Some(padded)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02X}")).collect()
+}
+
+/// A cursor over the bytes of a packet.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.0.len() < n {
+            return None;
+        }
+        let (taken, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Some(taken)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take(2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    /// Reads a multiprecision integer, i.e. its length in bits followed
+    /// by its big-endian bytes.
+    fn mpi(&mut self) -> Option<&'a [u8]> {
+        let bits = self.u16()? as usize;
+        self.take(bits.div_ceil(8))
+    }
+}
@@ -0,0 +1,212 @@
+//! Verification of SSH signatures against an `allowed_signers` file, as
+//! done by `ssh-keygen -Y verify`.
+//!
+//! See the `ALLOWED SIGNERS` section of `ssh-keygen(1)` for the format.
+
+use std::str::FromStr;
+
+use ssh_key::{HashAlg, PublicKey, SshSig};
+
+use super::{error, Kind, Status, Verification};
+
+/// The namespace of the signatures made by git.
+const NAMESPACE: &str = "git";
+
+/// The SSH keys that are allowed to sign, along with the principals they
+/// identify, parsed from the `allowed_signers` format of `ssh-keygen`.
+///
+/// The `namespaces`, `valid-after` and `valid-before` options are
+/// honored, where times without a `Z` suffix are read as UTC rather than
+/// local time. Entries marked as `cert-authority` are skipped, since
+/// signatures made with SSH certificates are not supported.
+#[derive(Clone, Debug, Default)]
+pub struct AllowedSigners {
+    signers: Vec<Signer>,
+}
+
+#[derive(Clone, Debug)]
+struct Signer {
+    principals: String,
+    namespaces: Option<Vec<String>>,
+    valid_after: Option<i64>,
+    valid_before: Option<i64>,
+    key: PublicKey,
+}
+
+impl Signer {
+    /// Whether this signer may sign in `namespace` at `time`.
+    fn allows(&self, namespace: &str, time: i64) -> bool {
+        self.namespaces
+            .as_ref()
+            .map_or(true, |namespaces| namespaces.iter().any(|n| n == namespace))
+            && self.valid_after.map_or(true, |after| time >= after)
+            && self.valid_before.map_or(true, |before| time <= before)
+    }
+}
+
+impl AllowedSigners {
+    /// The number of allowed signers.
+    pub fn len(&self) -> usize {
+        self.signers.len()
+    }
+
+    /// Whether there are no allowed signers.
+    pub fn is_empty(&self) -> bool {
+        self.signers.is_empty()
+    }
+}
+
+impl FromStr for AllowedSigners {
+    type Err = error::AllowedSigners;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut signers = Vec::new();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            signers.extend(signer(i + 1, line)?);
+        }
+        Ok(Self { signers })
+    }
+}
+
+/// Verifies the SSH `signature` of `payload`, made at `time`.
+pub(super) fn verify(
+    signers: &AllowedSigners,
+    signature: &[u8],
+    payload: &[u8],
+    time: i64,
+) -> Verification {
+    let verdict = |status, signer, fingerprint| Verification {
+        kind: Kind::Ssh,
+        status,
+        signer,
+        fingerprint,
+    };
+    let Ok(signature) = SshSig::from_pem(signature) else {
+        return verdict(Status::Bad, None, None);
+    };
+    let key = PublicKey::from(signature.public_key().clone());
+    let fingerprint = Some(key.fingerprint(HashAlg::Sha256).to_string());
+    match key.verify(NAMESPACE, payload, &signature) {
+        Ok(()) => {}
+        Err(ssh_key::Error::AlgorithmUnknown | ssh_key::Error::AlgorithmUnsupported { .. }) => {
+            return verdict(Status::Unsupported, None, fingerprint)
+        }
+        Err(_) => return verdict(Status::Bad, None, fingerprint),
+    }
+
+    match signers
+        .signers
+        .iter()
+        .find(|signer| signer.key.key_data() == key.key_data() && signer.allows(NAMESPACE, time))
+    {
+        Some(signer) => verdict(Status::Good, Some(signer.principals.clone()), fingerprint),
+        None => verdict(Status::UnknownKey, None, fingerprint),
+    }
+}
+
+/// Parses the entry at `line`, returning `None` for entries that are
+/// skipped.
+fn signer(line: usize, entry: &str) -> Result<Option<Signer>, error::AllowedSigners> {
+    let invalid = |reason| error::AllowedSigners::Invalid { line, reason };
+    let fields = split(entry, |c| c.is_ascii_whitespace());
+    let (principals, rest) = match fields.split_first() {
+        Some((principals, rest)) if !rest.is_empty() => (unquote(principals), rest),
+        _ => return Err(invalid("missing key")),
+    };
+
+    // The options are only present if the field after the principals is
+    // not a key type.
+    let (options, key) = if ssh_key::Algorithm::from_str(rest[0]).is_ok() {
+        (None, rest)
+    } else {
+        (Some(rest[0]), &rest[1..])
+    };
+    let key = PublicKey::from_openssh(&key.join(" "))
+        .map_err(|source| error::AllowedSigners::Key { line, source })?;
+
+    let mut signer = Signer {
+        principals: principals.to_owned(),
+        namespaces: None,
+        valid_after: None,
+        valid_before: None,
+        key,
+    };
+    for option in options.map(|o| split(o, |c| c == ',')).unwrap_or_default() {
+        let (name, value) = match option.split_once('=') {
+            Some((name, value)) => (name, Some(unquote(value))),
+            None => (option, None),
+        };
+        match (name.to_ascii_lowercase().as_str(), value) {
+            ("cert-authority", None) => return Ok(None),
+            ("namespaces", Some(value)) => {
+                signer.namespaces = Some(value.split(',').map(str::to_owned).collect())
+            }
+            ("valid-after", Some(value)) => {
+                signer.valid_after = Some(time(value).ok_or(invalid("invalid time"))?)
+            }
+            ("valid-before", Some(value)) => {
+                signer.valid_before = Some(time(value).ok_or(invalid("invalid time"))?)
+            }
+            _ => return Err(invalid("unsupported option")),
+        }
+    }
+    Ok(Some(signer))
+}
+
+/// Splits `s` at the characters matching `sep`, except within double
+/// quotes, skipping empty parts.
+fn split(s: &str, sep: impl Fn(char) -> bool) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut quoted = false;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        if c == '"' {
+            quoted = !quoted;
+        } else if !quoted && sep(c) {
+            parts.push(&s[start..i]);
+            start = i + c.len_utf8();
+        }
+    }
+    parts.push(&s[start..]);
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+fn unquote(s: &str) -> &str {
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(s)
+}
+
+/// Parses a time of the form `YYYYMMDD[HHMM[SS]][Z]` into seconds since
+/// the UNIX epoch.
+fn time(s: &str) -> Option<i64> {
+    let s = s.strip_suffix(['Z', 'z']).unwrap_or(s);
+    if !matches!(s.len(), 8 | 12 | 14) || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let field =
+        |range: std::ops::Range<usize>| s.get(range).map_or(Some(0), |f| f.parse::<i64>().ok());
+    let (year, month, day) = (field(0..4)?, field(4..6)?, field(6..8)?);
+    let (hour, minute, second) = (field(8..10)?, field(10..12)?, field(12..14)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    // The days since the epoch of a proleptic Gregorian date, where years
+    // start in March so that leap days come last.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146097 + day_of_era - 719468;
+    Some(days * 86400 + hour * 3600 + minute * 60 + second)
+}
@@ -1,6 +1,10 @@
+use std::collections::HashMap;
+
 #[cfg(feature = "serde")]
 use serde::Serialize;
 
+use crate::{Error, History, Time};
+
 /// Stats for a repository
 #[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
@@ -12,3 +16,64 @@ pub struct Stats {
     /// Number of contributors
     pub contributors: usize,
 }
+
+/// An author of commits in the history of a revision, see
+/// [`crate::Repository::contributors`].
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "camelCase"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Contributor {
+    /// The canonical name of the contributor.
+    pub name: String,
+    /// The canonical email of the contributor.
+    pub email: String,
+    /// Number of commits authored by the contributor.
+    pub commits: usize,
+    /// Author time of the earliest commit of the contributor.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::commit::serialize_time")
+    )]
+    pub first: Time,
+    /// Author time of the latest commit of the contributor.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::commit::serialize_time")
+    )]
+    pub last: Time,
+}
+
+/// Groups the commits of `history` by their author.
+pub(crate) fn contributors(history: History) -> Result<Vec<Contributor>, Error> {
+    let mut contributors = HashMap::<(String, String), Contributor>::new();
+    for commit in history {
+        let author = commit?.author;
+        let time = author.time;
+        contributors
+            .entry((author.name, author.email))
+            .and_modify(|c| {
+                c.commits += 1;
+                if time.seconds() < c.first.seconds() {
+                    c.first = time.clone();
+                }
+                if time.seconds() > c.last.seconds() {
+                    c.last = time.clone();
+                }
+            })
+            .or_insert_with_key(|(name, email)| Contributor {
+                name: name.clone(),
+                email: email.clone(),
+                commits: 1,
+                first: time.clone(),
+                last: time.clone(),
+            });
+    }
+
+    let mut contributors = contributors.into_values().collect::<Vec<_>>();
+    contributors.sort_by(|a, b| {
+        b.commits
+            .cmp(&a.commits)
+            .then_with(|| a.name.cmp(&b.name))
+            .then_with(|| a.email.cmp(&b.email))
+    });
+    Ok(contributors)
+}
@@ -0,0 +1,233 @@
+use std::{
+    collections::BTreeMap,
+    io::{Cursor, Read},
+};
+
+use flate2::read::{DeflateDecoder, GzDecoder};
+use radicle_surf::{
+    archive::{ArchiveOptions, Format},
+    Error, Oid, Repository,
+};
+use test_helpers::tempdir::WithTmpDir;
+
+use super::fixture::{commit_with, tmp_repo};
+
+/// An entry of an archive, i.e. its mode and content, or the target of a
+/// symbolic link.
+type Entries = BTreeMap<String, (u32, String)>;
+
+fn untar(archive: impl Read) -> Entries {
+    let mut archive = tar::Archive::new(archive);
+    archive
+        .entries()
+        .unwrap()
+        .map(|entry| {
+            let mut entry = entry.unwrap();
+            let header = entry.header();
+            assert_eq!(header.mtime().unwrap(), 1700000000);
+            assert_eq!(header.username().unwrap(), Some("root"));
+            let path = entry.path().unwrap().to_string_lossy().into_owned();
+            let mode = header.mode().unwrap();
+            let content = match entry.link_name().unwrap() {
+                Some(target) => target.to_string_lossy().into_owned(),
+                None => {
+                    let mut content = String::new();
+                    entry.read_to_string(&mut content).unwrap();
+                    content
+                }
+            };
+            (path, (mode, content))
+        })
+        .collect()
+}
+
+/// Reads the entries of a `zip` archive from its local headers, checking
+/// them against the central directory.
+fn unzip(archive: &[u8]) -> Entries {
+    let u16_at = |i: usize| u16::from_le_bytes([archive[i], archive[i + 1]]) as usize;
+    let u32_at = |i: usize| u32::from_le_bytes(archive[i..i + 4].try_into().unwrap());
+
+    let end = archive.len() - 22 - 40;
+    assert_eq!(u32_at(end), 0x06054b50);
+    let count = u16_at(end + 10);
+    let mut central = u32_at(end + 16) as usize;
+
+    let mut entries = Entries::new();
+    for _ in 0..count {
+        assert_eq!(u32_at(central), 0x02014b50);
+        let mode = u32_at(central + 38) >> 16;
+        let offset = u32_at(central + 42) as usize;
+        let name_len = u16_at(central + 28);
+        central += 46 + name_len + u16_at(central + 30);
+
+        assert_eq!(u32_at(offset), 0x04034b50);
+        let method = u16_at(offset + 8);
+        let crc = u32_at(offset + 14);
+        let size = u32_at(offset + 18) as usize;
+        let name_len = u16_at(offset + 26);
+        let start = offset + 30 + name_len + u16_at(offset + 28);
+        let name =
+            String::from_utf8(archive[offset + 30..offset + 30 + name_len].to_vec()).unwrap();
+        let data = &archive[start..start + size];
+        let mut content = Vec::new();
+        match method {
+            0 => content.extend_from_slice(data),
+            8 => {
+                DeflateDecoder::new(data).read_to_end(&mut content).unwrap();
+            }
+            _ => panic!("unexpected compression method {method}"),
+        }
+        assert_eq!(crc32(&content), crc);
+        entries.insert(name, (mode, String::from_utf8(content).unwrap()));
+    }
+    entries
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                crc >> 1 ^ 0xedb88320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn entries(entries: &[(&str, u32, &str)]) -> Entries {
+    entries
+        .iter()
+        .map(|(path, mode, content)| (path.to_string(), (*mode, content.to_string())))
+        .collect()
+}
+
+const MAIN: &str = "fn main() {\n    println!(\"hello, world\");\n}\n";
+
+fn repository() -> (WithTmpDir<git2::Repository>, Oid) {
+    let tmp = tmp_repo();
+    let oid = commit_with(
+        &tmp,
+        &[
+            (
+                ".gitattributes",
+                git2::FileMode::Blob,
+                "/tests export-ignore\n*.secret export-ignore\nVERSION export-subst\n",
+            ),
+            ("README.md", git2::FileMode::Blob, "readme"),
+            (
+                "VERSION",
+                git2::FileMode::Blob,
+                "$Format:%h %s%n$\n$Format:%an <%ae> %at$ %H\n",
+            ),
+            ("build.sh", git2::FileMode::BlobExecutable, "#!/bin/sh\n"),
+            ("docs/README.md", git2::FileMode::Link, "../README.md"),
+            (
+                "src/.gitattributes",
+                git2::FileMode::Blob,
+                "generated.rs export-ignore\n",
+            ),
+            ("src/generated.rs", git2::FileMode::Blob, "// generated"),
+            ("src/key.secret", git2::FileMode::Blob, "secret"),
+            ("src/main.rs", git2::FileMode::Blob, MAIN),
+            ("tests/test.rs", git2::FileMode::Blob, "#[test]"),
+        ],
+        "Release\n\nThe first one.\n",
+        &[],
+    );
+    (tmp, oid)
+}
+
+#[test]
+fn archive_tar() -> Result<(), Error> {
+    let (tmp, oid) = repository();
+    let repo = Repository::open(tmp.path())?;
+    let short = &oid.to_string()[..7];
+
+    let mut tar = Vec::new();
+    repo.archive(oid, Format::Tar, "project/", &mut tar)?;
+    let expected = entries(&[
+        ("project/", 0o755, ""),
+        (
+            "project/.gitattributes",
+            0o644,
+            "/tests export-ignore\n*.secret export-ignore\nVERSION export-subst\n",
+        ),
+        ("project/README.md", 0o644, "readme"),
+        (
+            "project/VERSION",
+            0o644,
+            &format!("{short} Release\n\nTest <test@test.com> 1700000000 %H\n"),
+        ),
+        ("project/build.sh", 0o755, "#!/bin/sh\n"),
+        ("project/docs/", 0o755, ""),
+        ("project/docs/README.md", 0o777, "../README.md"),
+        ("project/src/", 0o755, ""),
+        (
+            "project/src/.gitattributes",
+            0o644,
+            "generated.rs export-ignore\n",
+        ),
+        ("project/src/main.rs", 0o644, MAIN),
+    ]);
+    assert_eq!(untar(tar.as_slice()), expected);
+
+    let mut tar_gz = Vec::new();
+    repo.archive(oid, Format::TarGz, "project/", &mut tar_gz)?;
+    assert_eq!(tar_gz[..2], [0x1f, 0x8b]);
+    assert_eq!(untar(GzDecoder::new(tar_gz.as_slice())), expected);
+
+    Ok(())
+}
+
+#[test]
+fn archive_zip() -> Result<(), Error> {
+    let (tmp, oid) = repository();
+    let repo = Repository::open(tmp.path())?;
+
+    let mut zip = Vec::new();
+    repo.archive_with(
+        oid,
+        &ArchiveOptions::new(Format::Zip).prefix("src-").path("src"),
+        Cursor::new(&mut zip),
+    )?;
+    // The commit id is the comment of the archive.
+    assert!(zip.ends_with(oid.to_string().as_bytes()));
+    assert_eq!(
+        unzip(&zip),
+        entries(&[
+            (
+                "src-.gitattributes",
+                0o100644,
+                "generated.rs export-ignore\n"
+            ),
+            ("src-main.rs", 0o100644, MAIN),
+        ])
+    );
+
+    let mut zip = Vec::new();
+    repo.archive(oid, Format::Zip, "", &mut zip)?;
+    let actual = unzip(&zip);
+    assert_eq!(actual["build.sh"], (0o100755, "#!/bin/sh\n".to_owned()));
+    assert_eq!(actual["docs/"], (0o040755, String::new()));
+    assert_eq!(
+        actual["docs/README.md"],
+        (0o120777, "../README.md".to_owned())
+    );
+    assert!(!actual.contains_key("tests/"));
+    assert!(!actual.contains_key("tests/test.rs"));
+
+    assert!(matches!(
+        repo.archive_with(
+            oid,
+            &ArchiveOptions::new(Format::Zip).path("README.md"),
+            Vec::new()
+        ),
+        Err(Error::Archive(_))
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use std::{path::Path, str::FromStr};
+
+use radicle_git_ext::ref_format::refname;
+use radicle_surf::{Branch, Error, Oid, Repository};
+
+use super::GIT_PLATINUM;
+
+#[test]
+fn blame_readme() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let blame = repo.blame(Branch::local(refname!("master")), &"README.md")?;
+    let hunks = blame.hunks().collect::<Vec<_>>();
+
+    let expected = Oid::from_str("80bacafba303bf0cdf6142921f430ff265f25095")?;
+    assert_eq!(hunks.len(), 1);
+    assert_eq!(hunks[0].commit.id, expected);
+    assert_eq!(hunks[0].lines, 1..3);
+    assert_eq!(hunks[0].orig_path, Path::new("README.md"));
+    assert_eq!(blame.hunk_for_line(2).map(|h| h.commit.id), Some(expected));
+    assert!(blame.hunk_for_line(3).is_none());
+
+    Ok(())
+}
+
+#[test]
+fn blame_follows_renames() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let blame = repo.blame(Branch::local(refname!("diff-test")), &"emoji.txt")?;
+
+    let expected = Oid::from_str("1e0206da8571ca71c51c91154e2fee376e09b4e7")?;
+    let hunks = blame.hunks().collect::<Vec<_>>();
+    // Every line predates the rename, so is attributed to the old path.
+    assert!(!hunks.is_empty());
+    for hunk in hunks {
+        assert_eq!(hunk.commit.id, expected);
+        assert_eq!(hunk.orig_path, Path::new("text/emoji.txt"));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn blame_missing_path() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let blame = repo.blame(Branch::local(refname!("master")), &"does/not/exist");
+    assert!(matches!(blame, Err(Error::Repo(_))));
+
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use std::path::PathBuf;
+
+use radicle_surf::{codeowners::CodeOwners, Error, Repository};
+
+use super::fixture::{commit, tmp_repo};
+
+const CODEOWNERS: &str = r"# Default owners
+*                   @alice
+*.rs                @rust-team  # Rust code
+/build/             @build
+docs/*              docs@example.com
+apps/               @apps
+/scripts/**/*.sh    @ops
+src/**/generated    @codegen
+src/vendor/
+\#notes             @hash
+my\ file.txt        @space
+!negated            @nobody
+";
+
+#[test]
+fn parse() {
+    let owners = CodeOwners::parse(CODEOWNERS);
+    let rules = owners
+        .rules()
+        .map(|rule| (rule.line, rule.pattern.as_str(), rule.owners.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rules,
+        vec![
+            (2, "*", vec!["@alice".to_owned()]),
+            (3, "*.rs", vec!["@rust-team".to_owned()]),
+            (4, "/build/", vec!["@build".to_owned()]),
+            (5, "docs/*", vec!["docs@example.com".to_owned()]),
+            (6, "apps/", vec!["@apps".to_owned()]),
+            (7, "/scripts/**/*.sh", vec!["@ops".to_owned()]),
+            (8, "src/**/generated", vec!["@codegen".to_owned()]),
+            (9, "src/vendor/", vec![]),
+            (10, "#notes", vec!["@hash".to_owned()]),
+            (11, "my file.txt", vec!["@space".to_owned()]),
+        ]
+    );
+}
+
+#[test]
+fn owners() {
+    let owners = CodeOwners::parse(CODEOWNERS);
+    let owner = |path: &str| owners.owners(path).join(" ");
+
+    // The last matching rule wins.
+    assert_eq!(owner("README.md"), "@alice");
+    assert_eq!(owner("src/lib.rs"), "@rust-team");
+    assert_eq!(owner("build.rs"), "@rust-team");
+
+    // Directory patterns match everything within the directory.
+    assert_eq!(owner("build/out/main.o"), "@build");
+    assert_eq!(owner("apps/web/index.html"), "@apps");
+    assert_eq!(owner("nested/apps/web/index.html"), "@apps");
+    // Anchored patterns only match at the root.
+    assert_eq!(owner("nested/build/main.o"), "@alice");
+    // `docs/*` only matches files directly within `docs`.
+    assert_eq!(owner("docs/index.md"), "docs@example.com");
+    assert_eq!(owner("docs/guide/index.md"), "@alice");
+
+    assert_eq!(owner("scripts/ci.sh"), "@ops");
+    assert_eq!(owner("scripts/release/tag.sh"), "@ops");
+    assert_eq!(owner("tools/scripts/ci.sh"), "@alice");
+    assert_eq!(owner("src/generated/api.rs"), "@codegen");
+    assert_eq!(owner("src/a/b/generated"), "@codegen");
+
+    // A rule without owners removes the owners of earlier rules.
+    assert_eq!(owner("src/vendor/lib.rs"), "");
+    assert_eq!(
+        owners.rule_for("src/vendor/lib.rs").map(|rule| rule.line),
+        Some(9)
+    );
+
+    assert_eq!(owner("#notes"), "@hash");
+    assert_eq!(owner("my file.txt"), "@space");
+    assert_eq!(owner("negated"), "@alice");
+
+    assert_eq!(CodeOwners::parse("").owners("README.md"), &[] as &[String]);
+}
+
+#[test]
+fn invalid_patterns() {
+    let owners =
+        CodeOwners::parse("* @alice\n[z-a].md @nobody\n[a&&b].md @bob\n[unclosed @carol\n");
+    let rules = owners
+        .rules()
+        .map(|rule| (rule.line, rule.pattern.as_str()))
+        .collect::<Vec<_>>();
+    // A pattern that matches nothing is dropped.
+    assert_eq!(rules, vec![(1, "*"), (3, "[a&&b].md"), (4, "[unclosed")]);
+
+    assert_eq!(owners.owners("z.md"), &["@alice"]);
+    assert_eq!(owners.owners("&.md"), &["@bob"]);
+    assert_eq!(owners.owners("[unclosed"), &["@carol"]);
+}
+
+#[test]
+fn repository_codeowners() -> Result<(), Error> {
+    let tmp = tmp_repo();
+    let repo = Repository::open(tmp.path())?;
+
+    let none = commit(&tmp, &[("README.md", "readme")]);
+    assert!(repo.codeowners(none)?.is_none());
+
+    let old = commit(
+        &tmp,
+        &[
+            (".github/CODEOWNERS", "* @alice\n/src/ @bob @carol\n"),
+            ("CODEOWNERS", "* @ignored\n"),
+            ("README.md", "readme"),
+            ("src/lib.rs", "fn lib() {}"),
+            ("src/main.rs", "fn main() {}"),
+        ],
+    );
+    let owners = repo.codeowners(old)?.unwrap();
+    assert_eq!(owners.owners("README.md"), ["@alice"]);
+    assert_eq!(owners.owners("src/lib.rs"), ["@bob", "@carol"]);
+
+    let new = commit(
+        &tmp,
+        &[
+            (".github/CODEOWNERS", "* @alice\n/src/ @bob @carol\n"),
+            ("CODEOWNERS", "* @ignored\n"),
+            ("README.md", "new readme"),
+            ("src/lib.rs", "fn lib() {}"),
+            ("src/main.rs", "fn main() { lib() }"),
+        ],
+    );
+    let diff = repo.diff(old, new)?;
+    let affected = owners.affected_owners(&diff);
+    assert_eq!(
+        affected.into_iter().collect::<Vec<_>>(),
+        vec![
+            ("@alice".to_owned(), vec![PathBuf::from("README.md")]),
+            ("@bob".to_owned(), vec![PathBuf::from("src/main.rs")]),
+            ("@carol".to_owned(), vec![PathBuf::from("src/main.rs")]),
+        ]
+    );
+
+    Ok(())
+}
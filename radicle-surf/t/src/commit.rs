@@ -1,8 +1,8 @@
 use std::str::FromStr;
 
 use proptest::prelude::*;
-use radicle_git_ext::Oid;
-use radicle_surf::{Author, Commit, Time};
+use radicle_git_ext::{ref_format::refname, Oid};
+use radicle_surf::{Author, Commit, Note, Time};
 use test_helpers::roundtrip;
 
 proptest! {
@@ -13,8 +13,36 @@ proptest! {
 }
 
 fn commits_strategy() -> impl Strategy<Value = Commit> {
-    ("[a-fA-F0-9]{40}", any::<String>(), any::<i64>()).prop_map(|(id, text, time)| Commit {
+    (
+        "[a-fA-F0-9]{40}",
+        any::<String>(),
+        any::<i64>(),
+        prop::collection::vec(notes_strategy(), 0..2),
+    )
+        .prop_map(|(id, text, time, notes)| Commit {
+            id: Oid::from_str(&id).unwrap(),
+            author: Author {
+                name: text.clone(),
+                email: text.clone(),
+                time: Time::new(time, 0),
+            },
+            committer: Author {
+                name: text.clone(),
+                email: text.clone(),
+                time: Time::new(time, 0),
+            },
+            message: text.clone(),
+            summary: text,
+            parents: vec![Oid::from_str(&id).unwrap(), Oid::from_str(&id).unwrap()],
+            notes,
+        })
+}
+
+fn notes_strategy() -> impl Strategy<Value = Note> {
+    ("[a-fA-F0-9]{40}", any::<String>(), any::<i64>()).prop_map(|(id, text, time)| Note {
         id: Oid::from_str(&id).unwrap(),
+        target: Oid::from_str(&id).unwrap(),
+        name: refname!("review"),
         author: Author {
             name: text.clone(),
             email: text.clone(),
@@ -25,8 +53,6 @@ fn commits_strategy() -> impl Strategy<Value = Commit> {
             email: text.clone(),
             time: Time::new(time, 0),
         },
-        message: text.clone(),
-        summary: text,
-        parents: vec![Oid::from_str(&id).unwrap(), Oid::from_str(&id).unwrap()],
+        message: text,
     })
 }
@@ -2,14 +2,17 @@ use pretty_assertions::assert_eq;
 use radicle_git_ext::{ref_format::refname, Oid};
 use radicle_surf::{
     diff::{
-        Added, Diff, DiffContent, DiffFile, EofNewLine, FileDiff, FileMode, FileStats, Hunk, Line,
-        Modification, Modified, Stats,
+        inline::Granularity, Added, Diff, DiffContent, DiffFile, DiffOptions, DiffStream,
+        EofNewLine, FileDiff, FileMode, FileStats, Hunk, Line, Modification, Modified, Stats,
     },
     Branch, Error, Repository,
 };
 use std::{path::Path, str::FromStr};
 
-use super::GIT_PLATINUM;
+use super::{
+    fixture::{commit, commit_with, tmp_repo},
+    GIT_PLATINUM,
+};
 
 #[test]
 fn test_initial_diff() -> Result<(), Error> {
@@ -573,6 +576,7 @@ index 3f69208f3..cbc843c82 100644
                     "oid": "3f69208f30000000000000000000000000000000",
                 },
                 "oldPath": "radicle/src/node/tracking/config.rs",
+                "similarity": 96,
                 "status": "moved"
             },
         ],
@@ -669,3 +673,600 @@ index f89e4c0..7c56eb7 100644
         Some(EofNewLine::NewMissing)
     );
 }
+
+#[test]
+fn test_highlight_words() {
+    let hunk = Hunk {
+        header: Line::from(b"@@ -1,3 +1,3 @@\n".to_vec()),
+        lines: vec![
+            Modification::context(b"fn main() {\n".to_vec(), 1, 1),
+            Modification::deletion(b"    let x = foo(1);\n".to_vec(), 2),
+            Modification::addition(b"    let y = foo(10);\n".to_vec(), 2),
+            Modification::addition(b"}\n".to_vec(), 3),
+        ],
+        old: 1..3,
+        new: 1..4,
+    };
+
+    let words = hunk.highlight(Granularity::Word);
+    let changes = words
+        .lines
+        .iter()
+        .map(|l| l.changes.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        changes,
+        vec![vec![], vec![8..9, 16..17], vec![8..9, 16..18], vec![]]
+    );
+
+    let chars = hunk.highlight(Granularity::Char);
+    assert_eq!(chars.lines[1].changes, vec![8..9]);
+    assert_eq!(chars.lines[2].changes, vec![8..9, 17..18]);
+
+    assert_eq!(
+        serde_json::to_value(&words.lines[1]).unwrap(),
+        serde_json::json!({
+            "line": "    let x = foo(1);\n",
+            "lineNo": 2,
+            "type": "deletion",
+            "changes": [{ "start": 8, "end": 9 }, { "start": 16, "end": 17 }],
+        })
+    );
+}
+
+#[test]
+fn test_diff_options() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let rev = "29b78a041bffb955b597719b27c51134a49555c1";
+
+    let diff = repo.diff_commit(rev)?;
+    assert_eq!(diff.moved().count(), 1);
+
+    let diff = repo.diff_commit_with(rev, &DiffOptions::default().no_renames().no_copies())?;
+    assert_eq!(diff.moved().count(), 0);
+    assert!(diff
+        .deleted()
+        .any(|deleted| deleted.path == Path::new("text/emoji.txt")));
+    assert!(diff
+        .added()
+        .any(|added| added.path == Path::new("emoji.txt")));
+
+    let options = DiffOptions::default().context_lines(0);
+    let readme = repo.diff_file_with(
+        &"README.md",
+        "d6880352fc7fda8f521ae9b7357668b17bb5bad5",
+        "223aaf87d6ea62eef0014857640fd7c8dd0f80b5",
+        &options,
+    )?;
+    let FileDiff::Modified(modified) = readme else {
+        panic!("README.md should be modified");
+    };
+    let DiffContent::Plain { hunks, .. } = modified.diff else {
+        panic!("README.md should be plain text");
+    };
+    assert!(hunks
+        .iter()
+        .flat_map(|hunk| hunk.lines.iter())
+        .all(|line| !matches!(line, Modification::Context { .. })));
+
+    Ok(())
+}
+
+#[test]
+fn test_render_patch() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let diff = repo.diff_commit("29b78a041bffb955b597719b27c51134a49555c1")?;
+    let patch = diff.to_string();
+
+    assert!(patch.starts_with(
+        "diff --git a/LICENSE b/LICENSE
+new file mode 100644
+index 0000000000000000000000000000000000000000..02f70f56ec62396ceaf38804c37e169e875ab291
+--- /dev/null
++++ b/LICENSE
+@@ -0,0 +1,2 @@
++This is a license file.
++
+"
+    ));
+    assert!(patch.contains(
+        "diff --git a/text/emoji.txt b/emoji.txt
+similarity index 100%
+rename from text/emoji.txt
+rename to emoji.txt
+"
+    ));
+
+    // The rendered patch is parsed by libgit2 into the same diff.
+    let diff = repo.diff_commit("80bacafba303bf0cdf6142921f430ff265f25095")?;
+    let parsed = Diff::try_from(git2::Diff::from_buffer(diff.to_string().as_bytes()).unwrap())?;
+    assert_eq!(parsed, diff);
+
+    Ok(())
+}
+
+#[test]
+fn test_render_patch_applies() -> Result<(), Error> {
+    let lines =
+        |n: usize, tag: &str| -> String { (1..=n).map(|i| format!("line {i}{tag}\n")).collect() };
+    let (text, renamed, source, copy) = (
+        lines(20, ""),
+        lines(20, "") + "one more line\n",
+        lines(20, " of the source"),
+        lines(20, " of the source") + "copied\n",
+    );
+    let tmp = tmp_repo();
+    let from = commit_with(
+        &tmp,
+        &[
+            ("binary", FileMode::Blob.into(), "\0\x01\x02"),
+            ("deleted.txt", FileMode::Blob.into(), "deleted\n"),
+            ("eof.txt", FileMode::Blob.into(), "a\nb"),
+            ("exec.sh", FileMode::Blob.into(), "echo\n"),
+            ("old.txt", FileMode::Blob.into(), &text),
+            ("source.txt", FileMode::Blob.into(), &source),
+        ],
+        "from",
+        &[],
+    );
+    let to = commit_with(
+        &tmp,
+        &[
+            ("added.txt", FileMode::Blob.into(), "added\n"),
+            ("binary", FileMode::Blob.into(), "\0\x03\x04"),
+            ("copy.txt", FileMode::Blob.into(), &copy),
+            ("eof.txt", FileMode::Blob.into(), "a\nc\n"),
+            ("exec.sh", FileMode::BlobExecutable.into(), "echo\n"),
+            ("new.txt", FileMode::Blob.into(), &renamed),
+            (
+                "source.txt",
+                FileMode::Blob.into(),
+                &(source.clone() + "changed\n"),
+            ),
+        ],
+        "to",
+        &[from],
+    );
+    let repo = Repository::open(tmp.path())?;
+    let diff = repo.diff(from, to)?;
+    let patch = diff.to_string();
+    assert!(patch.contains("similarity index 97%\nrename from old.txt\nrename to new.txt\n"));
+    assert!(patch.contains("similarity index 97%\ncopy from source.txt\ncopy to copy.txt\n"));
+
+    // `git apply` accepts the patch on top of the old tree.
+    let from = tmp.find_commit(from.into()).unwrap();
+    tmp.checkout_tree(
+        from.as_object(),
+        Some(
+            git2::build::CheckoutBuilder::new()
+                .force()
+                .recreate_missing(true),
+        ),
+    )
+    .unwrap();
+    let workdir = tmp.workdir().unwrap();
+    std::fs::write(workdir.join("diff.patch"), &patch).unwrap();
+    let output = std::process::Command::new("git")
+        .args(["apply", "--check", "diff.patch"])
+        .current_dir(workdir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_patch_without_hunks() -> Result<(), Error> {
+    let tmp = tmp_repo();
+    let from = commit_with(
+        &tmp,
+        &[
+            ("exec.sh", FileMode::Blob.into(), "echo\n"),
+            ("old.txt", FileMode::Blob.into(), "text\n"),
+        ],
+        "from",
+        &[],
+    );
+    let to = commit_with(
+        &tmp,
+        &[
+            ("exec.sh", FileMode::BlobExecutable.into(), "echo\n"),
+            ("new.txt", FileMode::Blob.into(), "text\n"),
+        ],
+        "to",
+        &[from],
+    );
+    let repo = Repository::open(tmp.path())?;
+    let diff = repo.diff(from, to)?;
+    let parsed = Diff::parse(diff.to_string().as_bytes())?;
+
+    // The patch has no `index` lines for files whose content did not change,
+    // so everything but the object ids is compared.
+    let moved = |diff: &Diff| {
+        diff.moved()
+            .map(|m| {
+                let (old, new) = (&m.old.mode, &m.new.mode);
+                (
+                    m.old_path.clone(),
+                    m.new_path.clone(),
+                    old.clone(),
+                    new.clone(),
+                    m.similarity,
+                    m.diff.clone(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let modified = |diff: &Diff| {
+        diff.modified()
+            .map(|m| {
+                (
+                    m.path.clone(),
+                    m.old.mode.clone(),
+                    m.new.mode.clone(),
+                    m.diff.clone(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(moved(&parsed), moved(&diff));
+    assert_eq!(modified(&parsed), modified(&diff));
+    assert!(diff.moved().all(|m| m.diff == DiffContent::Empty));
+    assert!(diff.modified().all(|m| m.diff == DiffContent::Empty));
+
+    Ok(())
+}
+
+#[test]
+fn test_render_missing_eof_newline() {
+    let buf = r#"diff --git a/.env b/.env
+index f89e4c0000000000000000000000000000000000..7c56eb7000000000000000000000000000000000 100644
+--- a/.env
++++ b/.env
+@@ -1,2 +1,2 @@
+ a
+-hello=123
+\ No newline at end of file
++hello=1234
+"#;
+    let diff = git2::Diff::from_buffer(buf.as_bytes()).unwrap();
+    let diff = Diff::try_from(diff).unwrap();
+    assert_eq!(diff.to_string(), buf);
+}
+
+#[test]
+fn test_render_quoted_paths() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let diff = repo.diff_commit("a0dd9122d33dff2a35f564d564db127152c88e02")?;
+    let patch = diff.to_string();
+
+    assert!(patch.contains(r#"diff --git "a/special/faux\\path" "b/special/faux\\path""#));
+    assert!(patch.contains(
+        r#"diff --git "a/special/\360\237\221\271\360\237\221\271\360\237\221\271" "b/special/\360\237\221\271\360\237\221\271\360\237\221\271""#
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_patch() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let diff = repo.diff_commit("80bacafba303bf0cdf6142921f430ff265f25095")?;
+    assert_eq!(Diff::parse(diff.to_string().as_bytes())?, diff);
+
+    // Renames and copies without changes have no `index` line.
+    let diff = repo.diff_commit("29b78a041bffb955b597719b27c51134a49555c1")?;
+    let parsed: Diff = diff.to_string().parse()?;
+    assert_eq!(parsed.stats(), diff.stats());
+    assert_eq!(
+        parsed.added().collect::<Vec<_>>(),
+        diff.added().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        parsed.deleted().collect::<Vec<_>>(),
+        diff.deleted().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        parsed.modified().collect::<Vec<_>>(),
+        diff.modified().collect::<Vec<_>>()
+    );
+    let moved = parsed.moved().next().unwrap();
+    assert_eq!(moved.old_path, Path::new("text/emoji.txt"));
+    assert_eq!(moved.new_path, Path::new("emoji.txt"));
+    assert_eq!(moved.old, moved.new);
+    let copied = parsed.copied().next().unwrap();
+    assert_eq!(copied.old_path, Path::new("README.md"));
+    assert_eq!(copied.new_path, Path::new("file_operations/copied.md"));
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_extended_headers() -> Result<(), Error> {
+    let buf = r#"From: Alice <alice@example.com>
+Subject: [PATCH] Various changes
+
+---
+diff --git a/run.sh b/run.sh
+old mode 100644
+new mode 100755
+diff --git a/logo.png b/logo.png
+index 3f69208..cbc843c 100644
+Binary files a/logo.png and b/logo.png differ
+diff --git "a/tab\there" "b/tab\there"
+new file mode 100644
+index 0000000..f89e4c0
+--- /dev/null
++++ "b/tab\there"
+@@ -0,0 +1 @@
++hello
+\ No newline at end of file
+-- 
+2.42.0
+"#;
+    let diff = Diff::parse(buf.as_bytes())?;
+    assert_eq!(diff.files().count(), 3);
+
+    let mode = diff.modified().next().unwrap();
+    assert_eq!(mode.path, Path::new("run.sh"));
+    assert_eq!(mode.old.mode, FileMode::Blob);
+    assert_eq!(mode.new.mode, FileMode::BlobExecutable);
+
+    let binary = diff.modified().nth(1).unwrap();
+    assert_eq!(binary.path, Path::new("logo.png"));
+    assert_eq!(binary.diff, DiffContent::Binary);
+    assert_eq!(
+        binary.new.oid,
+        Oid::from_str("cbc843c000000000000000000000000000000000").unwrap()
+    );
+
+    let added = diff.added().next().unwrap();
+    assert_eq!(added.path, Path::new("tab\there"));
+    assert_eq!(added.diff.eof(), Some(EofNewLine::NewMissing));
+    assert_eq!(added.diff.stats().unwrap().additions, 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_unified() -> Result<(), Error> {
+    let buf = "--- a/.env\t2023-01-01 00:00:00.000000000 +0000
++++ b/.env\t2023-01-02 00:00:00.000000000 +0000
+@@ -1,2 +1,2 @@
+ a
+-hello=123
++hello=1234
+";
+    let diff = Diff::parse(buf.as_bytes())?;
+    let modified = diff.modified().next().unwrap();
+    assert_eq!(modified.path, Path::new(".env"));
+    let DiffContent::Plain { hunks, .. } = &modified.diff else {
+        panic!("expected a plain diff");
+    };
+    assert_eq!(
+        hunks.0[0].lines,
+        vec![
+            Modification::context(b"a\n".to_vec(), 1, 1),
+            Modification::deletion(b"hello=123\n".to_vec(), 2),
+            Modification::addition(b"hello=1234\n".to_vec(), 2),
+        ]
+    );
+
+    assert!(matches!(
+        Diff::parse(b"--- a/.env\n+++ b/.env\n@@ -1,2 +1,2 @@\n a\n"),
+        Err(radicle_surf::diff::parse::error::Parse::HunkTruncated(4))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_diff_workdir() -> Result<(), Error> {
+    let tmp = tmp_repo();
+    let workdir = tmp.workdir().unwrap();
+    let write = |path: &str, content: &str| {
+        let path = workdir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    };
+    let stage = |path: &str| {
+        let mut index = tmp.index().unwrap();
+        index.add_path(Path::new(path)).unwrap();
+        index.write().unwrap();
+    };
+
+    write("README.md", "Hello\n");
+    stage("README.md");
+    let repo = Repository::open(workdir)?;
+
+    // Nothing is committed yet, so the staged file is added.
+    let staged = repo.diff_index()?;
+    assert_eq!(staged.added().next().unwrap().path, Path::new("README.md"));
+    let head = commit(&tmp, &[("README.md", "Hello\n")]);
+    tmp.set_head_detached(head.into())?;
+    assert_eq!(repo.diff_index()?.files().count(), 0);
+
+    write("README.md", "Hello, world\n");
+    write("LICENSE", "MIT\n");
+    stage("LICENSE");
+    write("src/main.rs", "fn main() {}\n");
+
+    let staged = repo.diff_index()?;
+    assert_eq!(
+        staged.files().map(|f| f.path()).collect::<Vec<_>>(),
+        vec![Path::new("LICENSE")]
+    );
+
+    let unstaged = repo.diff_workdir()?;
+    assert_eq!(
+        unstaged.modified().next().unwrap().path,
+        Path::new("README.md")
+    );
+    let untracked = unstaged.added().next().unwrap();
+    assert_eq!(untracked.path, Path::new("src/main.rs"));
+    assert_eq!(
+        untracked.diff.stats(),
+        Some(&FileStats {
+            additions: 1,
+            deletions: 0
+        })
+    );
+
+    let all = repo.diff_rev_to_workdir(head)?;
+    assert_eq!(
+        all.files().map(|f| f.path()).collect::<Vec<_>>(),
+        vec![
+            Path::new("LICENSE"),
+            Path::new("README.md"),
+            Path::new("src/main.rs")
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_write_stat() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let diff = repo.diff_commit("29b78a041bffb955b597719b27c51134a49555c1")?;
+    let stat = |width| {
+        let mut buf = Vec::new();
+        diff.write_stat(&mut buf, width).unwrap();
+        String::from_utf8(buf).unwrap()
+    };
+
+    assert_eq!(
+        stat(80),
+        " LICENSE                                | 2 ++
+ README.md                              | 4 ++--
+ text/emoji.txt => emoji.txt            | 0
+ README.md => file_operations/copied.md | 0
+ text/arrows.txt                        | 7 -------
+ 5 files changed, 4 insertions(+), 9 deletions(-)
+"
+    );
+    assert_eq!(
+        stat(30),
+        " LICENSE           | 2 ++
+ README.md         | 4 +--
+ ...t => emoji.txt | 0
+ .../copied.md     | 0
+ text/arrows.txt   | 7 ------
+ 5 files changed, 4 insertions(+), 9 deletions(-)
+"
+    );
+
+    let diff = repo.diff_commit("e24124b7538658220b5aaf3b6ef53758f0a106dc")?;
+    let mut buf = Vec::new();
+    diff.write_stat(&mut buf, 80).unwrap();
+    assert!(String::from_utf8(buf)
+        .unwrap()
+        .starts_with(" {examples => src}/Eval.hs       | 0\n"));
+
+    Ok(())
+}
+
+#[test]
+fn test_write_stat_renames() -> Result<(), Error> {
+    let stat = |old: &str, new: &str| {
+        let patch = format!(
+            "diff --git a/{old} b/{new}\n\
+             similarity index 100%\n\
+             rename from {old}\n\
+             rename to {new}\n"
+        );
+        let mut buf = Vec::new();
+        Diff::from_str(&patch)
+            .unwrap()
+            .write_stat(&mut buf, 80)
+            .unwrap();
+        let stat = String::from_utf8(buf).unwrap();
+        stat.lines()
+            .next()
+            .unwrap()
+            .trim_end_matches(" | 0")
+            .trim()
+            .to_owned()
+    };
+
+    assert_eq!(stat("src/x/lib.rs", "src/lib.rs"), "src/{x => }/lib.rs");
+    assert_eq!(stat("src/lib.rs", "src/x/lib.rs"), "src/{ => x}/lib.rs");
+    assert_eq!(stat("a/b/c.rs", "a/d/c.rs"), "a/{b => d}/c.rs");
+    assert_eq!(stat("old.rs", "new.rs"), "old.rs => new.rs");
+
+    Ok(())
+}
+
+#[test]
+fn test_dirstat() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let diff = repo.diff(
+        "1e0206da8571ca71c51c91154e2fee376e09b4e7",
+        "29b78a041bffb955b597719b27c51134a49555c1",
+    )?;
+    let dirstat = diff.dirstat();
+    assert_eq!(
+        dirstat
+            .iter()
+            .map(|d| (d.path.as_path(), d.lines))
+            .collect::<Vec<_>>(),
+        vec![
+            (Path::new("file_operations"), 2),
+            (Path::new("src"), 101),
+            (Path::new("text"), 7),
+        ]
+    );
+    let total = diff.stats().insertions + diff.stats().deletions;
+    assert_eq!(dirstat[1].percent, 101.0 * 100.0 / total as f32);
+
+    Ok(())
+}
+
+#[test]
+fn test_diff_stream() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let commit = "29b78a041bffb955b597719b27c51134a49555c1";
+    let diff = repo.diff_commit(commit)?;
+    let stream = repo.diff_commit_stream(commit)?;
+    assert_eq!(stream.len(), 5);
+    assert_eq!(
+        stream.collect::<Result<Vec<_>, _>>()?,
+        diff.clone().into_files()
+    );
+
+    // The paths of the files whose changes were skipped.
+    let too_large = |stream: DiffStream| {
+        stream
+            .filter_map(|file| {
+                let file = file.unwrap();
+                let content = match &file {
+                    FileDiff::Added(x) => &x.diff,
+                    FileDiff::Deleted(x) => &x.diff,
+                    FileDiff::Modified(x) => &x.diff,
+                    FileDiff::Moved(x) => &x.diff,
+                    FileDiff::Copied(x) => &x.diff,
+                };
+                (*content == DiffContent::TooLarge).then(|| file.path().to_path_buf())
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        too_large(repo.diff_commit_stream(commit)?.max_lines(5)),
+        vec![Path::new("text/arrows.txt")]
+    );
+    assert_eq!(
+        too_large(repo.diff_commit_stream(commit)?.max_bytes(100)),
+        vec![
+            Path::new("README.md"),
+            Path::new("emoji.txt"),
+            Path::new("file_operations/copied.md"),
+            Path::new("text/arrows.txt"),
+        ]
+    );
+    Ok(())
+}
@@ -107,7 +107,47 @@ mod directory {
         assert!(matches!(entry, fs::Entry::Directory(_)));
         if let fs::Entry::Directory(d) = entry {
             assert_eq!(16297, d.size(&repo).unwrap());
+            assert_eq!(2, d.file_count(&repo).unwrap());
         }
+
+        // Nested directories are counted once.
+        assert_eq!(
+            root.disk_usage(&repo).unwrap(),
+            fs::DiskUsage {
+                bytes: 141788,
+                files: 24
+            }
+        );
+    }
+
+    #[test]
+    fn directory_disk_usage_cached() {
+        let repo = Repository::open(GIT_PLATINUM).unwrap();
+        let master = repo.root_dir(Branch::local(refname!("master"))).unwrap();
+        let src = master.find_directory(&"src", &repo).unwrap();
+
+        let mut cache = fs::DiskUsageCache::default();
+        let usage = master.disk_usage_cached(&repo, &mut cache).unwrap();
+        assert_eq!(usage, master.disk_usage(&repo).unwrap());
+        // The root and all 12 sub-directories are cached.
+        assert_eq!(cache.len(), 13);
+        assert_eq!(cache.get(&master.id()), Some(usage));
+        assert_eq!(
+            cache.get(&src.id()),
+            Some(fs::DiskUsage {
+                bytes: 16297,
+                files: 2
+            })
+        );
+
+        let dev = repo.root_dir(Branch::local(refname!("dev"))).unwrap();
+        assert_eq!(
+            dev.disk_usage_cached(&repo, &mut cache).unwrap(),
+            fs::DiskUsage {
+                bytes: 144221,
+                files: 14
+            }
+        );
     }
 
     #[test]
@@ -138,6 +178,83 @@ mod directory {
         );
     }
 
+    #[test]
+    fn directory_walk() {
+        let repo = Repository::open(GIT_PLATINUM).unwrap();
+        let root = repo.root_dir(Branch::local(refname!("master"))).unwrap();
+        let paths = |walk: fs::Walk| walk.map(|entry| entry.unwrap().path()).collect::<Vec<_>>();
+
+        let this = root.find_directory(&"this/is/a/really", &repo).unwrap();
+        assert_eq!(
+            paths(this.walk(&repo)),
+            vec![
+                Path::new("this/is/a/really/deeply"),
+                Path::new("this/is/a/really/deeply/nested"),
+                Path::new("this/is/a/really/deeply/nested/directory"),
+                Path::new("this/is/a/really/deeply/nested/directory/tree"),
+                Path::new("this/is/a/really/deeply/nested/directory/tree/.gitkeep"),
+            ]
+        );
+        assert_eq!(
+            paths(this.walk(&repo).order(fs::WalkOrder::PostOrder)),
+            vec![
+                Path::new("this/is/a/really/deeply/nested/directory/tree/.gitkeep"),
+                Path::new("this/is/a/really/deeply/nested/directory/tree"),
+                Path::new("this/is/a/really/deeply/nested/directory"),
+                Path::new("this/is/a/really/deeply/nested"),
+                Path::new("this/is/a/really/deeply"),
+            ]
+        );
+        assert_eq!(
+            paths(this.walk(&repo).max_depth(2)),
+            vec![
+                Path::new("this/is/a/really/deeply"),
+                Path::new("this/is/a/really/deeply/nested"),
+            ]
+        );
+
+        assert_eq!(
+            paths(
+                root.walk(&repo)
+                    .include("*.txt")
+                    .include("*.rs")
+                    .exclude("text/emoji.txt")
+            ),
+            vec![
+                Path::new("src/memory.rs"),
+                Path::new("text/arrows.txt"),
+                Path::new("text/garden.txt"),
+            ]
+        );
+
+        // Skipping a subtree never enters it.
+        let mut walk = root.walk(&repo).max_depth(2);
+        let mut visited = Vec::new();
+        while let Some(entry) = walk.next() {
+            let entry = entry.unwrap();
+            if entry.is_directory() && entry.name() != "text" {
+                walk.skip_subtree();
+            }
+            visited.push(entry.path());
+        }
+        assert_eq!(
+            visited,
+            vec![
+                Path::new(".i-am-well-hidden"),
+                Path::new(".i-too-am-hidden"),
+                Path::new("README.md"),
+                Path::new("bin"),
+                Path::new("special"),
+                Path::new("src"),
+                Path::new("text"),
+                Path::new("text/arrows.txt"),
+                Path::new("text/emoji.txt"),
+                Path::new("text/garden.txt"),
+                Path::new("this"),
+            ]
+        );
+    }
+
     /// Test that directories and files with glob metacharacters in their names
     /// can be browsed and have their history retrieved correctly.
     ///
@@ -0,0 +1,74 @@
+//! Helpers for building throwaway repositories in tests.
+
+use radicle_surf::Oid;
+use test_helpers::tempdir::WithTmpDir;
+
+/// Initialises a repository in a temporary directory, which is removed
+/// when the returned value is dropped.
+pub fn tmp_repo() -> WithTmpDir<git2::Repository> {
+    WithTmpDir::new(|path| git2::Repository::init(path).map_err(std::io::Error::other)).unwrap()
+}
+
+/// Commits the `files`, given by their paths, as the whole tree.
+pub fn commit(repo: &git2::Repository, files: &[(&str, &str)]) -> Oid {
+    commit_on(repo, files, &[])
+}
+
+/// Commits the `files`, given by their paths, as the whole tree, on top of
+/// `parents`.
+pub fn commit_on(repo: &git2::Repository, files: &[(&str, &str)], parents: &[Oid]) -> Oid {
+    let files = files
+        .iter()
+        .map(|(path, content)| (*path, git2::FileMode::Blob, *content))
+        .collect::<Vec<_>>();
+    commit_with(repo, &files, "commit", parents)
+}
+
+/// Commits the `files`, given by their paths and modes, as the whole tree,
+/// on top of `parents`.
+///
+/// The author and committer are `Test <test@test.com>`, at the time
+/// `1700000000`.
+pub fn commit_with(
+    repo: &git2::Repository,
+    files: &[(&str, git2::FileMode, &str)],
+    message: &str,
+    parents: &[Oid],
+) -> Oid {
+    let mut index = git2::Index::new().unwrap();
+    for (path, mode, content) in files {
+        let blob = repo.blob(content.as_bytes()).unwrap();
+        let entry = git2::IndexEntry {
+            ctime: git2::IndexTime::new(0, 0),
+            mtime: git2::IndexTime::new(0, 0),
+            dev: 0,
+            ino: 0,
+            mode: (*mode).into(),
+            uid: 0,
+            gid: 0,
+            file_size: content.len() as u32,
+            id: blob,
+            flags: 0,
+            flags_extended: 0,
+            path: path.as_bytes().to_vec(),
+        };
+        index.add(&entry).unwrap();
+    }
+    let tree = repo.find_tree(index.write_tree_to(repo).unwrap()).unwrap();
+    let parents = parents
+        .iter()
+        .map(|oid| repo.find_commit((*oid).into()).unwrap())
+        .collect::<Vec<_>>();
+    let time = git2::Time::new(1700000000, 0);
+    let sig = git2::Signature::new("Test", "test@test.com", &time).unwrap();
+    repo.commit(
+        None,
+        &sig,
+        &sig,
+        message,
+        &tree,
+        &parents.iter().collect::<Vec<_>>(),
+    )
+    .unwrap()
+    .into()
+}
@@ -0,0 +1,413 @@
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use radicle_git_ext::ref_format::refname;
+use radicle_surf::{Branch, Commit, Cursor, Error, Oid, Repository, Time};
+
+use super::{
+    fixture::{commit, commit_on, tmp_repo},
+    GIT_PLATINUM,
+};
+
+fn summaries(history: impl Iterator<Item = Result<Commit, Error>>) -> Vec<String> {
+    history.map(|commit| commit.unwrap().summary).collect()
+}
+
+#[test]
+fn by_author_and_time() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let history = repo
+        .history(Branch::local(refname!("master")))?
+        .by_author("Fintan.Halpenny@gmail.com")
+        .since(Time::new(1578309972, 0))
+        .until(Time::new(1584362684, 0));
+
+    assert_eq!(
+        summaries(history),
+        vec![
+            "Updated README with radicle-surf link",
+            "Extend the docs (#2)"
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn by_committer_and_message() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let history = repo
+        .history(Branch::local(refname!("master")))?
+        .by_committer("rudolfs@osins.org")
+        .by_message("file");
+
+    assert_eq!(
+        summaries(history),
+        vec![
+            "Delete unneeded file",
+            "Add file which will be deleted later",
+            "Add dotfiles",
+            "Add text files",
+            "Add some binary files",
+            "Add some source code example files",
+        ]
+    );
+
+    let history = repo
+        .history(Branch::local(refname!("master")))?
+        .by_message_regex(r"(?m)\(#\d\)$")?
+        .no_merges();
+    assert_eq!(
+        summaries(history),
+        vec![
+            "Add files with special characters in their filenames (#5)",
+            "Remove src/Folder.svelte (#3)",
+            "Extend the docs (#2)",
+            "Add a long commit message to commit message body (#1)",
+        ]
+    );
+
+    assert!(matches!(
+        repo.history(Branch::local(refname!("master")))?
+            .by_message_regex("("),
+        Err(Error::Regex(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn merges_only() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let history = repo
+        .history(Branch::local(refname!("master")))?
+        .merges_only();
+
+    assert_eq!(
+        summaries(history),
+        vec!["Merge pull request #4 from FintanH/fintan/update-readme-no-sig"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn by_paths() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let history = repo
+        .history(Branch::local(refname!("master")))?
+        .by_paths([Path::new("src/Folder.svelte"), Path::new("bin")]);
+
+    assert_eq!(
+        summaries(history),
+        vec![
+            "Remove src/Folder.svelte (#3)",
+            "Move examples to \"src\"",
+            "Add some binary files",
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn range() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let history = repo.history_range(
+        Branch::local(refname!("master")),
+        Branch::local(refname!("dev")),
+    )?;
+
+    assert_eq!(summaries(history), vec!["Commit on the dev branch"]);
+
+    Ok(())
+}
+
+#[test]
+fn symmetric_difference() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let history = repo.history_symmetric(
+        Branch::local(refname!("dev")),
+        Branch::local(refname!("master")),
+    )?;
+    let mut commits = summaries(history);
+    commits.sort();
+
+    assert_eq!(
+        commits,
+        vec![
+            "Add a long commit message to commit message body (#1)",
+            "Add file which will be deleted later",
+            "Add files with special characters in their filenames (#5)",
+            "Commit on the dev branch",
+            "Delete unneeded file",
+            "Extend the docs (#2)",
+            "Merge pull request #4 from FintanH/fintan/update-readme-no-sig",
+            "Remove src/Folder.svelte (#3)",
+            "Updated README with radicle-surf link",
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn include_and_exclude() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let history = repo
+        .history(Branch::local(refname!("master")))?
+        .include(Branch::local(refname!("dev")))?
+        .exclude("80ded66281a4de2889cc07293a8f10947c6d57fe")?
+        .exclude("1820cb07c1a890016ca5578aa652fd4d4c38967e")?;
+    let mut commits = summaries(history);
+    commits.sort();
+
+    assert_eq!(
+        commits,
+        vec![
+            "Add a long commit message to commit message body (#1)",
+            "Add files with special characters in their filenames (#5)",
+            "Commit on the dev branch",
+            "Extend the docs (#2)",
+            "Merge pull request #4 from FintanH/fintan/update-readme-no-sig",
+            "Remove src/Folder.svelte (#3)",
+            "Updated README with radicle-surf link",
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn first_parent() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let mainline = summaries(
+        repo.history(Branch::local(refname!("master")))?
+            .first_parent()?,
+    );
+
+    assert_eq!(mainline.len(), 14);
+    assert!(!mainline
+        .iter()
+        .any(|s| s == "Updated README with radicle-surf link"));
+
+    Ok(())
+}
+
+#[test]
+fn reverse_topological() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let commits = summaries(
+        repo.history(Branch::local(refname!("master")))?
+            .topological()?
+            .by_time()?
+            .reverse()?,
+    );
+
+    assert_eq!(commits.len(), 15);
+    assert_eq!(commits.first().unwrap(), "Initial commit FTW!");
+    assert_eq!(
+        commits.last().unwrap(),
+        "Add files with special characters in their filenames (#5)"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn pagination() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let master = Branch::local(refname!("master"));
+    let all = summaries(repo.history(&master)?.topological()?);
+
+    let mut paged = Vec::new();
+    let mut page = repo.history(&master)?.page(4)?;
+    loop {
+        paged.extend(page.commits.into_iter().map(|c| c.summary));
+        let Some(cursor) = page.next else { break };
+        // Cursors are passed around as strings.
+        let cursor = cursor.to_string().parse::<Cursor>()?;
+        page = repo.history(&master)?.resume(&cursor)?.page(4)?;
+    }
+
+    assert_eq!(paged, all);
+
+    Ok(())
+}
+
+/// Pages through `history` with pages of `size` commits, checking that
+/// only the last page is short or lacks a cursor.
+///
+/// The summaries are sorted, since the order of commits with no ancestry
+/// between them depends on where the walk resumed.
+fn paginate<'a>(
+    history: impl Fn() -> Result<radicle_surf::History<'a>, Error>,
+    size: usize,
+) -> Result<Vec<String>, Error> {
+    let mut paged = Vec::new();
+    let mut page = history()?.page(size)?;
+    loop {
+        assert!(!page.commits.is_empty(), "empty page of size {size}");
+        paged.extend(page.commits.into_iter().map(|c| c.summary));
+        let Some(cursor) = page.next else { break };
+        page = history()?.resume(&cursor)?.page(size)?;
+    }
+    paged.sort();
+    Ok(paged)
+}
+
+#[test]
+fn pagination_exact_pages() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let master = Branch::local(refname!("master"));
+    let mut all = summaries(repo.history(&master)?.topological()?);
+    all.sort();
+
+    // Every size that divides the history ends on a full page, which must
+    // not lead to an empty one.
+    for size in 1..=all.len() + 1 {
+        assert_eq!(paginate(|| repo.history(&master), size)?, all);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn pagination_range() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let master = Branch::local(refname!("master"));
+    let from = Oid::from_str("1e0206da8571ca71c51c91154e2fee376e09b4e7")?;
+    let mut all = summaries(repo.history_range(from, &master)?.topological()?);
+    assert_eq!(all.len(), 9);
+    all.sort();
+
+    // The excluded parents at the boundary of the range are never walked,
+    // yet the last page has no cursor.
+    for size in 1..=all.len() + 1 {
+        assert_eq!(paginate(|| repo.history_range(from, &master), size)?, all);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn pagination_symmetric() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let dev = Branch::local(refname!("dev"));
+    let master = Branch::local(refname!("master"));
+    let mut all = summaries(repo.history_symmetric(&dev, &master)?.topological()?);
+    assert!(all.len() > 2);
+
+    let mut paged = Vec::new();
+    let mut page = repo.history_symmetric(&dev, &master)?.page(2)?;
+    loop {
+        paged.extend(page.commits.into_iter().map(|c| c.summary));
+        let Some(cursor) = page.next else { break };
+        page = repo
+            .history_symmetric(&dev, &master)?
+            .resume(&cursor)?
+            .page(2)?;
+    }
+
+    // The order of commits with no ancestry between them depends on where
+    // the walk resumed, but each commit is produced exactly once.
+    paged.sort();
+    all.sort();
+    assert_eq!(paged, all);
+
+    Ok(())
+}
+
+#[test]
+fn pagination_errors() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let mut history = repo.history(Branch::local(refname!("master")))?.reverse()?;
+    assert!(matches!(history.page(4), Err(Error::Cursor(_))));
+
+    let mut history = repo.history(Branch::local(refname!("master")))?;
+    history.next();
+    assert!(matches!(history.page(4), Err(Error::Cursor(_))));
+
+    let mut history = repo.history(Branch::local(refname!("master")))?;
+    assert!(matches!(history.page(0), Err(Error::Cursor(_))));
+
+    assert!("not-a-cursor".parse::<Cursor>().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn count_commits() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let master = Branch::local(refname!("master"));
+    let dev = Branch::local(refname!("dev"));
+
+    assert_eq!(repo.count_commits(&master, &dev)?, 1);
+    assert_eq!(repo.count_commits(&dev, &master)?, 8);
+
+    Ok(())
+}
+
+#[test]
+fn follow_renames() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let follow = repo
+        .history(Branch::local(refname!("master")))?
+        .follow(&"src/memory.rs");
+    let commits = follow
+        .map(|found| found.map(|(commit, path)| (commit.summary, path)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    assert_eq!(
+        commits,
+        vec![
+            (
+                "Move examples to \"src\"".to_string(),
+                PathBuf::from("src/memory.rs")
+            ),
+            (
+                "Add some source code example files".to_string(),
+                PathBuf::from("examples/memory.rs")
+            ),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn follow_path() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let mut follow = repo
+        .history(Branch::local(refname!("master")))?
+        .follow(&"src/memory.rs");
+    assert_eq!(follow.path(), Path::new("src/memory.rs"));
+
+    // The path is the one of the last produced commit, even though that
+    // commit renamed the file.
+    let (_, path) = follow.next().unwrap()?;
+    assert_eq!(path, Path::new("src/memory.rs"));
+    assert_eq!(follow.path(), Path::new("src/memory.rs"));
+
+    let (_, path) = follow.next().unwrap()?;
+    assert_eq!(path, Path::new("examples/memory.rs"));
+    assert_eq!(follow.path(), Path::new("examples/memory.rs"));
+
+    assert!(follow.next().is_none());
+    assert_eq!(follow.path(), Path::new("examples/memory.rs"));
+
+    // The path stays the same when the history ends with a rename.
+    let tmp = tmp_repo();
+    let old = commit(&tmp, &[("old.txt", "text\n")]);
+    let new = commit_on(&tmp, &[("new.txt", "text\n")], &[old]);
+    let repo = Repository::open(tmp.path())?;
+    let mut follow = repo.history_range(old, new)?.follow(&"new.txt");
+    let (commit, path) = follow.next().unwrap()?;
+    assert_eq!((commit.id, path), (new, PathBuf::from("new.txt")));
+    assert!(follow.next().is_none());
+    assert_eq!(follow.path(), Path::new("new.txt"));
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use radicle_git_ext::ref_format::refname;
+use radicle_surf::{Branch, Error, Language, Repository};
+
+use super::{
+    fixture::{commit, tmp_repo},
+    GIT_PLATINUM,
+};
+
+fn names(languages: &[Language]) -> Vec<(&str, usize, usize)> {
+    languages
+        .iter()
+        .map(|l| (l.name.as_str(), l.bytes, l.files))
+        .collect()
+}
+
+#[test]
+fn language_stats() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let stats = repo.language_stats(Branch::local(refname!("dev")))?;
+    assert_eq!(
+        names(&stats.languages),
+        vec![
+            ("Haskell", 10035, 1),
+            ("Rust", 6253, 1),
+            ("Svelte", 2523, 1)
+        ]
+    );
+    assert_eq!(stats.bytes, 10035 + 6253 + 2523);
+    let percent = stats.languages.iter().map(|l| l.percent).sum::<f32>();
+    assert!((percent - 100.0).abs() < 0.01);
+    Ok(())
+}
+
+#[test]
+fn language_stats_attributes() -> Result<(), Error> {
+    let tmp = tmp_repo();
+    let oid = commit(
+        &tmp,
+        &[
+            (
+                ".gitattributes",
+                "*.inc linguist-language=C\ngen/** linguist-generated\n*.md linguist-detectable\n\
+                 *.[&&~~] linguist-language=C\n[unclosed linguist-generated\n[z-a] linguist-vendored\n",
+            ),
+            ("README.md", "# Readme\n"),
+            ("Makefile", "all:\n"),
+            ("gen/parser.c", "int x;\n"),
+            ("script", "#!/usr/bin/env python3\nprint()\n"),
+            ("src/lib.inc", "int y;\n"),
+            ("src/lib.&", "int z;\n"),
+            ("src/vendor/dep.rs", "fn f() {}\n"),
+            ("src/.gitattributes", "*.inc -linguist-detectable\n"),
+            ("src/main.rs", "fn main() {}\n"),
+        ],
+    );
+
+    let repo = Repository::open(tmp.path())?;
+    let stats = repo.language_stats(oid)?;
+    assert_eq!(
+        names(&stats.languages),
+        vec![
+            ("Python", 31, 1),
+            ("Rust", 13, 1),
+            ("Markdown", 9, 1),
+            ("C", 7, 1),
+            ("Makefile", 5, 1),
+        ]
+    );
+    Ok(())
+}
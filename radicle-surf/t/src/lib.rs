@@ -7,30 +7,66 @@ mod file_system;
 #[cfg(test)]
 mod source;
 
+#[cfg(test)]
+mod archive;
+
+#[cfg(test)]
+mod blame;
+
 #[cfg(test)]
 mod branch;
 
 #[cfg(test)]
 mod code_browsing;
 
+#[cfg(test)]
+mod codeowners;
+
 #[cfg(test)]
 mod commit;
 
 #[cfg(test)]
 mod diff;
 
+#[cfg(test)]
+mod fixture;
+
+#[cfg(test)]
+mod history;
+
+#[cfg(test)]
+mod language;
+
 #[cfg(test)]
 mod last_commit;
 
+#[cfg(test)]
+mod mailmap;
+
+#[cfg(test)]
+mod merge;
+
+#[cfg(test)]
+mod metadata;
+
 #[cfg(test)]
 mod namespace;
 
+#[cfg(test)]
+mod note;
+
 #[cfg(test)]
 mod reference;
 
 #[cfg(test)]
 mod rev;
 
+#[cfg(test)]
+mod search;
+
+#[cfg(test)]
+mod signature;
+
 #[cfg(test)]
 mod submodule;
 
@@ -0,0 +1,144 @@
+use std::fs;
+
+use radicle_git_ext::ref_format::refname;
+use radicle_surf::{Branch, Error, Mailmap, Oid, Repository, Stats, Time};
+
+use super::{fixture::tmp_repo, GIT_PLATINUM};
+
+fn authors(contributors: &[radicle_surf::Contributor]) -> Vec<(&str, &str, usize)> {
+    contributors
+        .iter()
+        .map(|c| (c.name.as_str(), c.email.as_str(), c.commits))
+        .collect()
+}
+
+#[test]
+fn contributors() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let contributors = repo.contributors(Branch::local(refname!("master")))?;
+
+    assert_eq!(
+        authors(&contributors),
+        vec![
+            ("Rūdolfs Ošiņš", "rudolfs@osins.org", 11),
+            ("Alexander Simmerl", "a.simmerl@gmail.com", 2),
+            ("Fintan Halpenny", "fintan.halpenny@gmail.com", 1),
+            ("FintanH", "fintan.halpenny@gmail.com", 1),
+        ]
+    );
+    assert_eq!(contributors[1].first.seconds(), 1576170713);
+    assert_eq!(contributors[1].last.seconds(), 1584367899);
+
+    Ok(())
+}
+
+#[test]
+fn history_mailmap() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let mailmap = Mailmap::from_buffer("Fintan Halpenny <fintan.halpenny@gmail.com>\n")?;
+    let history = repo
+        .history(Branch::local(refname!("master")))?
+        .mailmap(mailmap)?
+        .by_author("fintan.halpenny@gmail.com");
+
+    let names = history
+        .map(|commit| commit.map(|commit| commit.author.name))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(names, vec!["Fintan Halpenny", "Fintan Halpenny"]);
+
+    let history = repo
+        .history(Branch::local(refname!("master")))?
+        .mailmap(Mailmap::from_buffer("")?)?
+        .no_mailmap()?
+        .by_author("fintan.halpenny@gmail.com");
+    let names = history
+        .map(|commit| commit.map(|commit| commit.author.name))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(names, vec!["FintanH", "Fintan Halpenny"]);
+
+    Ok(())
+}
+
+#[test]
+fn repository_mailmap() -> Result<(), Error> {
+    let tmp = tmp_repo();
+    let mut parent = None;
+    for (i, (name, email)) in [
+        ("Jane", "jane@old.example"),
+        ("Jane Doe", "jane@example.com"),
+        ("John", "john@example.com"),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let sig = git2::Signature::new(name, email, &git2::Time::new(i as i64 * 100, 0)).unwrap();
+        let content = format!("{}\n", "line\n".repeat(i));
+        let blob = tmp.blob(content.as_bytes()).unwrap();
+        let mut builder = tmp.treebuilder(None).unwrap();
+        builder.insert("file", blob, 0o100644).unwrap();
+        let tree = tmp.find_tree(builder.write().unwrap()).unwrap();
+        let parents = parent
+            .iter()
+            .map(|oid| tmp.find_commit(*oid).unwrap())
+            .collect::<Vec<_>>();
+        let parents = parents.iter().collect::<Vec<_>>();
+        parent = Some(
+            tmp.commit(Some("HEAD"), &sig, &sig, "commit", &tree, &parents)
+                .unwrap(),
+        );
+    }
+    fs::write(
+        tmp.workdir().unwrap().join(".mailmap"),
+        "Jane Doe <jane@example.com> <jane@old.example>\n",
+    )
+    .unwrap();
+
+    let repo = Repository::open(tmp.path())?;
+    let head = Oid::from(parent.unwrap());
+    let contributors = repo.contributors(head)?;
+    assert_eq!(
+        authors(&contributors),
+        vec![
+            ("Jane Doe", "jane@example.com", 2),
+            ("John", "john@example.com", 1),
+        ]
+    );
+    assert_eq!(contributors[0].first, Time::new(0, 0));
+    assert_eq!(contributors[0].last, Time::new(100, 0));
+
+    assert_eq!(
+        repo.stats_from(&head)?,
+        Stats {
+            commits: 3,
+            branches: 1,
+            contributors: 2,
+        }
+    );
+
+    // A `History` only resolves the mailmap when it is set.
+    let emails = |history: radicle_surf::History| {
+        history
+            .map(|commit| commit.map(|commit| commit.author.email))
+            .collect::<Result<Vec<_>, _>>()
+    };
+    assert_eq!(
+        emails(repo.history(head)?)?,
+        vec!["john@example.com", "jane@example.com", "jane@old.example"]
+    );
+    assert_eq!(
+        emails(repo.history(head)?.mailmap(repo.mailmap()?)?)?,
+        vec!["john@example.com", "jane@example.com", "jane@example.com"]
+    );
+
+    let blame = repo.blame(head, &"file")?;
+    let emails = blame
+        .hunks()
+        .map(|hunk| hunk.commit.author.email.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        emails,
+        vec!["jane@example.com", "john@example.com", "jane@example.com"]
+    );
+
+    Ok(())
+}
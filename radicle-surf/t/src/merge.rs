@@ -0,0 +1,191 @@
+use std::{path::Path, str::FromStr};
+
+use radicle_git_ext::Oid;
+use radicle_surf::{
+    diff::{
+        combined::{CombinedContent, CombinedHunk, Origin},
+        DiffContent,
+    },
+    Error, Repository,
+};
+
+use super::{
+    fixture::{commit, commit_on, tmp_repo},
+    GIT_PLATINUM,
+};
+
+#[test]
+fn merge_base() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let base = repo.merge_base(
+        Oid::from_str("a57846bbc8ced6587bf8329fc4bce970eb7b757e").unwrap(),
+        Oid::from_str("80bacafba303bf0cdf6142921f430ff265f25095").unwrap(),
+    )?;
+    assert_eq!(
+        base,
+        Some(Oid::from_str("d6880352fc7fda8f521ae9b7357668b17bb5bad5").unwrap())
+    );
+    Ok(())
+}
+
+#[test]
+fn merge_preview() -> Result<(), Error> {
+    let tmp = tmp_repo();
+    let base = commit(&tmp, &[("a.txt", "1\n2\n3\n"), ("gone.txt", "x\n")]);
+    let ours = commit_on(
+        &tmp,
+        &[("a.txt", "1\ntwo\n3\n"), ("gone.txt", "x\n")],
+        &[base],
+    );
+    let theirs = commit_on(
+        &tmp,
+        &[("a.txt", "1\nzwei\n3\n"), ("b.txt", "new\n")],
+        &[base],
+    );
+    let unrelated = commit(&tmp, &[("c.txt", "c\n")]);
+    let repo = Repository::open(tmp.path())?;
+
+    assert_eq!(repo.merge_base(ours, theirs)?, Some(base));
+    assert_eq!(repo.merge_base(ours, unrelated)?, None);
+    assert!(repo.diff_merge_base(ours, unrelated).is_err());
+
+    // The changes on their side only.
+    let diff = repo.diff_merge_base(ours, theirs)?;
+    assert_eq!(
+        diff.files().map(|f| f.path()).collect::<Vec<_>>(),
+        vec![
+            Path::new("a.txt"),
+            Path::new("b.txt"),
+            Path::new("gone.txt")
+        ]
+    );
+
+    let preview = repo.merge_preview(ours, theirs)?;
+    assert!(!preview.is_clean());
+    assert_eq!(
+        preview.conflicts().collect::<Vec<_>>(),
+        vec![Path::new("a.txt")]
+    );
+    let diff = preview.diff();
+    assert_eq!(diff.added().next().unwrap().path, Path::new("b.txt"));
+    assert_eq!(diff.deleted().next().unwrap().path, Path::new("gone.txt"));
+    let conflict = diff.modified().next().unwrap();
+    assert_eq!(conflict.path, Path::new("a.txt"));
+    let DiffContent::Plain { hunks, .. } = &conflict.diff else {
+        panic!("expected a plain diff");
+    };
+    let added = hunks
+        .iter()
+        .flat_map(|h| h.lines.iter())
+        .filter_map(|l| match l {
+            radicle_surf::diff::Modification::Addition(a) => {
+                Some(a.line.from_utf8_lossy().into_owned())
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        added,
+        vec!["<<<<<<< ours\n", "=======\n", "zwei\n", ">>>>>>> theirs\n"]
+    );
+
+    let clean = repo.merge_preview(base, theirs)?;
+    assert!(clean.is_clean());
+    assert_eq!(clean.diff().files().count(), 3);
+
+    Ok(())
+}
+
+#[test]
+fn diff_commit_parent() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let merge = Oid::from_str("223aaf87d6ea62eef0014857640fd7c8dd0f80b5").unwrap();
+    let first = Oid::from_str("a57846bbc8ced6587bf8329fc4bce970eb7b757e").unwrap();
+    let second = Oid::from_str("80bacafba303bf0cdf6142921f430ff265f25095").unwrap();
+
+    assert_eq!(repo.diff_commit_parent(merge, 0)?, repo.diff_commit(merge)?);
+    assert_eq!(
+        repo.diff_commit_parent(merge, 1)?,
+        repo.diff(second, merge)?
+    );
+    assert_eq!(repo.diff(first, merge)?, repo.diff_commit(merge)?);
+    assert!(repo.diff_commit_parent(merge, 2).is_err());
+
+    // The merge was clean, so nothing differs from both parents.
+    assert_eq!(repo.diff_combined(merge)?.files().count(), 0);
+
+    Ok(())
+}
+
+#[test]
+fn diff_combined() -> Result<(), Error> {
+    let tmp = tmp_repo();
+    let lines = |changes: &[(usize, &str)]| {
+        let mut lines = (1..=12).map(|n| n.to_string()).collect::<Vec<_>>();
+        for (n, line) in changes {
+            lines[n - 1] = line.to_string();
+        }
+        lines.join("\n") + "\n"
+    };
+    let base = commit(&tmp, &[("a.txt", &lines(&[])), ("b.txt", "x\n")]);
+    let ours = commit_on(
+        &tmp,
+        &[
+            ("a.txt", &lines(&[(2, "two"), (10, "ten")])),
+            ("b.txt", "z\n"),
+        ],
+        &[base],
+    );
+    let theirs = commit_on(
+        &tmp,
+        &[
+            ("a.txt", &lines(&[(2, "zwei"), (12, "TWELVE")])),
+            ("b.txt", "y\n"),
+        ],
+        &[base],
+    );
+    let merge = commit_on(
+        &tmp,
+        &[
+            ("a.txt", &lines(&[(2, "deux"), (10, "ten"), (12, "TWELVE")])),
+            ("b.txt", "y\n"),
+        ],
+        &[ours, theirs],
+    );
+    let repo = Repository::open(tmp.path())?;
+
+    // `b.txt` is taken as is from their side.
+    let diff = repo.diff_combined(merge)?;
+    let files = diff.into_files();
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].path, Path::new("a.txt"));
+
+    let CombinedContent::Plain { hunks } = &files[0].diff else {
+        panic!("expected a plain diff");
+    };
+    let render = |hunk: &CombinedHunk| {
+        let mut s = hunk.header.from_utf8_lossy().into_owned();
+        for line in &hunk.lines {
+            for origin in &line.origins {
+                s.push(match origin {
+                    Origin::Context => ' ',
+                    Origin::Addition => '+',
+                    Origin::Deletion => '-',
+                });
+            }
+            s.push_str(&line.line.from_utf8_lossy());
+        }
+        s
+    };
+    assert_eq!(
+        hunks.iter().map(render).collect::<Vec<_>>(),
+        vec![
+            "@@@ -1,5 -1,5 +1,5 @@@\n  1\n- two\n -zwei\n++deux\n  3\n  4\n  5\n",
+            "@@@ -7,6 -7,6 +7,6 @@@\n  7\n  8\n  9\n -10\n +ten\n  11\n- 12\n+ TWELVE\n",
+        ]
+    );
+    assert_eq!(hunks[1].parents, vec![7..13, 7..13]);
+    assert_eq!(hunks[1].new, 7..13);
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use std::path::Path;
+
+use radicle_git_ext::ref_format::refname;
+use radicle_surf::{Branch, Error, Repository};
+
+use super::{
+    fixture::{commit, tmp_repo},
+    GIT_PLATINUM,
+};
+
+#[test]
+fn metadata_git_platinum() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let metadata = repo.metadata(Branch::local(refname!("master")), &"")?;
+    assert_eq!(
+        metadata.readme.map(|readme| readme.path),
+        Some("README.md".into())
+    );
+    assert!(metadata.licenses.is_empty());
+    assert_eq!(metadata.contributing, None);
+    assert_eq!(metadata.codeowners, None);
+
+    let metadata = repo.metadata(Branch::local(refname!("master")), &"src")?;
+    assert_eq!(metadata.readme, None);
+
+    Ok(())
+}
+
+#[test]
+fn metadata() -> Result<(), Error> {
+    let tmp = tmp_repo();
+    let oid = commit(
+        &tmp,
+        &[
+            ("README.txt", "text"),
+            ("readme.md", "lower case"),
+            ("README.md", "markdown"),
+            ("docs/README.md", "docs"),
+            ("LICENSE-MIT", include_str!("../../../LICENSE-MIT")),
+            ("LICENSE-APACHE", include_str!("../../../LICENSE-APACHE")),
+            (
+                "COPYING",
+                "                    GNU GENERAL PUBLIC LICENSE\n\
+                 \x20                      Version 3, 29 June 2007\n",
+            ),
+            ("LICENSE.md", "<!-- SPDX-License-Identifier: MPL-2.0 -->\n"),
+            ("license.rs", "fn main() {}\n"),
+            (".github/CODEOWNERS", "* @alice\n"),
+            (".github/CONTRIBUTING.md", "Contributions welcome\n"),
+            ("CODEOWNERS", "* @bob\n"),
+            ("src/README", "plain"),
+            ("src/Readme.rst", "rst"),
+            ("src/LICENSE.txt", "All rights reserved.\n"),
+            ("src/main.rs", "fn main() {}\n"),
+        ],
+    );
+    let repo = Repository::open(tmp.path())?;
+
+    let metadata = repo.metadata(oid, &"")?;
+    let readme = metadata.readme.unwrap();
+    assert_eq!(readme.path, Path::new("README.md"));
+    assert_eq!(repo.blob_ref(readme.id)?.as_ref(), b"markdown");
+    assert_eq!(
+        metadata
+            .licenses
+            .iter()
+            .map(|license| (license.path.to_str().unwrap(), license.spdx.as_deref()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("COPYING", Some("GPL-3.0")),
+            ("LICENSE-APACHE", Some("Apache-2.0")),
+            ("LICENSE-MIT", Some("MIT")),
+            ("LICENSE.md", Some("MPL-2.0")),
+        ]
+    );
+    assert_eq!(
+        metadata.contributing.map(|file| file.path),
+        Some(".github/CONTRIBUTING.md".into())
+    );
+    assert_eq!(
+        metadata.codeowners.map(|file| file.path),
+        Some(".github/CODEOWNERS".into())
+    );
+
+    let metadata = repo.metadata(oid, &"src")?;
+    assert_eq!(
+        metadata.readme.map(|file| file.path),
+        Some("src/Readme.rst".into())
+    );
+    assert_eq!(
+        metadata
+            .licenses
+            .iter()
+            .map(|license| (license.path.to_str().unwrap(), license.spdx.as_deref()))
+            .collect::<Vec<_>>(),
+        vec![("src/LICENSE.txt", None)]
+    );
+    assert_eq!(metadata.contributing, None);
+    assert_eq!(
+        metadata.codeowners.map(|file| file.path),
+        Some(".github/CODEOWNERS".into())
+    );
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use radicle_git_ext::ref_format::{qualified, refname, refspec::pattern};
+use radicle_surf::{Commit, Error, Glob, Note, Oid, Repository};
+
+use super::fixture::tmp_repo;
+
+fn commit(repo: &git2::Repository, message: &str) -> Oid {
+    let tree = repo
+        .find_tree(repo.treebuilder(None).unwrap().write().unwrap())
+        .unwrap();
+    let sig = git2::Signature::now("Test", "test@test.com").unwrap();
+    let parents = repo
+        .head()
+        .ok()
+        .and_then(|head| head.peel_to_commit().ok())
+        .into_iter()
+        .collect::<Vec<_>>();
+    repo.commit(
+        Some("HEAD"),
+        &sig,
+        &sig,
+        message,
+        &tree,
+        &parents.iter().collect::<Vec<_>>(),
+    )
+    .unwrap()
+    .into()
+}
+
+fn note(repo: &git2::Repository, refname: &str, oid: Oid, message: &str) {
+    let sig = git2::Signature::now("Reviewer", "reviewer@test.com").unwrap();
+    repo.note(&sig, &sig, Some(refname), oid.into(), message, false)
+        .unwrap();
+}
+
+#[test]
+fn notes() -> Result<(), Error> {
+    let tmp = tmp_repo();
+    let first = commit(&tmp, "first");
+    let second = commit(&tmp, "second");
+    note(&tmp, "refs/notes/commits", first, "Tested-by: CI\n");
+    note(&tmp, "refs/notes/review/alice", first, "LGTM\n");
+    note(&tmp, "refs/notes/review/bob", second, "Needs work\n");
+
+    let repo = Repository::open(tmp.path())?;
+    let refs = repo
+        .note_refs(&Glob::all_notes())?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        refs,
+        vec![
+            qualified!("refs/notes/commits"),
+            qualified!("refs/notes/review/alice"),
+            qualified!("refs/notes/review/bob"),
+        ]
+    );
+    let refs = repo
+        .note_refs(&Glob::notes(pattern!("review/*")))?
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        refs,
+        vec![
+            qualified!("refs/notes/review/alice"),
+            qualified!("refs/notes/review/bob"),
+        ]
+    );
+
+    let note = repo
+        .note(first, &qualified!("refs/notes/commits"))?
+        .unwrap();
+    assert_eq!(note.target, first);
+    assert_eq!(note.name, refname!("commits"));
+    assert_eq!(note.refname(), qualified!("refs/notes/commits"));
+    assert_eq!(note.message, "Tested-by: CI\n");
+    assert_eq!(note.author.name, "Reviewer");
+    assert_eq!(repo.note(second, &qualified!("refs/notes/commits"))?, None);
+    assert!(matches!(
+        repo.note(first, &qualified!("refs/heads/master")),
+        Err(Error::Note(_))
+    ));
+
+    let messages = |notes: Vec<Note>| {
+        notes
+            .into_iter()
+            .map(|note| (note.name.to_string(), note.message))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        messages(repo.notes(first, &Glob::all_notes())?),
+        vec![
+            ("commits".to_owned(), "Tested-by: CI\n".to_owned()),
+            ("review/alice".to_owned(), "LGTM\n".to_owned()),
+        ]
+    );
+    assert_eq!(
+        messages(repo.notes(
+            second,
+            &Glob::notes(pattern!("commits")).insert(pattern!("review/*"))
+        )?),
+        vec![("review/bob".to_owned(), "Needs work\n".to_owned())]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn commit_notes_serialization() -> Result<(), Error> {
+    let tmp = tmp_repo();
+    let oid = commit(&tmp, "commit");
+    note(&tmp, "refs/notes/review", oid, "LGTM\n");
+
+    let repo = Repository::open(tmp.path())?;
+    let mut commit: Commit = repo.commit(oid)?;
+    let json = serde_json::to_value(&commit).unwrap();
+    assert!(json.get("notes").is_none());
+
+    commit.notes = repo.notes(oid, &Glob::all_notes())?;
+    let json = serde_json::to_value(&commit).unwrap();
+    let notes = json["notes"].as_array().unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0]["name"], "review");
+    assert_eq!(notes[0]["target"], oid.to_string());
+    assert_eq!(notes[0]["message"], "LGTM\n");
+    assert_eq!(serde_json::from_value::<Commit>(json).unwrap(), commit);
+
+    Ok(())
+}
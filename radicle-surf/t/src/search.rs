@@ -0,0 +1,124 @@
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use radicle_git_ext::ref_format::refname;
+use radicle_surf::{
+    search::{Match, SearchOptions},
+    Branch, Error, Oid, Repository,
+};
+
+use super::{fixture::tmp_repo, GIT_PLATINUM};
+
+#[test]
+fn search_paths() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let master = Branch::local(refname!("master"));
+    let paths = |query| -> Result<Vec<PathBuf>, Error> {
+        Ok(repo
+            .search_paths(&master, query)?
+            .into_iter()
+            .map(|m| m.path)
+            .collect())
+    };
+
+    let matches = repo.search_paths(&master, "mem")?;
+    assert_eq!(matches[0].path, Path::new("src/memory.rs"));
+    assert_eq!(matches[0].indices, vec![4, 5, 6]);
+
+    // Matches at the start of path components win.
+    assert_eq!(
+        paths("tg")?,
+        vec![
+            PathBuf::from("text/garden.txt"),
+            PathBuf::from("this/is/a/really/deeply/nested/directory/tree/.gitkeep"),
+        ]
+    );
+    assert_eq!(paths("REAd")?, paths("read")?);
+    assert!(paths("zzz")?.is_empty());
+
+    let options = SearchOptions::default().glob("text/*").limit(2);
+    let matches = repo.search_paths_with(&master, "txt", &options)?;
+    assert_eq!(
+        matches.into_iter().map(|m| m.path).collect::<Vec<_>>(),
+        vec![
+            PathBuf::from("text/emoji.txt"),
+            PathBuf::from("text/arrows.txt"),
+        ]
+    );
+
+    let dir = repo.directory(&master, &"text")?;
+    let matches = dir.fuzzy_find("gdn", &repo)?;
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].path, Path::new("garden.txt"));
+    Ok(())
+}
+
+#[test]
+fn grep() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let master = Branch::local(refname!("master"));
+
+    let options = SearchOptions::default().glob("src/*.rs").context_lines(1);
+    let matches = repo.grep_with(&master, "fn ", &options)?;
+    assert_eq!(
+        matches.iter().map(|m| m.line_no).collect::<Vec<_>>(),
+        vec![32, 63, 77, 96, 135, 145, 149, 153, 157, 163]
+    );
+    assert_eq!(
+        matches[0],
+        Match {
+            path: PathBuf::from("src/memory.rs"),
+            line_no: 32,
+            line: "    pub fn new() -> Self {".to_string(),
+            ranges: vec![Range { start: 8, end: 11 }],
+            before: vec!["impl MemoryClient {".to_string()],
+            after: vec!["        let genesis_config = GenesisConfig {".to_string()],
+        }
+    );
+
+    let options = SearchOptions::default().regex(true).ignore_case(true);
+    let matches = repo.grep_with(&master, "^IMPL", &options)?;
+    assert_eq!(
+        matches.iter().map(|m| m.line_no).collect::<Vec<_>>(),
+        vec![31, 95]
+    );
+    assert!(repo.grep(&master, "^impl")?.is_empty());
+    assert!(matches!(
+        repo.grep_with(&master, "(", &options),
+        Err(Error::Regex(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn grep_binary() -> Result<(), Error> {
+    let tmp = tmp_repo();
+    let mut tb = tmp.treebuilder(None).unwrap();
+    for (path, content) in [("bin", &b"needle\0\n"[..]), ("text", b"a\nneedle\n")] {
+        let blob = tmp.blob(content).unwrap();
+        tb.insert(path, blob, git2::FileMode::Blob.into()).unwrap();
+    }
+    let tree = tmp.find_tree(tb.write().unwrap()).unwrap();
+    let sig = git2::Signature::now("Test", "test@test.com").unwrap();
+    let oid = Oid::from(tmp.commit(None, &sig, &sig, "commit", &tree, &[]).unwrap());
+
+    let repo = Repository::open(tmp.path())?;
+    let paths = |options: &SearchOptions| -> Result<Vec<PathBuf>, Error> {
+        Ok(repo
+            .grep_with(oid, "needle", options)?
+            .into_iter()
+            .map(|m| m.path)
+            .collect())
+    };
+    assert_eq!(
+        paths(&SearchOptions::default())?,
+        vec![PathBuf::from("text")]
+    );
+    assert_eq!(
+        paths(&SearchOptions::default().binary(true))?,
+        vec![PathBuf::from("bin"), PathBuf::from("text")]
+    );
+    Ok(())
+}
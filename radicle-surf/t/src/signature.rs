@@ -0,0 +1,443 @@
+use std::str::FromStr;
+
+use radicle_surf::{
+    signature::{error, AllowedSigners, Keyring, Kind, Status, Verification, Verifier},
+    Error, Oid, Repository,
+};
+
+use super::{fixture::tmp_repo, GIT_PLATINUM};
+
+const SSH_SIGNATURE: &str = "\
+-----BEGIN SSH SIGNATURE-----
+U1NIU0lHAAAAAQAAADMAAAALc3NoLWVkMjU1MTkAAAAg9OJVm5Unhd0YkDELNy18I8DxEK
+5g9FqvKM8oe/0Plz0AAAADZ2l0AAAAAAAAAAZzaGE1MTIAAABTAAAAC3NzaC1lZDI1NTE5
+AAAAQJvkIFn/VJmhWLns9JlCcYBT2VlCuPpZSPsmzdKAyKdKKKC6irponFIiYFGaNnV56g
+9KmkQYd3t1HXUcJJU/+Aw=
+-----END SSH SIGNATURE-----";
+
+const SSH_KEY: &str = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIPTiVZuVJ4XdGJAxCzctfCPA8RCuYPRaryjPKHv9D5c9 alice@example.com";
+
+const SSH_OTHER_KEY: &str =
+    "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIMBc0BGKb8R8n+1JhsZorNdB26AU/rm+8YCfko0oolpI";
+
+/// Signed by the Ed25519 signing subkey of Bob.
+const BOB_SIGNATURE: &str = "\
+-----BEGIN PGP SIGNATURE-----
+
+iIYEABYIAC4WIQTAoJexOh8R96dt6iOxbOb48ijGuQUCatLceRAcYm9iQGV4YW1w
+bGUuY29tAAoJELFs5vjyKMa5v9oBANTMtsgz1UCVcEeR58u3b5q/9m+xF6IIk+tn
+ONPFvH9zAQC1PNiYVOYZZKBMNUduE+/ZUxZL8dQRa94xlZnj5Jk1DQ==
+=QrM1
+-----END PGP SIGNATURE-----";
+
+const BOB_KEY: &str = "\
+-----BEGIN PGP PUBLIC KEY BLOCK-----
+
+mDMEatLceBYJKwYBBAHaRw8BAQdAmh+w/XZsbAzFfKBD6uDKvxVtfd9tKb5Qt515
+m+G7pj+0FUJvYiA8Ym9iQGV4YW1wbGUuY29tPoiQBBMWCAA4FiEEwaLZPJ62NmwG
+EET7yuuURUlzuGAFAmrS3HgCGwEFCwkIBwIGFQoJCAsCBBYCAwECHgECF4AACgkQ
+yuuURUlzuGAKqgEAoKsWS+jcwEVnZ1sIQqgfe/MLpVOBYQvHYQ7mz+ukEiwBAOij
+Ywj/91NK9P4eWDbLz7JlV8FvgiEMP9JreW1S4LgMuDMEatLceBYJKwYBBAHaRw8B
+AQdAVGAZJA3ctkqsMPOq/AZFI5I7s/YKm+CvThcuqw6sHTCI7wQYFggAIBYhBMGi
+2TyetjZsBhBE+8rrlEVJc7hgBQJq0tx4AhsCAIEJEMrrlEVJc7hgdiAEGRYIAB0W
+IQTAoJexOh8R96dt6iOxbOb48ijGuQUCatLceAAKCRCxbOb48ijGuRABAQDr8sSd
+59SY7WpsI5qUy56o/SM4Uk4sIAchBopaQq75wwD9HBvB2R6JgCSMawEfVDKApoNm
+oNuxyT7/zl0NJKsvcgC6OwD/fXNJuCSW4X10lncpo7fgzGiTe15ENpP9BYmmhtHA
+gQgBAPNoogUG8Td3qHzEs21EF4odd6qfjv1CY0cKqzboC/IA
+=ap8B
+-----END PGP PUBLIC KEY BLOCK-----";
+
+/// Signed by the RSA key of Carol.
+const CAROL_SIGNATURE: &str = "\
+-----BEGIN PGP SIGNATURE-----
+
+iQFGBAABCgAwFiEEFAj+g/SV/Z5FnfleLQT3BcE7/jIFAmrS3HkSHGNhcm9sQGV4
+YW1wbGUuY29tAAoJEC0E9wXBO/4yqmoH/j+9cuBwpMUEaIlZHZW7ihk/G1wbsu7j
+Nqyt1tTdwTRSUcl7s1nrs4rAFM4TldxiHfqGPGvRWioS9o0JwSVigOKxsdNGkhWq
+QdN83mogUb6OmeKoEj60CIhPSB+aY0HQlTb0dRdmjGpkDWQb3XalSxiIeidMn75P
+MTtRWaLE2z+LIUpVgO3XXSgKWUUJ6GuGgQ/+8MKMN8qM73xQUQzhVpDiR2aASM1E
+YRybnfIc5xRbenR4qYv7QBiizWLwPMuA4rtkcuWjCv/3aD6StXrg3vfWck4ct9uY
+QoZC45X6joyDv/FSGN/u6HhhuCL5pktaQoXTpFrZ05MBzkmsUqJ2Cus=
+=trji
+-----END PGP SIGNATURE-----";
+
+const CAROL_KEY: &str = "\
+-----BEGIN PGP PUBLIC KEY BLOCK-----
+
+mQENBGrS3HgBCAChah4HN1mXcpbmIeF3IHDZ/39NJ1tInU76lI+9ZbZD+1eCcfcm
+tyxdoJR9wrk9PQ1f+vyNK9sG0/zBVtNAEzvDuXAo75nkCXenLAA7HkwF9/dMStR9
+ZWqASkwKL/nDePPNqtfMuINcQ7Z73831HvW9bj6YlxddMKQa39no972NHesfIE5o
+Hgh3INNB5UP05A2AakvlkWSOKn2SLg6aTmxK7BwylQyXISnnlZjlISHl31iuE32R
+AEP3nvUY1YiJ8U2acxrr38LQHnI3wvVQYJw2F9trAjFjijsMlnhGjb/wNEh1q47Q
+E09zqOBRb1Nbui0OYR5/62ZQ+LkDBB+Bs3CtABEBAAG0GUNhcm9sIDxjYXJvbEBl
+eGFtcGxlLmNvbT6JAU4EEwEKADgWIQQUCP6D9JX9nkWd+V4tBPcFwTv+MgUCatLc
+eAIbAwULCQgHAgYVCgkICwIEFgIDAQIeAQIXgAAKCRAtBPcFwTv+MgSNB/wLmOrg
+0dScpOWhTZqjevY1hzq+WpE25PXk8TVeLphhbqPnt5afbMCWSAG9J9MUuw7N89tF
+Wzt42guVaNZNUpN1NNCTyBW0H/YqBKsi8e0MIV8lcaVvzyNmUQoOrTKrThDjMmpo
+gTDa6a4eKK8zy2Y888UsYEJwFydDz23HfYvGncIa2OT38sqiT8t6O4k23N3/odEw
+31XaRpsA3GqVBPidnp9SzZu8AX+2JgRqAclGWQDW/MhXKnVdtPGaBcaow7cTm9Gg
+kFBA0G74f4GEFXZNEplqU4pr3PrsxVg1uKGJ3FCSBX4pzMaCzq8HkpsZYk+VgK3N
+qtPOSeGrf5q2qJ6/
+=Jr6w
+-----END PGP PUBLIC KEY BLOCK-----";
+
+/// Signed by the Ed25519 signing subkey of Mallory.
+const MALLORY_SIGNATURE: &str = "\
+-----BEGIN PGP SIGNATURE-----
+
+iIoEABYIADIWIQTQwrlmSmk+b/lRZY4PgUxMX1rTFQUCZUS80BQcbWFsbG9yeUBl
+eGFtcGxlLmNvbQAKCRAPgUxMX1rTFY0BAQDi2vcOnFCitKXUHzVS77RaJvt/GPgt
+NwrFbA3Mw40dKAD+MKbA9yvVaVzhDtZRDo4BrEgDFwiicngovVJIrxYrVA4=
+=foI3
+-----END PGP SIGNATURE-----";
+
+/// The key of Mallory, with the user id of Bob added without a
+/// certification.
+const MALLORY_KEY: &str = "\
+-----BEGIN PGP PUBLIC KEY BLOCK-----
+
+mDMEZUSuwBYJKwYBBAHaRw8BAQdA+iwcZ/kj+rdefuzNnMGgbgH/rb6WiI1GCpIR
+9CEsbLe0FUJvYiA8Ym9iQGV4YW1wbGUuY29tPrQdTWFsbG9yeSA8bWFsbG9yeUBl
+eGFtcGxlLmNvbT6IkAQTFggAOBYhBC6+d4l7RjM6+Gs5kAUgVUc3/t1gBQJlRK7A
+AhsBBQsJCAcCBhUKCQgLAgQWAgMBAh4BAheAAAoJEAUgVUc3/t1g5REA/2fk44ZG
+jjELOpHHyeT2ZPo8XUdCM12gzpQiCebiDfceAP9wR3NVO9va0VMMmhK2hr2PGLmF
+PBrfNV7khMxDQWQ6DrgzBGVErsAWCSsGAQQB2kcPAQEHQHNbphSNDcnwWvFFPqd4
+A/IDiqM2fgBCOoIcwY4L6R3IiO8EGBYIACAWIQQuvneJe0YzOvhrOZAFIFVHN/7d
+YAUCZUSuwAIbAgCBCRAFIFVHN/7dYHYgBBkWCAAdFiEE0MK5ZkppPm/5UWWOD4FM
+TF9a0xUFAmVErsAACgkQD4FMTF9a0xWJowEAkuOPLs7VSC4H4ijCNOkqIpcHF6Qo
+MdrcZTiEq/WLwvABAJWjE0FwSwC1nrPSbKwZrCMjQ6mHeEASfAMcPM+ZmScJCfQB
+ANIf19RI37D9CBspx9XVSZsNt1rxdxAWdIrTDOiOgyy9APwJlMT7jk+0RRCyQkdb
+tpXbSrC6pCKaCG8zNIztPr60CA==
+=2Wh0
+-----END PGP PUBLIC KEY BLOCK-----";
+
+/// The key of Bob, with the signing subkey of Mallory appended along with
+/// its binding signature made by Mallory.
+const BOB_FORGED_KEY: &str = "\
+-----BEGIN PGP PUBLIC KEY BLOCK-----
+
+mDMEatLceBYJKwYBBAHaRw8BAQdAmh+w/XZsbAzFfKBD6uDKvxVtfd9tKb5Qt515
+m+G7pj+0FUJvYiA8Ym9iQGV4YW1wbGUuY29tPoiQBBMWCAA4FiEEwaLZPJ62NmwG
+EET7yuuURUlzuGAFAmrS3HgCGwEFCwkIBwIGFQoJCAsCBBYCAwECHgECF4AACgkQ
+yuuURUlzuGAKqgEAoKsWS+jcwEVnZ1sIQqgfe/MLpVOBYQvHYQ7mz+ukEiwBAOij
+Ywj/91NK9P4eWDbLz7JlV8FvgiEMP9JreW1S4LgMuDMEatLceBYJKwYBBAHaRw8B
+AQdAVGAZJA3ctkqsMPOq/AZFI5I7s/YKm+CvThcuqw6sHTCI7wQYFggAIBYhBMGi
+2TyetjZsBhBE+8rrlEVJc7hgBQJq0tx4AhsCAIEJEMrrlEVJc7hgdiAEGRYIAB0W
+IQTAoJexOh8R96dt6iOxbOb48ijGuQUCatLceAAKCRCxbOb48ijGuRABAQDr8sSd
+59SY7WpsI5qUy56o/SM4Uk4sIAchBopaQq75wwD9HBvB2R6JgCSMawEfVDKApoNm
+oNuxyT7/zl0NJKsvcgC6OwD/fXNJuCSW4X10lncpo7fgzGiTe15ENpP9BYmmhtHA
+gQgBAPNoogUG8Td3qHzEs21EF4odd6qfjv1CY0cKqzboC/IAuDMEZUSuwBYJKwYB
+BAHaRw8BAQdAc1umFI0NyfBa8UU+p3gD8gOKozZ+AEI6ghzBjgvpHciI7wQYFggA
+IBYhBC6+d4l7RjM6+Gs5kAUgVUc3/t1gBQJlRK7AAhsCAIEJEAUgVUc3/t1gdiAE
+GRYIAB0WIQTQwrlmSmk+b/lRZY4PgUxMX1rTFQUCZUSuwAAKCRAPgUxMX1rTFYmj
+AQCS448uztVILgfiKMI06SoilwcXpCgx2txlOISr9YvC8AEAlaMTQXBLALWes9Js
+rBmsIyNDqYd4QBJ8Axw8z5mZJwkJ9AEA0h/X1EjfsP0IGynH1dVJmw23WvF3EBZ0
+itMM6I6DLL0A/AmUxPuOT7RFELJCR1u2ldtKsLqkIpoIbzM0jO0+vrQI
+=PLA/
+-----END PGP PUBLIC KEY BLOCK-----";
+
+/// Signed by Dave, before the key was revoked.
+const DAVE_SIGNATURE: &str = "\
+-----BEGIN PGP SIGNATURE-----
+
+iIcEABYIAC8WIQSYbWbGzjslkehydr4WSkjuv3jkJAUCZUS80BEcZGF2ZUBleGFt
+cGxlLmNvbQAKCRAWSkjuv3jkJGgHAQCTfnfxfGpEXjDNfdoxKlVCt+jIYMp/QBiS
+KOckk/jyPgEA4bYlEd4Qe6wNkJRW4p+dAXHrMg9QpTAV983IOoMITwE=
+=S7RT
+-----END PGP SIGNATURE-----";
+
+/// The revoked key of Dave.
+const DAVE_KEY: &str = "\
+-----BEGIN PGP PUBLIC KEY BLOCK-----
+
+mDMEZUSuwBYJKwYBBAHaRw8BAQdAicxDuPJW4JNQ9vuAgHFZXBwfUBk6PRufsWWR
+0tt3kxGIeAQgFggAIBYhBJhtZsbOOyWR6HJ2vhZKSO6/eOQkBQJlRK7AAh0AAAoJ
+EBZKSO6/eOQkKggA/j6CnPxcbqlYZzvQMxJUf401zWDacS2g5Yg+5a5dJUoOAP9s
+RnYPWO4GDKPJJqXpONNWuDar0df6vkRWYd7DNO8UBbQXRGF2ZSA8ZGF2ZUBleGFt
+cGxlLmNvbT6IkAQTFggAOBYhBJhtZsbOOyWR6HJ2vhZKSO6/eOQkBQJlRK7AAhsD
+BQsJCAcCBhUKCQgLAgQWAgMBAh4BAheAAAoJEBZKSO6/eOQk7dgA/iOZ+ngr5Ere
++PI4nkYrhgJzfDJShLDxGlXUsJHBAUKDAP4zeOysvW4BsnrJ5bvJyEGB8LZB4S2T
+fNOj658WA3/GBw==
+=FABM
+-----END PGP PUBLIC KEY BLOCK-----";
+
+/// Signed by Erin, before the key expired.
+const ERIN_SIGNATURE: &str = "\
+-----BEGIN PGP SIGNATURE-----
+
+iIcEABYIAC8WIQT5bgfuWLpFDztV0yY3mU2NHNyiegUCZUS80BEcZXJpbkBleGFt
+cGxlLmNvbQAKCRA3mU2NHNyietU2AP9y8V5V6y1fXdMddCabGQJdgOMELshP0U4K
+ZfpVk33laQD/cPBMnljTQyHCddIRbh3m7IsgLrT7evd7Ze7HtwGhTgg=
+=ASsl
+-----END PGP SIGNATURE-----";
+
+/// Signed by Erin, after the key expired.
+const ERIN_EXPIRED_SIGNATURE: &str = "\
+-----BEGIN PGP SIGNATURE-----
+
+iIcEABYIAC8WIQT5bgfuWLpFDztV0yY3mU2NHNyiegUCZUijQBEcZXJpbkBleGFt
+cGxlLmNvbQAKCRA3mU2NHNyieuA+AQCF3T/v8LXrr1NA/GcM/nPwEjobGopDAbhi
+Ae2HDARh7gEAtvovdReoMXBRLGP5fzbjwR9T+PVzpvIwnoibIhyyVwA=
+=2aGO
+-----END PGP SIGNATURE-----";
+
+/// Signed by Erin, expiring a day after it was made.
+const ERIN_EXPIRING_SIGNATURE: &str = "\
+-----BEGIN PGP SIGNATURE-----
+
+iI0EABYIADUWIQT5bgfuWLpFDztV0yY3mU2NHNyiegUCZUS80AWDAAFRgBEcZXJp
+bkBleGFtcGxlLmNvbQAKCRA3mU2NHNyieu3LAP9le7r3Ldyb8kgxNSHCjHvdcfHP
+rdiojrACvfsu8r8tYQEAptIPUL5Ol9U1Yolt0DeW7JfaHbLGYNON01peTtBgNAc=
+=lPjg
+-----END PGP SIGNATURE-----";
+
+/// Signed by Erin, with a critical notation that is not understood.
+const ERIN_CRITICAL_SIGNATURE: &str = "\
+-----BEGIN PGP SIGNATURE-----
+
+iKYEABYIAE4WIQT5bgfuWLpFDztV0yY3mU2NHNyiegUCZUS80B6UgAAAAAASAANy
+ZXZpZXdAZXhhbXBsZS5jb215ZXMRHGVyaW5AZXhhbXBsZS5jb20ACgkQN5lNjRzc
+onq3hQD+Na+l3zxstfzxV1VHDklXfD3aIYHwM0f0ZqjHiYre2+gA/0Pt+ddybAt+
+6QPoumKSyiFJxIlm8dyy2KL/qxHJBnIM
+=Ejvq
+-----END PGP SIGNATURE-----";
+
+/// The key of Erin, expiring two days after its creation.
+const ERIN_KEY: &str = "\
+-----BEGIN PGP PUBLIC KEY BLOCK-----
+
+mDMEZUSuwBYJKwYBBAHaRw8BAQdA+j6R9bdT57/qspIf06tmr96VVHrvrYgAG97U
+MGCK++u0F0VyaW4gPGVyaW5AZXhhbXBsZS5jb20+iJYEExYIAD4CGwMFCwkIBwIG
+FQoJCAsCBBYCAwECHgECF4AWIQT5bgfuWLpFDztV0yY3mU2NHNyiegUCZUYAQAUJ
+AAKjAAAKCRA3mU2NHNyieoydAP4lKTe3ngVWsP1rbp582tTbXO80YX0nLIoXgHEz
+fcC2VAD+O7Lqi/euWMfKtg1N4VhRdeRjrJe9tUniWb/uuAkTSQQ=
+=ma26
+-----END PGP PUBLIC KEY BLOCK-----";
+
+/// Writes a commit of the empty tree with `message` and `signature`. The
+/// signatures above are made for the message `Signed commit`.
+fn signed_commit(repo: &git2::Repository, message: &str, signature: &str) -> Oid {
+    let tree = repo.treebuilder(None).unwrap().write().unwrap();
+    let buffer = format!(
+        "tree {tree}\n\
+         author Alice <alice@example.com> 1700000000 +0000\n\
+         committer Alice <alice@example.com> 1700000000 +0000\n\
+         \n\
+         {message}\n"
+    );
+    repo.commit_signed(&buffer, signature, None).unwrap().into()
+}
+
+fn verify(signature: &str, message: &str, verifier: &Verifier) -> Result<Verification, Error> {
+    let tmp = tmp_repo();
+    let oid = signed_commit(&tmp, message, signature);
+    let repo = Repository::open(tmp.path())?;
+    Ok(repo.verify_commit(oid, verifier)?.unwrap())
+}
+
+fn allowed_signers(signers: &str) -> Verifier {
+    Verifier::default().allowed_signers(AllowedSigners::from_str(signers).unwrap())
+}
+
+#[test]
+fn verify_ssh() -> Result<(), Error> {
+    let signers = format!("# Signers\n\nalice@example.com,alice@work.example {SSH_KEY}\n");
+    let verification = verify(SSH_SIGNATURE, "Signed commit", &allowed_signers(&signers))?;
+    assert_eq!(verification.kind, Kind::Ssh);
+    assert_eq!(verification.status, Status::Good);
+    assert_eq!(
+        verification.signer.as_deref(),
+        Some("alice@example.com,alice@work.example")
+    );
+    assert!(verification
+        .fingerprint
+        .is_some_and(|f| f.starts_with("SHA256:")));
+
+    let verification = verify(SSH_SIGNATURE, "Tampered commit", &allowed_signers(&signers))?;
+    assert_eq!(verification.status, Status::Bad);
+    assert_eq!(verification.signer, None);
+
+    let other = format!("bob@example.com {SSH_OTHER_KEY}");
+    let verification = verify(SSH_SIGNATURE, "Signed commit", &allowed_signers(&other))?;
+    assert_eq!(verification.status, Status::UnknownKey);
+    assert!(verification.fingerprint.is_some());
+
+    Ok(())
+}
+
+#[test]
+fn verify_ssh_options() -> Result<(), Error> {
+    // The commit is made at 2023-11-14T22:13:20Z.
+    for (options, status) in [
+        (r#"namespaces="file,git""#, Status::Good),
+        (r#"namespaces="file""#, Status::UnknownKey),
+        ("valid-after=20231114,valid-before=20231115", Status::Good),
+        ("valid-after=20231114221321Z", Status::UnknownKey),
+        ("valid-before=202311142213", Status::UnknownKey),
+        ("cert-authority", Status::UnknownKey),
+    ] {
+        let signers = format!("alice@example.com {options} {SSH_KEY}");
+        let verification = verify(SSH_SIGNATURE, "Signed commit", &allowed_signers(&signers))?;
+        assert_eq!(verification.status, status, "{options}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn allowed_signers_errors() {
+    assert_eq!(
+        AllowedSigners::from_str("\nalice@example.com").unwrap_err(),
+        error::AllowedSigners::Invalid {
+            line: 2,
+            reason: "missing key"
+        }
+    );
+    assert!(matches!(
+        AllowedSigners::from_str("alice@example.com ssh-ed25519 AAAA"),
+        Err(error::AllowedSigners::Key { line: 1, .. })
+    ));
+    assert_eq!(
+        AllowedSigners::from_str(&format!("alice@example.com no-touch {SSH_KEY}")).unwrap_err(),
+        error::AllowedSigners::Invalid {
+            line: 1,
+            reason: "unsupported option"
+        }
+    );
+}
+
+#[test]
+fn verify_pgp() -> Result<(), Error> {
+    let keyring = Keyring::from_bytes(format!("{BOB_KEY}\n{CAROL_KEY}\n").as_bytes())?;
+    assert_eq!(keyring.len(), 2);
+    let verifier = Verifier::default().keyring(keyring);
+
+    let verification = verify(BOB_SIGNATURE, "Signed commit", &verifier)?;
+    assert_eq!(
+        verification,
+        Verification {
+            kind: Kind::Pgp,
+            status: Status::Good,
+            signer: Some("Bob <bob@example.com>".to_owned()),
+            fingerprint: Some("C0A097B13A1F11F7A76DEA23B16CE6F8F228C6B9".to_owned()),
+        }
+    );
+
+    let verification = verify(CAROL_SIGNATURE, "Signed commit", &verifier)?;
+    assert_eq!(verification.status, Status::Good);
+    assert_eq!(
+        verification.signer.as_deref(),
+        Some("Carol <carol@example.com>")
+    );
+    assert_eq!(
+        verification.fingerprint.as_deref(),
+        Some("1408FE83F495FD9E459DF95E2D04F705C13BFE32")
+    );
+
+    for signature in [BOB_SIGNATURE, CAROL_SIGNATURE] {
+        let verification = verify(signature, "Tampered commit", &verifier)?;
+        assert_eq!(verification.status, Status::Bad);
+    }
+
+    let verifier = Verifier::default().keyring(Keyring::from_bytes(CAROL_KEY.as_bytes())?);
+    let verification = verify(BOB_SIGNATURE, "Signed commit", &verifier)?;
+    assert_eq!(verification.status, Status::UnknownKey);
+    assert_eq!(verification.signer, None);
+
+    Ok(())
+}
+
+#[test]
+fn keyring_errors() {
+    let corrupted = CAROL_KEY.replace("mQENBGrS", "mQENBGrT");
+    assert_eq!(
+        Keyring::from_bytes(corrupted.as_bytes()).unwrap_err(),
+        error::Keyring::Armor("checksum mismatch")
+    );
+    assert_eq!(
+        Keyring::from_bytes(b"-----BEGIN PGP PUBLIC KEY BLOCK-----\n").unwrap_err(),
+        error::Keyring::Armor("missing end line")
+    );
+    assert_eq!(
+        Keyring::from_bytes(&[0x99, 0x01]).unwrap_err(),
+        error::Keyring::Malformed
+    );
+}
+
+#[test]
+fn keyring_bindings() -> Result<(), Error> {
+    let verifier = Verifier::default().keyring(Keyring::from_bytes(BOB_FORGED_KEY.as_bytes())?);
+    let verification = verify(MALLORY_SIGNATURE, "Signed commit", &verifier)?;
+    assert_eq!(verification.status, Status::UnknownKey);
+    assert_eq!(verification.signer, None);
+    let verification = verify(BOB_SIGNATURE, "Signed commit", &verifier)?;
+    assert_eq!(verification.status, Status::Good);
+
+    let verifier = Verifier::default().keyring(Keyring::from_bytes(MALLORY_KEY.as_bytes())?);
+    let verification = verify(MALLORY_SIGNATURE, "Signed commit", &verifier)?;
+    assert_eq!(verification.status, Status::Good);
+    assert_eq!(
+        verification.signer.as_deref(),
+        Some("Mallory <mallory@example.com>")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn keyring_revocation_and_expiry() -> Result<(), Error> {
+    let keyring = Keyring::from_bytes(format!("{DAVE_KEY}\n{ERIN_KEY}\n").as_bytes())?;
+    assert_eq!(keyring.len(), 2);
+    let verifier = Verifier::default().keyring(keyring);
+
+    let verification = verify(DAVE_SIGNATURE, "Signed commit", &verifier)?;
+    assert_eq!(verification.status, Status::RevokedKey);
+    assert_eq!(
+        verification.signer.as_deref(),
+        Some("Dave <dave@example.com>")
+    );
+
+    let verification = verify(ERIN_SIGNATURE, "Signed commit", &verifier)?;
+    assert_eq!(verification.status, Status::Good);
+    assert_eq!(
+        verification.signer.as_deref(),
+        Some("Erin <erin@example.com>")
+    );
+
+    let verification = verify(ERIN_EXPIRED_SIGNATURE, "Signed commit", &verifier)?;
+    assert_eq!(verification.status, Status::ExpiredKey);
+    assert_eq!(
+        verification.signer.as_deref(),
+        Some("Erin <erin@example.com>")
+    );
+
+    let verification = verify(ERIN_EXPIRING_SIGNATURE, "Signed commit", &verifier)?;
+    assert_eq!(verification.status, Status::ExpiredSignature);
+
+    let verification = verify(ERIN_CRITICAL_SIGNATURE, "Signed commit", &verifier)?;
+    assert_eq!(verification.status, Status::Bad);
+
+    Ok(())
+}
+
+#[test]
+fn verify_commit() -> Result<(), Error> {
+    let repo = Repository::open(GIT_PLATINUM)?;
+    let verifier = Verifier::default();
+
+    let verification = repo.verify_commit("e24124b7538658220b5aaf3b6ef53758f0a106dc", &verifier)?;
+    assert_eq!(
+        verification,
+        Some(Verification {
+            kind: Kind::Pgp,
+            status: Status::UnknownKey,
+            signer: None,
+            fingerprint: Some("8A67BFE8C098AFC0204A6D3268322CBBBE0D02BA".to_owned()),
+        })
+    );
+
+    let verification = repo.verify_commit("80bacafba303bf0cdf6142921f430ff265f25095", &verifier)?;
+    assert_eq!(verification, None);
+
+    Ok(())
+}